@@ -1,10 +1,11 @@
 use anyhow::{Context, Result};
 use clap::Parser;
-use lazycompass_storage::{ConfigPaths, load_config};
+use lazycompass_storage::{ConfigPaths, load_config_with_profile, resolve_profile_name};
 
 use crate::cli::{Cli, Commands};
 use crate::commands::{
-    run_agg, run_config, run_indexes, run_init, run_insert, run_query, run_update, run_upgrade,
+    run_agg, run_config, run_doctor, run_history, run_indexes, run_init, run_insert, run_lint,
+    run_query, run_saved, run_update, run_upgrade,
 };
 use crate::logging::{apply_cli_overrides, init_logging};
 
@@ -20,30 +21,43 @@ enum AppAction {
         dangerously_enable_write: bool,
         allow_pipeline_writes: bool,
         allow_insecure: bool,
+        profile: Option<String>,
     },
     Query {
         args: crate::cli::QueryArgs,
         dangerously_enable_write: bool,
         allow_pipeline_writes: bool,
         allow_insecure: bool,
+        profile: Option<String>,
     },
     Agg {
         args: crate::cli::AggArgs,
         dangerously_enable_write: bool,
         allow_pipeline_writes: bool,
         allow_insecure: bool,
+        profile: Option<String>,
     },
     Insert {
         args: crate::cli::InsertArgs,
         dangerously_enable_write: bool,
         allow_pipeline_writes: bool,
         allow_insecure: bool,
+        profile: Option<String>,
     },
     Update {
         args: crate::cli::UpdateArgs,
         dangerously_enable_write: bool,
         allow_pipeline_writes: bool,
         allow_insecure: bool,
+        profile: Option<String>,
+    },
+    Saved(crate::cli::SavedArgs),
+    History(crate::cli::HistoryArgs),
+    Lint(crate::cli::LintArgs),
+    Doctor {
+        args: crate::cli::DoctorArgs,
+        allow_insecure: bool,
+        profile: Option<String>,
     },
     Config(crate::cli::ConfigArgs),
     Upgrade(crate::cli::UpgradeArgs),
@@ -51,10 +65,12 @@ enum AppAction {
         dangerously_enable_write: bool,
         allow_pipeline_writes: bool,
         allow_insecure: bool,
+        profile: Option<String>,
     },
 }
 
 fn dispatch(cli: Cli) -> AppAction {
+    let profile = resolve_profile_name(cli.profile);
     match cli.command {
         Some(Commands::Init(args)) => AppAction::Init(args),
         Some(Commands::Indexes(args)) => AppAction::Indexes {
@@ -62,30 +78,43 @@ fn dispatch(cli: Cli) -> AppAction {
             dangerously_enable_write: cli.dangerously_enable_write,
             allow_pipeline_writes: cli.allow_pipeline_writes,
             allow_insecure: cli.allow_insecure,
+            profile,
         },
         Some(Commands::Query(args)) => AppAction::Query {
             args,
             dangerously_enable_write: cli.dangerously_enable_write,
             allow_pipeline_writes: cli.allow_pipeline_writes,
             allow_insecure: cli.allow_insecure,
+            profile,
         },
         Some(Commands::Agg(args)) => AppAction::Agg {
             args,
             dangerously_enable_write: cli.dangerously_enable_write,
             allow_pipeline_writes: cli.allow_pipeline_writes,
             allow_insecure: cli.allow_insecure,
+            profile,
         },
         Some(Commands::Insert(args)) => AppAction::Insert {
             args,
             dangerously_enable_write: cli.dangerously_enable_write,
             allow_pipeline_writes: cli.allow_pipeline_writes,
             allow_insecure: cli.allow_insecure,
+            profile,
         },
         Some(Commands::Update(args)) => AppAction::Update {
             args,
             dangerously_enable_write: cli.dangerously_enable_write,
             allow_pipeline_writes: cli.allow_pipeline_writes,
             allow_insecure: cli.allow_insecure,
+            profile,
+        },
+        Some(Commands::Saved(args)) => AppAction::Saved(args),
+        Some(Commands::History(args)) => AppAction::History(args),
+        Some(Commands::Lint(args)) => AppAction::Lint(args),
+        Some(Commands::Doctor(args)) => AppAction::Doctor {
+            args,
+            allow_insecure: cli.allow_insecure,
+            profile,
         },
         Some(Commands::Config(args)) => AppAction::Config(args),
         Some(Commands::Upgrade(args)) => AppAction::Upgrade(args),
@@ -93,6 +122,7 @@ fn dispatch(cli: Cli) -> AppAction {
             dangerously_enable_write: cli.dangerously_enable_write,
             allow_pipeline_writes: cli.allow_pipeline_writes,
             allow_insecure: cli.allow_insecure,
+            profile,
         },
     }
 }
@@ -107,56 +137,82 @@ fn execute(action: AppAction) -> Result<()> {
             dangerously_enable_write,
             allow_pipeline_writes,
             allow_insecure,
+            profile,
         } => run_indexes(
             args,
             dangerously_enable_write,
             allow_pipeline_writes,
             allow_insecure,
+            profile.as_deref(),
         )?,
         AppAction::Query {
             args,
             dangerously_enable_write,
             allow_pipeline_writes,
             allow_insecure,
+            profile,
         } => run_query(
             args,
             dangerously_enable_write,
             allow_pipeline_writes,
             allow_insecure,
+            profile.as_deref(),
         )?,
         AppAction::Agg {
             args,
             dangerously_enable_write,
             allow_pipeline_writes,
             allow_insecure,
+            profile,
         } => run_agg(
             args,
             dangerously_enable_write,
             allow_pipeline_writes,
             allow_insecure,
+            profile.as_deref(),
         )?,
         AppAction::Insert {
             args,
             dangerously_enable_write,
             allow_pipeline_writes,
             allow_insecure,
+            profile,
         } => run_insert(
             args,
             dangerously_enable_write,
             allow_pipeline_writes,
             allow_insecure,
+            profile.as_deref(),
         )?,
         AppAction::Update {
             args,
             dangerously_enable_write,
             allow_pipeline_writes,
             allow_insecure,
+            profile,
         } => run_update(
             args,
             dangerously_enable_write,
             allow_pipeline_writes,
             allow_insecure,
+            profile.as_deref(),
         )?,
+        AppAction::Saved(args) => {
+            run_saved(args)?;
+        }
+        AppAction::History(args) => {
+            run_history(args)?;
+        }
+        AppAction::Lint(args) => {
+            run_lint(args)?;
+        }
+        AppAction::Doctor {
+            args,
+            allow_insecure,
+            profile,
+        } => {
+            run_doctor(args, allow_insecure, profile.as_deref())?;
+        }
         AppAction::Config(args) => {
             run_config(args)?;
         }
@@ -167,14 +223,20 @@ fn execute(action: AppAction) -> Result<()> {
             dangerously_enable_write,
             allow_pipeline_writes,
             allow_insecure,
+            profile,
         } => {
             let cwd = std::env::current_dir().context("unable to resolve current directory")?;
             let paths = ConfigPaths::resolve_from(&cwd)?;
-            let mut config = load_config(&paths)?;
+            let mut config = load_config_with_profile(&paths, profile.as_deref())?;
             apply_cli_overrides(&mut config, allow_insecure);
             init_logging(&paths, &config)?;
             tracing::info!(component = "tui", command = "tui", "lazycompass started");
-            lazycompass_tui::run(config, dangerously_enable_write, allow_pipeline_writes)?;
+            lazycompass_tui::run(
+                config,
+                dangerously_enable_write,
+                allow_pipeline_writes,
+                allow_insecure,
+            )?;
         }
     }
 
@@ -198,10 +260,48 @@ mod tests {
                 dangerously_enable_write: true,
                 allow_pipeline_writes: false,
                 allow_insecure: false,
+                profile: None,
             }
         ));
     }
 
+    #[test]
+    fn dispatch_passes_profile_flag_to_commands() {
+        let cli = Cli::parse_from([
+            "lazycompass",
+            "query",
+            "--collection",
+            "users",
+            "--profile",
+            "staging",
+        ]);
+        let action = dispatch(cli);
+        assert!(matches!(
+            action,
+            AppAction::Query { profile: Some(ref profile), .. } if profile == "staging"
+        ));
+    }
+
+    #[test]
+    fn dispatch_routes_doctor_with_global_flags() {
+        let cli = Cli::parse_from([
+            "lazycompass",
+            "--allow-insecure",
+            "doctor",
+            "--connection",
+            "local",
+        ]);
+        let action = dispatch(cli);
+        assert!(matches!(
+            action,
+            AppAction::Doctor {
+                ref args,
+                allow_insecure: true,
+                profile: None,
+            } if args.connection.as_deref() == Some("local") && !args.no_ping
+        ));
+    }
+
     #[test]
     fn dispatch_routes_insert_with_global_flags() {
         let cli = Cli::parse_from([
@@ -275,6 +375,83 @@ mod tests {
         assert!(matches!(action, AppAction::Upgrade(_)));
     }
 
+    #[test]
+    fn dispatch_routes_saved_subcommands() {
+        let cli = Cli::parse_from([
+            "lazycompass",
+            "saved",
+            "mv",
+            "query",
+            "recent_orders",
+            "app.orders.recent_orders",
+            "--force",
+        ]);
+        let action = dispatch(cli);
+        match action {
+            AppAction::Saved(crate::cli::SavedArgs {
+                command: crate::cli::SavedCommands::Mv(args),
+            }) => {
+                assert_eq!(args.kind, crate::cli::SavedKind::Query);
+                assert_eq!(args.to, "app.orders.recent_orders");
+                assert!(args.force);
+            }
+            _ => panic!("expected saved mv command"),
+        }
+    }
+
+    #[test]
+    fn dispatch_routes_history_command() {
+        let cli = Cli::parse_from(["lazycompass", "history", "--limit", "5", "--table"]);
+        let action = dispatch(cli);
+        match action {
+            AppAction::History(args) => {
+                assert_eq!(args.limit, 5);
+                assert!(args.table);
+                assert!(!args.clear);
+            }
+            _ => panic!("expected history command"),
+        }
+    }
+
+    #[test]
+    fn dispatch_routes_lint_command() {
+        let cli = Cli::parse_from(["lazycompass", "lint", "--strict"]);
+        let action = dispatch(cli);
+        match action {
+            AppAction::Lint(args) => assert!(args.strict),
+            _ => panic!("expected lint command"),
+        }
+    }
+
+    #[test]
+    fn cli_parser_accepts_saved_list_filters() {
+        let cli = Cli::parse_from([
+            "lazycompass",
+            "saved",
+            "list",
+            "--kind",
+            "agg",
+            "--scope",
+            "scoped",
+            "--tag",
+            "ops",
+            "--tag",
+            "daily",
+            "--table",
+        ]);
+
+        match cli.command {
+            Some(Commands::Saved(crate::cli::SavedArgs {
+                command: crate::cli::SavedCommands::List(args),
+            })) => {
+                assert_eq!(args.kind, Some(crate::cli::SavedKind::Agg));
+                assert_eq!(args.tags, vec!["ops".to_string(), "daily".to_string()]);
+                assert!(args.table);
+            }
+            _ => panic!("expected saved list command"),
+        }
+    }
+
     #[test]
     fn cli_parser_accepts_query_output_flag() {
         let cli = Cli::parse_from([
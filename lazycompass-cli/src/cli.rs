@@ -1,4 +1,4 @@
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -32,6 +32,13 @@ pub(crate) struct Cli {
         help = "Permit insecure Mongo connections for this run"
     )]
     pub(crate) allow_insecure: bool,
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help = "Layer a config profile over global and repo config (default: $LAZYCOMPASS_PROFILE)"
+    )]
+    pub(crate) profile: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -48,6 +55,14 @@ pub(crate) enum Commands {
     Insert(InsertArgs),
     #[command(about = "Replace one document by _id")]
     Update(UpdateArgs),
+    #[command(about = "Manage saved queries and aggregations")]
+    Saved(SavedArgs),
+    #[command(about = "List recent query and aggregation runs")]
+    History(HistoryArgs),
+    #[command(about = "Check saved specs and config for problems")]
+    Lint(LintArgs),
+    #[command(about = "Diagnose config, environment, and connectivity")]
+    Doctor(DoctorArgs),
     #[command(about = "Open or update LazyCompass config")]
     Config(ConfigArgs),
     #[command(about = "Upgrade LazyCompass from release assets or source")]
@@ -220,6 +235,146 @@ pub(crate) struct UpdateArgs {
     pub(crate) file: Option<String>,
 }
 
+#[derive(Args)]
+#[command(
+    about = "Manage saved queries and aggregations",
+    long_about = "List, show, create, edit, remove, rename, and copy saved specs under .lazycompass. IDs use <name> for shared specs or <db>.<collection>.<name> for scoped specs; renaming or copying to a different database or collection rewrites the scope."
+)]
+pub(crate) struct SavedArgs {
+    #[command(subcommand)]
+    pub(crate) command: SavedCommands,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum SavedKind {
+    Query,
+    Agg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum SavedScopeFilter {
+    Shared,
+    Scoped,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum SavedCommands {
+    #[command(about = "List saved queries and aggregations")]
+    List(SavedListArgs),
+    #[command(about = "Print a saved spec payload")]
+    Show(SavedTargetArgs),
+    #[command(
+        about = "Create a saved spec",
+        long_about = "Create a saved spec from --file or by editing a template in your editor. The payload accepts ObjectId(...) and ISODate(...) and is stored as Extended JSON."
+    )]
+    New(SavedNewArgs),
+    #[command(about = "Edit a saved spec in your editor")]
+    Edit(SavedTargetArgs),
+    #[command(about = "Remove a saved spec")]
+    Rm(SavedTargetArgs),
+    #[command(about = "Rename a saved spec, rewriting its scope from the new ID")]
+    Mv(SavedTransferArgs),
+    #[command(about = "Copy a saved spec to a new ID")]
+    Copy(SavedTransferArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct SavedListArgs {
+    #[arg(long, value_enum, help = "Only list queries or aggregations")]
+    pub(crate) kind: Option<SavedKind>,
+    #[arg(long, value_enum, help = "Only list shared or scoped specs")]
+    pub(crate) scope: Option<SavedScopeFilter>,
+    #[arg(long, help = "Only list specs scoped to this database")]
+    pub(crate) db: Option<String>,
+    #[arg(long, help = "Only list specs scoped to this collection")]
+    pub(crate) collection: Option<String>,
+    #[arg(
+        long = "tag",
+        help = "Only list specs with this tag; repeat to require several"
+    )]
+    pub(crate) tags: Vec<String>,
+    #[arg(long, help = "Render output as a table")]
+    pub(crate) table: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct SavedTargetArgs {
+    #[arg(value_enum, help = "Saved spec kind")]
+    pub(crate) kind: SavedKind,
+    #[arg(help = "Saved spec ID")]
+    pub(crate) id: String,
+}
+
+#[derive(Args)]
+pub(crate) struct SavedNewArgs {
+    #[arg(value_enum, help = "Saved spec kind")]
+    pub(crate) kind: SavedKind,
+    #[arg(help = "Saved spec ID: <name> or <db>.<collection>.<name>")]
+    pub(crate) id: String,
+    #[arg(long = "tag", help = "Tag to attach; repeat for several")]
+    pub(crate) tags: Vec<String>,
+    #[arg(long, help = "Path to a file containing the payload JSON")]
+    pub(crate) file: Option<String>,
+}
+
+#[derive(Args)]
+pub(crate) struct SavedTransferArgs {
+    #[arg(value_enum, help = "Saved spec kind")]
+    pub(crate) kind: SavedKind,
+    #[arg(help = "Existing saved spec ID")]
+    pub(crate) from: String,
+    #[arg(help = "New saved spec ID: <name> or <db>.<collection>.<name>")]
+    pub(crate) to: String,
+    #[arg(long, help = "Overwrite the destination if it already exists")]
+    pub(crate) force: bool,
+}
+
+#[derive(Args)]
+#[command(
+    about = "List recent query and aggregation runs",
+    long_about = "List recorded query and aggregation runs from the CLI and TUI, newest first. History is stored in the global config directory and can be tuned or disabled with [history] in config."
+)]
+pub(crate) struct HistoryArgs {
+    #[arg(long, default_value_t = 20, help = "Maximum number of entries to show")]
+    pub(crate) limit: usize,
+    #[arg(long, help = "Only show runs against this connection")]
+    pub(crate) connection: Option<String>,
+    #[arg(long, help = "Only show runs against this database")]
+    pub(crate) db: Option<String>,
+    #[arg(long, help = "Only show runs against this collection")]
+    pub(crate) collection: Option<String>,
+    #[arg(long, help = "Render history as a table")]
+    pub(crate) table: bool,
+    #[arg(
+        long,
+        conflicts_with_all = ["limit", "connection", "db", "collection", "table"],
+        help = "Delete all recorded history"
+    )]
+    pub(crate) clear: bool,
+}
+
+#[derive(Args)]
+#[command(
+    about = "Check saved specs and config for problems",
+    long_about = "Parse every saved query and aggregation, check scoped database and collection names, flag unknown or write aggregation stages, and validate config connections and env var references. Prints file:line diagnostics and exits non-zero when errors are found."
+)]
+pub(crate) struct LintArgs {
+    #[arg(long, help = "Fail on warnings as well as errors")]
+    pub(crate) strict: bool,
+}
+
+#[derive(Args)]
+#[command(
+    about = "Diagnose config, environment, and connectivity",
+    long_about = "Report resolved config paths, which config and .env files were found, config load and lint problems, symlink and permission issues, insecure connections, editor and clipboard availability, and ping each connection with round-trip latency, server version, and topology. Exits non-zero when errors are found."
+)]
+pub(crate) struct DoctorArgs {
+    #[arg(long, help = "Only ping this connection")]
+    pub(crate) connection: Option<String>,
+    #[arg(long, conflicts_with = "connection", help = "Skip connectivity checks")]
+    pub(crate) no_ping: bool,
+}
+
 #[derive(Args)]
 #[command(
     about = "Open or update LazyCompass config",
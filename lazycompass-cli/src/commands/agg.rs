@@ -1,9 +1,14 @@
 use anyhow::{Context, Result};
-use lazycompass_core::{AggregationRequest, AggregationTarget, OutputFormat, WriteGuard};
-use lazycompass_mongo::{AggregationSpec, MongoExecutor};
-use lazycompass_storage::{ConfigPaths, StorageSnapshot, load_storage};
+use lazycompass_core::{
+    AggregationRequest, AggregationTarget, HistoryEntry, HistoryPayload, OutputFormat, WriteGuard,
+};
+use lazycompass_mongo::{AggregationSpec, MongoExecutor, pipeline_write_stage};
+use lazycompass_storage::{ConfigPaths, StorageSnapshot, load_storage_with_profile};
+use std::time::Instant;
 
 use super::database::resolve_database_arg;
+use super::history::record_history;
+use super::protection::ensure_connection_write;
 use crate::cli::AggArgs;
 use crate::errors::report_warnings;
 use crate::logging::{apply_cli_overrides, init_logging};
@@ -14,10 +19,11 @@ pub(crate) fn run_agg(
     dangerously_enable_write: bool,
     allow_pipeline_writes: bool,
     allow_insecure: bool,
+    profile: Option<&str>,
 ) -> Result<()> {
     let cwd = std::env::current_dir().context("unable to resolve current directory")?;
     let paths = ConfigPaths::resolve_from(&cwd)?;
-    let storage = load_storage(&paths)?;
+    let storage = load_storage_with_profile(&paths, profile)?;
     let mut config = storage.config.clone();
     apply_cli_overrides(&mut config, allow_insecure);
     let write_guard = WriteGuard::new(dangerously_enable_write, allow_pipeline_writes);
@@ -46,8 +52,28 @@ pub(crate) fn run_agg(
         collection = spec.collection.as_str(),
         "executing aggregation"
     );
+    if let Some(stage) = pipeline_write_stage(&spec.pipeline)? {
+        write_guard.ensure_pipeline_allowed(stage)?;
+        ensure_connection_write(write_guard, connection, "aggregation pipeline write stages")?;
+    }
+    let saved_id = match &request.target {
+        AggregationTarget::Saved { id, .. } => Some(id.clone()),
+        AggregationTarget::Inline { .. } => None,
+    };
+    let history_entry = HistoryEntry::started(
+        Some(connection.name.clone()),
+        spec.database.clone(),
+        spec.collection.clone(),
+        saved_id,
+        HistoryPayload::Aggregation {
+            pipeline: spec.pipeline.clone(),
+        },
+    );
     let runtime = tokio::runtime::Runtime::new().context("unable to start async runtime")?;
-    let documents = runtime.block_on(executor.execute_aggregation(&config, write_guard, &spec))?;
+    let started = Instant::now();
+    let result = runtime.block_on(executor.execute_aggregation(&config, write_guard, &spec));
+    record_history(&paths, &config, history_entry, started, &result);
+    let documents = result?;
     print_documents(request.output, &documents, output_path.as_deref())
 }
 
@@ -158,7 +184,8 @@ fn resolve_aggregation_spec(
 #[cfg(test)]
 mod tests {
     use lazycompass_core::{
-        AggregationTarget, Config, ConnectionSpec, OutputFormat, SavedAggregation, SavedScope,
+        AggregationTarget, Config, ConnectionProtection, ConnectionSpec, OutputFormat,
+        SavedAggregation, SavedScope,
     };
     use lazycompass_storage::StorageSnapshot;
 
@@ -195,7 +222,10 @@ mod tests {
             connections: vec![ConnectionSpec {
                 name: name.to_string(),
                 uri: format!("mongodb://{name}:27017"),
+                uri_command: None,
                 default_database: default_database.map(ToString::to_string),
+                protection: ConnectionProtection::Open,
+                options: None,
             }],
             ..Config::default()
         }
@@ -293,6 +323,7 @@ mod tests {
                     collection: "scoped_collection".to_string(),
                 },
                 pipeline: r#"[{"$match":{"active":true}}]"#.to_string(),
+                tags: Vec::new(),
             }],
         );
 
@@ -318,6 +349,7 @@ mod tests {
                 id: "saved.shared".to_string(),
                 scope: SavedScope::Shared,
                 pipeline: r#"[{"$match":{"active":true}}]"#.to_string(),
+                tags: Vec::new(),
             }],
         );
 
@@ -365,6 +397,7 @@ mod tests {
                 id: "saved.shared".to_string(),
                 scope: SavedScope::Shared,
                 pipeline: r#"[{"$match":{"active":true}}]"#.to_string(),
+                tags: Vec::new(),
             }],
         );
 
@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use lazycompass_core::ConnectionSpec;
+use lazycompass_core::{ConnectionOptions, ConnectionProtection, ConnectionSpec};
 use lazycompass_storage::{
     ConfigPaths, append_connection_to_global_config, append_connection_to_repo_config,
     ensure_not_symlinked_file, ensure_secure_dir, write_secure_file,
@@ -149,10 +149,21 @@ fn collect_connection_via_editor() -> Result<ConnectionSpec> {
     let template = r#"# Edit the connection details below and save the file.
 # Lines starting with # are comments and will be ignored.
 # URI values can use env vars like ${MONGO_URI}.
+# Or replace uri with a secret command, run without a shell:
+# uri_command = ["pass", "show", "mongo/prod"]
 
 name = "my-connection"
 uri = "${MONGO_URI}"
 default_database = "mydb"
+# protection = "confirm"  # open (default), confirm, or read_only
+
+# Or remove uri and describe the connection with structured fields (keep this table last):
+# [options]
+# hosts = ["db1:27017", "db2:27017"]
+# username = "app"
+# password_env = "MONGO_PASSWORD"
+# tls = true
+# replica_set = "rs0"
     "#;
 
     let temp_path = create_secure_temp_file("connection", "toml", template)?;
@@ -169,7 +180,7 @@ default_database = "mydb"
             .join("\n");
 
         let connection: ConnectionSpec = toml::from_str(&toml_content).with_context(
-            || "invalid TOML in connection definition; expected fields: name, uri, default_database",
+            || "invalid TOML in connection definition; expected fields: name, uri, uri_command or options, default_database",
         )?;
 
         validate_connection(connection)
@@ -185,7 +196,7 @@ fn collect_connection_interactively<R: BufRead, W: Write>(
 ) -> Result<ConnectionSpec> {
     writeln!(
         output,
-        "Add a MongoDB connection. URI values can use env vars like ${{MONGO_URI}}."
+        "Add a MongoDB connection. URI values can use env vars like ${{MONGO_URI}}; type 'fields' to enter hosts, auth, and TLS separately."
     )
     .context("unable to write prompt")?;
 
@@ -196,6 +207,14 @@ fn collect_connection_interactively<R: BufRead, W: Write>(
         "Connection URI is required. Example: mongodb://localhost:27017 or ${MONGO_URI}.",
         "connection uri cannot be empty",
     )?;
+    let (uri, options) = if uri.eq_ignore_ascii_case("fields") {
+        (
+            String::new(),
+            Some(collect_connection_options(input, output)?),
+        )
+    } else {
+        (uri, None)
+    };
     let default_database = prompt_optional(
         input,
         output,
@@ -213,10 +232,112 @@ fn collect_connection_interactively<R: BufRead, W: Write>(
     validate_connection(ConnectionSpec {
         name,
         uri,
+        uri_command: None,
         default_database,
+        protection: ConnectionProtection::Open,
+        options,
+    })
+}
+
+fn collect_connection_options<R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+) -> Result<ConnectionOptions> {
+    let hosts = prompt_required(
+        input,
+        output,
+        "Hosts (comma separated)",
+        "At least one host is required. Example: db1:27017,db2:27017.",
+        "hosts cannot be empty",
+    )?;
+    let username = prompt_optional(input, output, "Username (optional)", "Leave blank to skip.")?;
+    let password_env = match username {
+        Some(_) => prompt_optional(
+            input,
+            output,
+            "Password env var (optional)",
+            "Leave blank to skip. Example: MONGO_PASSWORD.",
+        )?,
+        None => None,
+    };
+    let auth_source = prompt_optional(
+        input,
+        output,
+        "Auth source (optional)",
+        "Leave blank to skip.",
+    )?;
+    let auth_mechanism = prompt_optional(
+        input,
+        output,
+        "Auth mechanism (optional)",
+        "Leave blank for the server default. Example: SCRAM-SHA-256 or MONGODB-X509.",
+    )?;
+    let tls = prompt_flag(input, output, "Use TLS? (y/N)")?;
+    let (tls_ca_file, tls_cert_file) = if tls == Some(true) {
+        (
+            prompt_optional(
+                input,
+                output,
+                "TLS CA file (optional)",
+                "Leave blank to skip.",
+            )?,
+            prompt_optional(
+                input,
+                output,
+                "TLS client certificate file (optional)",
+                "Leave blank to skip.",
+            )?,
+        )
+    } else {
+        (None, None)
+    };
+    let replica_set = prompt_optional(
+        input,
+        output,
+        "Replica set (optional)",
+        "Leave blank to skip.",
+    )?;
+    let app_name = prompt_optional(input, output, "App name (optional)", "Leave blank to skip.")?;
+    let direct_connection = prompt_flag(input, output, "Direct connection? (y/N)")?;
+
+    Ok(ConnectionOptions {
+        hosts: hosts
+            .split(',')
+            .map(str::trim)
+            .filter(|host| !host.is_empty())
+            .map(ToString::to_string)
+            .collect(),
+        username,
+        password_env,
+        auth_source,
+        auth_mechanism,
+        tls,
+        tls_ca_file,
+        tls_cert_file,
+        replica_set,
+        app_name,
+        direct_connection,
+        password: None,
     })
 }
 
+/// Blank answers stay unset so the driver default applies.
+fn prompt_flag<R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    label: &str,
+) -> Result<Option<bool>> {
+    loop {
+        let value = prompt_line(input, output, label, "Leave blank to skip.")?;
+        match value.to_ascii_lowercase().as_str() {
+            "" => return Ok(None),
+            "y" | "yes" => return Ok(Some(true)),
+            "n" | "no" => return Ok(Some(false)),
+            _ => writeln!(output, "answer y or n").context("unable to write prompt")?,
+        }
+    }
+}
+
 fn prompt_required<R: BufRead, W: Write>(
     input: &mut R,
     output: &mut W,
@@ -274,8 +395,8 @@ fn validate_connection(connection: ConnectionSpec) -> Result<ConnectionSpec> {
     if connection.name.trim().is_empty() {
         anyhow::bail!("connection name cannot be empty");
     }
-    if connection.uri.trim().is_empty() {
-        anyhow::bail!("connection uri cannot be empty");
+    if let Some(problem) = connection.uri_source_problem() {
+        anyhow::bail!("connection has {problem}");
     }
     Ok(connection)
 }
@@ -391,6 +512,29 @@ mod tests {
         assert!(rendered.contains("connection name cannot be empty"));
     }
 
+    #[test]
+    fn collect_connection_interactively_supports_structured_fields() {
+        let input =
+            b"fields\ndb1:27017, db2:27017\napp\nAPP_PASSWORD\nadmin\n\nmaybe\ny\n/etc/ca.pem\n\nrs0\n\n\n\nprod\n";
+        let mut reader = &input[..];
+        let mut output = Vec::new();
+
+        let connection =
+            collect_connection_interactively(&mut reader, &mut output).expect("collect prompts");
+        let rendered = String::from_utf8(output).expect("utf8");
+
+        assert!(connection.uri.is_empty());
+        assert_eq!(connection.name, "prod");
+        let options = connection.options.expect("structured options");
+        assert_eq!(options.hosts, vec!["db1:27017", "db2:27017"]);
+        assert_eq!(options.password_env.as_deref(), Some("APP_PASSWORD"));
+        assert_eq!(options.tls, Some(true));
+        assert_eq!(options.tls_ca_file.as_deref(), Some("/etc/ca.pem"));
+        assert_eq!(options.replica_set.as_deref(), Some("rs0"));
+        assert_eq!(options.direct_connection, None);
+        assert!(rendered.contains("answer y or n"));
+    }
+
     #[test]
     fn config_add_connection_subcommand_accepts_editor_flag() {
         let parsed =
@@ -42,7 +42,7 @@ pub(crate) fn resolve_database_arg(
 
 #[cfg(test)]
 mod tests {
-    use lazycompass_core::{Config, ConnectionSpec};
+    use lazycompass_core::{Config, ConnectionProtection, ConnectionSpec};
 
     use super::resolve_database_arg;
 
@@ -57,7 +57,10 @@ mod tests {
         ConnectionSpec {
             name: name.to_string(),
             uri: format!("mongodb://{name}:27017"),
+            uri_command: None,
             default_database: default_database.map(ToString::to_string),
+            protection: ConnectionProtection::Open,
+            options: None,
         }
     }
 
@@ -0,0 +1,312 @@
+use anyhow::{Context, Result};
+use lazycompass_core::{Config, LintSeverity, connection_security_warnings};
+use lazycompass_mongo::MongoExecutor;
+use lazycompass_storage::{
+    ConfigPaths, dotenv_path_for_config, ensure_not_symlinked_file, lint_config,
+    load_config_with_profile, permission_warnings,
+};
+use lazycompass_tui::clipboard_methods;
+use std::env;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::cli::DoctorArgs;
+use crate::editor::{default_editor, parse_editor_command};
+use crate::logging::apply_cli_overrides;
+
+pub(crate) fn run_doctor(
+    args: DoctorArgs,
+    allow_insecure: bool,
+    profile: Option<&str>,
+) -> Result<()> {
+    let cwd = env::current_dir().context("unable to resolve current directory")?;
+    let paths = ConfigPaths::resolve_from(&cwd)?;
+    let stdout = io::stdout();
+    let mut report = Report::new(stdout.lock());
+
+    report.section("Paths")?;
+    report.info(&format!("global root: {}", paths.global_root.display()))?;
+    match &paths.repo_root {
+        Some(root) => report.info(&format!("repo root: {}", root.display()))?,
+        None => report.info("repo root: none (no .lazycompass directory found)")?,
+    }
+
+    let config_files: Vec<PathBuf> = std::iter::once(paths.global_config_path())
+        .chain(paths.repo_config_path())
+        .collect();
+    report.section("Config files")?;
+    for path in &config_files {
+        check_file(&mut report, path)?;
+    }
+    report.section("Env files")?;
+    for path in config_files
+        .iter()
+        .filter_map(|path| dotenv_path_for_config(path))
+    {
+        check_file(&mut report, &path)?;
+    }
+
+    report.section("Config")?;
+    let config = match load_config_with_profile(&paths, profile) {
+        Ok(mut config) => {
+            apply_cli_overrides(&mut config, allow_insecure);
+            let profile = config
+                .active_profile
+                .as_deref()
+                .map(|name| format!(" with profile '{name}'"))
+                .unwrap_or_default();
+            report.ok(&format!(
+                "loaded {} connection(s){profile}",
+                config.connections.len()
+            ))?;
+            Some(config)
+        }
+        Err(error) => {
+            report.error(&format!("{error:#}"))?;
+            None
+        }
+    };
+    for diagnostic in lint_config(&paths) {
+        let message = format!("{diagnostic}");
+        match diagnostic.severity {
+            LintSeverity::Error => report.error(&message)?,
+            LintSeverity::Warning => report.warn(&message)?,
+        }
+    }
+
+    report.section("Permissions")?;
+    let warnings = permission_warnings(&paths);
+    if warnings.is_empty() {
+        report.ok("config directories and files are private")?;
+    }
+    for warning in warnings {
+        report.warn(&warning)?;
+    }
+
+    if let Some(config) = &config {
+        report.section("Connection security")?;
+        let warnings = connection_security_warnings(config);
+        if warnings.is_empty() {
+            report.ok("no insecure connections")?;
+        }
+        for warning in warnings {
+            report.warn(&warning)?;
+        }
+    }
+
+    report.section("Editor")?;
+    check_editor(&mut report)?;
+
+    report.section("Clipboard")?;
+    check_clipboard(&mut report)?;
+
+    report.section("Connections")?;
+    match &config {
+        None => report.info("skipped: config did not load")?,
+        Some(_) if args.no_ping => report.info("skipped: --no-ping")?,
+        Some(config) => ping_connections(&mut report, config, args.connection.as_deref())?,
+    }
+
+    report.summary()
+}
+
+fn check_file<W: Write>(report: &mut Report<W>, path: &Path) -> Result<()> {
+    if let Err(error) = ensure_not_symlinked_file(path) {
+        return report.error(&format!("{error:#}"));
+    }
+    if path.is_file() {
+        report.ok(&path.display().to_string())
+    } else {
+        report.info(&format!("{} (not found)", path.display()))
+    }
+}
+
+fn check_editor<W: Write>(report: &mut Report<W>) -> Result<()> {
+    let configured = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .find(|name| env::var(name).is_ok_and(|value| !value.trim().is_empty()));
+    let editor = default_editor();
+    let source = match configured {
+        Some(name) => format!("${name}"),
+        None => {
+            report.warn("$VISUAL and $EDITOR are unset; the TUI cannot open an editor")?;
+            "CLI fallback".to_string()
+        }
+    };
+    let program = match parse_editor_command(&editor) {
+        Ok(args) => args.into_iter().next().unwrap_or_default(),
+        Err(error) => return report.error(&format!("editor '{editor}' from {source}: {error:#}")),
+    };
+    match find_program(&program) {
+        Some(path) => report.ok(&format!("{editor} from {source} ({})", path.display())),
+        None => report.error(&format!(
+            "editor '{program}' from {source} was not found on PATH"
+        )),
+    }
+}
+
+fn check_clipboard<W: Write>(report: &mut Report<W>) -> Result<()> {
+    let methods = clipboard_methods();
+    let available: Vec<&str> = methods
+        .native_commands
+        .iter()
+        .copied()
+        .filter(|program| find_program(program).is_some())
+        .collect();
+    match available.first() {
+        Some(program) => report.ok(&format!("native: {program}"))?,
+        None => report.warn(&format!(
+            "no native clipboard command found (tried {})",
+            methods.native_commands.join(", ")
+        ))?,
+    }
+    if methods.prefers_osc52 {
+        report.info("OSC52 terminal escape is tried first because $SSH_TTY is set")
+    } else {
+        report.info("OSC52 terminal escape is the fallback")
+    }
+}
+
+fn ping_connections<W: Write>(
+    report: &mut Report<W>,
+    config: &Config,
+    only: Option<&str>,
+) -> Result<()> {
+    let connections: Vec<_> = config
+        .connections
+        .iter()
+        .filter(|connection| only.is_none_or(|name| connection.name == name))
+        .collect();
+    if connections.is_empty() {
+        return match only {
+            Some(name) => report.error(&format!("connection '{name}' not found")),
+            None => report.info("no connections configured"),
+        };
+    }
+
+    let runtime = tokio::runtime::Runtime::new().context("unable to start async runtime")?;
+    let executor = MongoExecutor::new();
+    for connection in connections {
+        match runtime.block_on(executor.ping(config, connection)) {
+            Ok(status) => report.ok(&format!(
+                "{}: {}ms, MongoDB {}, {}",
+                connection.name,
+                status.latency.as_millis(),
+                status.version.as_deref().unwrap_or("version unknown"),
+                status.topology
+            ))?,
+            Err(error) => report.error(&format!("{}: {error:#}", connection.name))?,
+        }
+    }
+    Ok(())
+}
+
+fn find_program(program: &str) -> Option<PathBuf> {
+    let candidate = Path::new(program);
+    if candidate.components().count() > 1 {
+        return candidate.is_file().then(|| candidate.to_path_buf());
+    }
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path).find_map(|dir| {
+        let full = dir.join(program);
+        if full.is_file() {
+            return Some(full);
+        }
+        let exe = full.with_extension("exe");
+        (cfg!(windows) && exe.is_file()).then_some(exe)
+    })
+}
+
+/// Writes checks as they run so slow pings still show earlier results.
+struct Report<W: Write> {
+    out: W,
+    errors: usize,
+    warnings: usize,
+}
+
+impl<W: Write> Report<W> {
+    fn new(out: W) -> Self {
+        Self {
+            out,
+            errors: 0,
+            warnings: 0,
+        }
+    }
+
+    fn section(&mut self, title: &str) -> Result<()> {
+        writeln!(self.out, "\n{title}").context("unable to write doctor report")
+    }
+
+    fn line(&mut self, status: &str, message: &str) -> Result<()> {
+        writeln!(self.out, "  {status:<5} {message}").context("unable to write doctor report")
+    }
+
+    fn ok(&mut self, message: &str) -> Result<()> {
+        self.line("ok", message)
+    }
+
+    fn info(&mut self, message: &str) -> Result<()> {
+        self.line("-", message)
+    }
+
+    fn warn(&mut self, message: &str) -> Result<()> {
+        self.warnings += 1;
+        self.line("warn", message)
+    }
+
+    fn error(&mut self, message: &str) -> Result<()> {
+        self.errors += 1;
+        self.line("error", message)
+    }
+
+    fn summary(mut self) -> Result<()> {
+        writeln!(
+            self.out,
+            "\n{} error(s), {} warning(s)",
+            self.errors, self.warnings
+        )
+        .context("unable to write doctor report")?;
+        if self.errors > 0 {
+            anyhow::bail!("doctor found {} error(s)", self.errors);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazycompass_core::Config;
+    use std::path::Path;
+
+    use super::{Report, check_file, find_program, ping_connections};
+
+    #[test]
+    fn report_counts_problems_and_fails_on_errors() {
+        let mut output = Vec::new();
+        let mut report = Report::new(&mut output);
+        report.section("Config").expect("section");
+        report.ok("loaded 1 connection(s)").expect("ok");
+        report.warn("insecure").expect("warn");
+        check_file(
+            &mut report,
+            Path::new("/nonexistent/lazycompass/config.toml"),
+        )
+        .expect("check file");
+        ping_connections(&mut report, &Config::default(), Some("prod")).expect("ping");
+        let err = report.summary().expect_err("expected error summary");
+        assert_eq!(err.to_string(), "doctor found 1 error(s)");
+
+        let rendered = String::from_utf8(output).expect("utf8");
+        assert!(rendered.contains("  ok    loaded 1 connection(s)"));
+        assert!(rendered.contains("  -     /nonexistent/lazycompass/config.toml (not found)"));
+        assert!(rendered.contains("  error connection 'prod' not found"));
+        assert!(rendered.contains("1 error(s), 1 warning(s)"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn find_program_searches_path() {
+        assert!(find_program("sh").is_some());
+        assert!(find_program("lazycompass-definitely-missing").is_none());
+    }
+}
@@ -0,0 +1,237 @@
+use anyhow::{Context, Result};
+use lazycompass_core::{Config, HistoryEntry, HistoryPayload, OutputFormat, format_timestamp_ms};
+use lazycompass_mongo::{Bson, Document, parse_json_value};
+use lazycompass_storage::{ConfigPaths, append_history, clear_history, load_history};
+use std::time::Instant;
+
+use crate::cli::HistoryArgs;
+use crate::output::print_documents;
+
+pub(crate) fn run_history(args: HistoryArgs) -> Result<()> {
+    let cwd = std::env::current_dir().context("unable to resolve current directory")?;
+    let paths = ConfigPaths::resolve_from(&cwd)?;
+
+    if args.clear {
+        if clear_history(&paths)? {
+            println!("cleared query history");
+        } else {
+            println!("query history is already empty");
+        }
+        return Ok(());
+    }
+
+    let documents: Vec<Document> = load_history(&paths)?
+        .iter()
+        .rev()
+        .filter(|entry| entry_matches(entry, &args))
+        .take(args.limit)
+        .map(|entry| entry_document(entry, args.table))
+        .collect();
+    let format = if args.table {
+        OutputFormat::Table
+    } else {
+        OutputFormat::JsonPretty
+    };
+    print_documents(format, &documents, None)
+}
+
+/// Records a finished CLI run. History is best effort, so write failures only log a warning.
+pub(super) fn record_history(
+    paths: &ConfigPaths,
+    config: &Config,
+    mut entry: HistoryEntry,
+    started: Instant,
+    result: &Result<Vec<Document>>,
+) {
+    let outcome = match result {
+        Ok(documents) => Ok(documents.len()),
+        Err(error) => Err(format!("{error:#}")),
+    };
+    entry.finish(started.elapsed(), outcome);
+    if let Err(error) = append_history(paths, &config.history, entry) {
+        tracing::warn!(
+            component = "cli",
+            error = %format!("{error:#}"),
+            "unable to record query history"
+        );
+    }
+}
+
+fn entry_matches(entry: &HistoryEntry, args: &HistoryArgs) -> bool {
+    let connection_matches = args
+        .connection
+        .as_deref()
+        .is_none_or(|wanted| entry.connection.as_deref() == Some(wanted));
+    let database_matches = args.db.as_deref().is_none_or(|db| entry.database == db);
+    let collection_matches = args
+        .collection
+        .as_deref()
+        .is_none_or(|collection| entry.collection == collection);
+    connection_matches && database_matches && collection_matches
+}
+
+fn entry_document(entry: &HistoryEntry, table: bool) -> Document {
+    let mut document = Document::new();
+    document.insert("timestamp", format_timestamp_ms(entry.timestamp_ms));
+    document.insert("kind", entry.payload.kind_label());
+    document.insert("connection", optional_string(entry.connection.as_deref()));
+    document.insert("database", entry.database.clone());
+    document.insert("collection", entry.collection.clone());
+    document.insert("saved_id", optional_string(entry.saved_id.as_deref()));
+    document.insert("duration_ms", entry.duration_ms as i64);
+    document.insert(
+        "result_count",
+        entry
+            .result_count
+            .map(|count| Bson::Int64(count as i64))
+            .unwrap_or(Bson::Null),
+    );
+    document.insert("error", optional_string(entry.error.as_deref()));
+    // Table output only renders scalar columns, so keep the payload as compact text there.
+    let payload = if table {
+        Bson::String(payload_text(&entry.payload))
+    } else {
+        payload_value(&entry.payload)
+    };
+    document.insert("payload", payload);
+    document
+}
+
+fn optional_string(value: Option<&str>) -> Bson {
+    value
+        .map(|value| Bson::String(value.to_string()))
+        .unwrap_or(Bson::Null)
+}
+
+fn payload_text(payload: &HistoryPayload) -> String {
+    match payload {
+        HistoryPayload::Query {
+            filter,
+            projection,
+            sort,
+            limit,
+        } => {
+            let mut parts = Vec::new();
+            for (field, value) in [
+                ("filter", filter),
+                ("projection", projection),
+                ("sort", sort),
+            ] {
+                if let Some(value) = value {
+                    parts.push(format!("{field}={value}"));
+                }
+            }
+            if let Some(limit) = limit {
+                parts.push(format!("limit={limit}"));
+            }
+            parts.join(" ")
+        }
+        HistoryPayload::Aggregation { pipeline } => pipeline.clone(),
+    }
+}
+
+fn payload_value(payload: &HistoryPayload) -> Bson {
+    // Fall back to the raw text so entries recorded from unparsable input still display.
+    let json = |label: &str, value: &str| {
+        parse_json_value(label, value).unwrap_or_else(|_| Bson::String(value.to_string()))
+    };
+    match payload {
+        HistoryPayload::Query {
+            filter,
+            projection,
+            sort,
+            limit,
+        } => {
+            let mut document = Document::new();
+            for (field, value) in [
+                ("filter", filter),
+                ("projection", projection),
+                ("sort", sort),
+            ] {
+                if let Some(value) = value {
+                    document.insert(field, json(field, value));
+                }
+            }
+            if let Some(limit) = limit {
+                document.insert("limit", *limit as i64);
+            }
+            Bson::Document(document)
+        }
+        HistoryPayload::Aggregation { pipeline } => json("pipeline", pipeline),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazycompass_core::{HistoryEntry, HistoryPayload};
+    use lazycompass_mongo::Bson;
+
+    use super::{entry_document, entry_matches};
+    use crate::cli::HistoryArgs;
+
+    fn entry() -> HistoryEntry {
+        HistoryEntry {
+            timestamp_ms: 0,
+            connection: Some("local".to_string()),
+            database: "app".to_string(),
+            collection: "orders".to_string(),
+            saved_id: None,
+            payload: HistoryPayload::Query {
+                filter: Some(r#"{"status":"open"}"#.to_string()),
+                projection: None,
+                sort: None,
+                limit: Some(5),
+            },
+            duration_ms: 7,
+            result_count: Some(2),
+            error: None,
+        }
+    }
+
+    fn args() -> HistoryArgs {
+        HistoryArgs {
+            limit: 20,
+            connection: None,
+            db: None,
+            collection: None,
+            table: false,
+            clear: false,
+        }
+    }
+
+    #[test]
+    fn entry_matches_filters_by_namespace() {
+        assert!(entry_matches(&entry(), &args()));
+        assert!(entry_matches(
+            &entry(),
+            &HistoryArgs {
+                connection: Some("local".to_string()),
+                collection: Some("orders".to_string()),
+                ..args()
+            }
+        ));
+        assert!(!entry_matches(
+            &entry(),
+            &HistoryArgs {
+                db: Some("other".to_string()),
+                ..args()
+            }
+        ));
+    }
+
+    #[test]
+    fn entry_document_flattens_payload_for_tables() {
+        let document = entry_document(&entry(), false);
+        assert!(matches!(document.get("payload"), Some(Bson::Document(_))));
+        assert_eq!(
+            document.get_str("timestamp").expect("timestamp"),
+            "1970-01-01 00:00:00"
+        );
+
+        let document = entry_document(&entry(), true);
+        assert_eq!(
+            document.get_str("payload").expect("payload"),
+            r#"filter={"status":"open"} limit=5"#
+        );
+    }
+}
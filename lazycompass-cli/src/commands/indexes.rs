@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use lazycompass_core::OutputFormat;
 use lazycompass_mongo::MongoExecutor;
-use lazycompass_storage::{ConfigPaths, load_storage};
+use lazycompass_storage::{ConfigPaths, load_storage_with_profile};
 
 use super::database::resolve_database_arg;
 use crate::cli::IndexesArgs;
@@ -14,10 +14,11 @@ pub(crate) fn run_indexes(
     _dangerously_enable_write: bool,
     _allow_pipeline_writes: bool,
     allow_insecure: bool,
+    profile: Option<&str>,
 ) -> Result<()> {
     let cwd = std::env::current_dir().context("unable to resolve current directory")?;
     let paths = ConfigPaths::resolve_from(&cwd)?;
-    let storage = load_storage(&paths)?;
+    let storage = load_storage_with_profile(&paths, profile)?;
     let mut config = storage.config.clone();
     apply_cli_overrides(&mut config, allow_insecure);
     init_logging(&paths, &config)?;
@@ -74,7 +75,7 @@ fn output_format(args: &IndexesArgs) -> OutputFormat {
 
 #[cfg(test)]
 mod tests {
-    use lazycompass_core::{Config, ConnectionSpec, OutputFormat};
+    use lazycompass_core::{Config, ConnectionProtection, ConnectionSpec, OutputFormat};
 
     use super::output_format;
     use crate::cli::IndexesArgs;
@@ -96,7 +97,10 @@ mod tests {
             connections: vec![ConnectionSpec {
                 name: name.to_string(),
                 uri: format!("mongodb://{name}:27017"),
+                uri_command: None,
                 default_database: default_database.map(ToString::to_string),
+                protection: ConnectionProtection::Open,
+                options: None,
             }],
             ..Config::default()
         }
@@ -1,9 +1,10 @@
 use anyhow::{Context, Result};
 use lazycompass_core::WriteGuard;
 use lazycompass_mongo::{DocumentInsertSpec, MongoExecutor, parse_json_document};
-use lazycompass_storage::{ConfigPaths, load_storage};
+use lazycompass_storage::{ConfigPaths, load_storage_with_profile};
 
 use super::database::resolve_database_arg;
+use super::protection::ensure_connection_write;
 use crate::cli::InsertArgs;
 use crate::editor::read_document_input;
 use crate::errors::report_warnings;
@@ -15,10 +16,11 @@ pub(crate) fn run_insert(
     dangerously_enable_write: bool,
     allow_pipeline_writes: bool,
     allow_insecure: bool,
+    profile: Option<&str>,
 ) -> Result<()> {
     let cwd = std::env::current_dir().context("unable to resolve current directory")?;
     let paths = ConfigPaths::resolve_from(&cwd)?;
-    let storage = load_storage(&paths)?;
+    let storage = load_storage_with_profile(&paths, profile)?;
     let mut config = storage.config.clone();
     apply_cli_overrides(&mut config, allow_insecure);
     let write_guard = WriteGuard::new(dangerously_enable_write, allow_pipeline_writes);
@@ -39,6 +41,7 @@ pub(crate) fn run_insert(
         collection = spec.collection.as_str(),
         "inserting document"
     );
+    ensure_connection_write(write_guard, resolved_connection, "insert documents")?;
     let runtime = tokio::runtime::Runtime::new().context("unable to start async runtime")?;
     let inserted_id = runtime.block_on(executor.insert_document(&config, write_guard, &spec))?;
     println!("inserted document {}", format_bson(&inserted_id));
@@ -72,7 +75,7 @@ fn build_insert_spec(
 
 #[cfg(test)]
 mod tests {
-    use lazycompass_core::{Config, ConnectionSpec};
+    use lazycompass_core::{Config, ConnectionProtection, ConnectionSpec};
 
     use super::build_insert_spec;
     use crate::cli::InsertArgs;
@@ -82,7 +85,10 @@ mod tests {
             connections: vec![ConnectionSpec {
                 name: "local".to_string(),
                 uri: "mongodb://localhost:27017".to_string(),
+                uri_command: None,
                 default_database: Some("app".to_string()),
+                protection: ConnectionProtection::Open,
+                options: None,
             }],
             ..Config::default()
         }
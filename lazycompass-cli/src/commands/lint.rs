@@ -0,0 +1,267 @@
+use anyhow::{Context, Result};
+use lazycompass_core::{
+    LintDiagnostic, LintSeverity, SavedScope, line_of, validate_collection_name,
+    validate_database_name,
+};
+use lazycompass_mongo::{
+    is_known_pipeline_stage, is_pipeline_write_stage, normalize_json_text, parse_json_document,
+};
+use lazycompass_storage::{
+    ConfigPaths, lint_config, parse_saved_aggregation, parse_saved_query, saved_aggregation_files,
+    saved_query_files, validate_saved_id,
+};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::cli::LintArgs;
+
+pub(crate) fn run_lint(args: LintArgs) -> Result<()> {
+    let cwd = std::env::current_dir().context("unable to resolve current directory")?;
+    let paths = ConfigPaths::resolve_from(&cwd)?;
+
+    let mut diagnostics = lint_config(&paths);
+    for path in saved_query_files(&paths)? {
+        diagnostics.extend(lint_saved_file(&path, lint_saved_query));
+    }
+    for path in saved_aggregation_files(&paths)? {
+        diagnostics.extend(lint_saved_file(&path, lint_saved_aggregation));
+    }
+    diagnostics.sort_by(|left, right| {
+        (&left.path, left.line, left.severity).cmp(&(&right.path, right.line, right.severity))
+    });
+
+    for diagnostic in &mut diagnostics {
+        if let Ok(relative) = diagnostic.path.strip_prefix(&cwd) {
+            diagnostic.path = relative.to_path_buf();
+        }
+        println!("{diagnostic}");
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == LintSeverity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+    println!("{errors} error(s), {warnings} warning(s)");
+
+    if errors > 0 || (args.strict && warnings > 0) {
+        anyhow::bail!("lint found {errors} error(s) and {warnings} warning(s)");
+    }
+    Ok(())
+}
+
+fn lint_saved_file(
+    path: &Path,
+    lint: fn(&Path, &str, &str) -> Vec<LintDiagnostic>,
+) -> Vec<LintDiagnostic> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            return vec![LintDiagnostic::error(
+                path,
+                1,
+                format!("unable to read saved spec: {error}"),
+            )];
+        }
+    };
+    let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+        return vec![LintDiagnostic::error(
+            path,
+            1,
+            "saved spec filename is not valid UTF-8",
+        )];
+    };
+    if let Err(error) = validate_saved_id(id) {
+        return vec![LintDiagnostic::error(
+            path,
+            1,
+            format!("invalid saved id '{id}': {error}"),
+        )];
+    }
+    lint(path, id, &contents)
+}
+
+/// Runs the same shell-literal normalization as `saved new`/`saved edit` before parsing.
+fn normalize_saved_json(path: &Path, contents: &str) -> Result<String, LintDiagnostic> {
+    let normalized = normalize_json_text(contents)
+        .map_err(|error| LintDiagnostic::error(path, 1, format!("invalid JSON: {error:#}")))?;
+    if let Err(error) = serde_json::from_str::<Value>(&normalized) {
+        return Err(LintDiagnostic::error(
+            path,
+            error.line().max(1),
+            format!("invalid JSON: {error}"),
+        ));
+    }
+    Ok(normalized)
+}
+
+fn lint_saved_query(path: &Path, id: &str, contents: &str) -> Vec<LintDiagnostic> {
+    let normalized = match normalize_saved_json(path, contents) {
+        Ok(normalized) => normalized,
+        Err(diagnostic) => return vec![diagnostic],
+    };
+    let query = match parse_saved_query(id, &normalized) {
+        Ok(query) => query,
+        Err(error) => {
+            return vec![LintDiagnostic::error(
+                path,
+                error_line(contents, &error),
+                format!("{error:#}"),
+            )];
+        }
+    };
+
+    let mut diagnostics = lint_scope(path, &query.scope);
+    for (field, value) in [
+        ("filter", &query.filter),
+        ("projection", &query.projection),
+        ("sort", &query.sort),
+    ] {
+        if let Some(value) = value
+            && let Err(error) = parse_json_document(field, value)
+        {
+            diagnostics.push(LintDiagnostic::error(
+                path,
+                line_of(contents, &format!("\"{field}\"")),
+                format!("{error:#}"),
+            ));
+        }
+    }
+    diagnostics
+}
+
+fn lint_saved_aggregation(path: &Path, id: &str, contents: &str) -> Vec<LintDiagnostic> {
+    let normalized = match normalize_saved_json(path, contents) {
+        Ok(normalized) => normalized,
+        Err(diagnostic) => return vec![diagnostic],
+    };
+    let aggregation = match parse_saved_aggregation(id, &normalized) {
+        Ok(aggregation) => aggregation,
+        Err(error) => {
+            return vec![LintDiagnostic::error(
+                path,
+                error_line(contents, &error),
+                format!("{error:#}"),
+            )];
+        }
+    };
+
+    let mut diagnostics = lint_scope(path, &aggregation.scope);
+    let stages = match serde_json::from_str::<Value>(&aggregation.pipeline) {
+        Ok(Value::Array(stages)) => stages,
+        _ => {
+            diagnostics.push(LintDiagnostic::error(
+                path,
+                1,
+                "pipeline must be a JSON array",
+            ));
+            return diagnostics;
+        }
+    };
+    for (index, stage) in stages.iter().enumerate() {
+        let Some(stage) = stage.as_object() else {
+            diagnostics.push(LintDiagnostic::error(
+                path,
+                1,
+                format!("pipeline stage {index} must be a JSON object"),
+            ));
+            continue;
+        };
+        let mut keys = stage.keys();
+        let (Some(name), None) = (keys.next(), keys.next()) else {
+            diagnostics.push(LintDiagnostic::error(
+                path,
+                1,
+                format!("pipeline stage {index} must have exactly one stage operator"),
+            ));
+            continue;
+        };
+        let line = line_of(contents, &format!("\"{name}\""));
+        if !is_known_pipeline_stage(name) {
+            diagnostics.push(LintDiagnostic::error(
+                path,
+                line,
+                format!("unknown aggregation stage '{name}'"),
+            ));
+        } else if is_pipeline_write_stage(name) {
+            diagnostics.push(LintDiagnostic::warning(
+                path,
+                line,
+                format!(
+                    "write stage '{name}' requires --dangerously-enable-write and --allow-pipeline-writes"
+                ),
+            ));
+        }
+    }
+    diagnostics
+}
+
+fn lint_scope(path: &Path, scope: &SavedScope) -> Vec<LintDiagnostic> {
+    let Some((database, collection)) = scope.database_collection() else {
+        return Vec::new();
+    };
+    let mut diagnostics = Vec::new();
+    if let Err(message) = validate_database_name(database) {
+        diagnostics.push(LintDiagnostic::error(path, 1, message));
+    }
+    if let Err(message) = validate_collection_name(collection) {
+        diagnostics.push(LintDiagnostic::error(path, 1, message));
+    }
+    diagnostics
+}
+
+/// Payload errors name the offending field in quotes; point at that field when present.
+fn error_line(contents: &str, error: &anyhow::Error) -> usize {
+    let message = format!("{error:#}");
+    message
+        .split('\'')
+        .skip(1)
+        .step_by(2)
+        .find(|field| contents.contains(&format!("\"{field}\"")))
+        .map(|field| line_of(contents, &format!("\"{field}\"")))
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use lazycompass_core::LintSeverity;
+    use std::path::Path;
+
+    use super::{lint_saved_aggregation, lint_saved_query};
+
+    #[test]
+    fn lint_saved_query_reports_field_errors_at_their_line() {
+        let contents = "{\n  \"filter\": {},\n  \"limit\": \"ten\"\n}\n";
+        let diagnostics = lint_saved_query(Path::new("q.json"), "recent", contents);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 3);
+        assert!(diagnostics[0].message.contains("limit"));
+    }
+
+    #[test]
+    fn lint_saved_query_accepts_shell_literals_and_checks_scope() {
+        let contents = r#"{ "filter": { "_id": ObjectId("64e1f2b4c2a3e02c9a0a9c10") } }"#;
+        assert!(lint_saved_query(Path::new("q.json"), "app.orders.recent", contents).is_empty());
+
+        let diagnostics = lint_saved_query(Path::new("q.json"), "app.$orders.recent", contents);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("collection name"));
+    }
+
+    #[test]
+    fn lint_saved_aggregation_flags_unknown_and_write_stages() {
+        let contents =
+            "[\n  { \"$match\": {} },\n  { \"$mtach\": {} },\n  { \"$out\": \"archive\" }\n]\n";
+        let diagnostics = lint_saved_aggregation(Path::new("a.json"), "daily", contents);
+        let summary: Vec<(usize, LintSeverity)> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.severity))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(3, LintSeverity::Error), (4, LintSeverity::Warning)]
+        );
+        assert!(diagnostics[0].message.contains("$mtach"));
+    }
+}
@@ -1,18 +1,27 @@
 mod agg;
 mod config;
 mod database;
+mod doctor;
+mod history;
 mod indexes;
 mod init;
 mod insert;
+mod lint;
+mod protection;
 mod query;
+mod saved;
 mod update;
 mod upgrade;
 
 pub(crate) use agg::run_agg;
 pub(crate) use config::run_config;
+pub(crate) use doctor::run_doctor;
+pub(crate) use history::run_history;
 pub(crate) use indexes::run_indexes;
 pub(crate) use init::run_init;
 pub(crate) use insert::run_insert;
+pub(crate) use lint::run_lint;
 pub(crate) use query::run_query;
+pub(crate) use saved::run_saved;
 pub(crate) use update::run_update;
 pub(crate) use upgrade::run_upgrade;
@@ -0,0 +1,119 @@
+use anyhow::{Context, Result};
+use lazycompass_core::{ConnectionSpec, WriteGuard};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Checks session write mode and the connection's protection level before a CLI write.
+/// `confirm` connections prompt on stderr so piped JSON output on stdout stays clean.
+pub(super) fn ensure_connection_write(
+    guard: WriteGuard,
+    connection: &ConnectionSpec,
+    action: &str,
+) -> Result<()> {
+    guard.ensure_write_allowed(action)?;
+    connection.ensure_write_allowed(action)?;
+    if !connection.protection.requires_confirmation() {
+        return Ok(());
+    }
+    if !(io::stdin().is_terminal() && io::stderr().is_terminal()) {
+        anyhow::bail!(
+            "connection '{}' requires typing its name to confirm {action}; rerun in an interactive terminal",
+            connection.name
+        );
+    }
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    confirm_connection_name(connection, action, &mut input, &mut io::stderr())
+}
+
+fn confirm_connection_name<R: BufRead, W: Write>(
+    connection: &ConnectionSpec,
+    action: &str,
+    input: &mut R,
+    output: &mut W,
+) -> Result<()> {
+    write!(
+        output,
+        "{action} on protected connection '{}'. Type the connection name to continue: ",
+        connection.name
+    )
+    .context("unable to write prompt")?;
+    output.flush().context("unable to flush prompt")?;
+
+    let mut line = String::new();
+    input
+        .read_line(&mut line)
+        .context("unable to read confirmation")?;
+    if line.trim() != connection.name {
+        anyhow::bail!(
+            "confirmation did not match connection '{}'; {action} cancelled",
+            connection.name
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use lazycompass_core::{ConnectionProtection, ConnectionSpec, WriteGuard};
+    use std::io::Cursor;
+
+    use super::{confirm_connection_name, ensure_connection_write};
+
+    fn connection(protection: ConnectionProtection) -> ConnectionSpec {
+        ConnectionSpec {
+            name: "prod".to_string(),
+            uri: "mongodb://localhost:27017".to_string(),
+            uri_command: None,
+            default_database: None,
+            protection,
+            options: None,
+        }
+    }
+
+    #[test]
+    fn ensure_connection_write_blocks_read_only_even_with_write_mode() {
+        let err = ensure_connection_write(
+            WriteGuard::new(true, true),
+            &connection(ConnectionProtection::ReadOnly),
+            "insert documents",
+        )
+        .expect_err("expected read-only error");
+        assert!(err.to_string().contains("connection 'prod' is read-only"));
+
+        assert!(
+            ensure_connection_write(
+                WriteGuard::new(true, false),
+                &connection(ConnectionProtection::Open),
+                "insert documents",
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn confirm_connection_name_requires_exact_name() {
+        let connection = connection(ConnectionProtection::Confirm);
+        let mut output = Vec::new();
+        confirm_connection_name(
+            &connection,
+            "insert documents",
+            &mut Cursor::new("prod\n"),
+            &mut output,
+        )
+        .expect("confirmed");
+        assert!(
+            String::from_utf8(output)
+                .expect("utf8")
+                .contains("protected connection 'prod'")
+        );
+
+        let err = confirm_connection_name(
+            &connection,
+            "insert documents",
+            &mut Cursor::new("staging\n"),
+            &mut Vec::new(),
+        )
+        .expect_err("expected mismatch");
+        assert!(err.to_string().contains("insert documents cancelled"));
+    }
+}
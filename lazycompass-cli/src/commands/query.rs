@@ -1,9 +1,11 @@
 use anyhow::{Context, Result};
-use lazycompass_core::{OutputFormat, QueryRequest, QueryTarget};
+use lazycompass_core::{HistoryEntry, HistoryPayload, OutputFormat, QueryRequest, QueryTarget};
 use lazycompass_mongo::{MongoExecutor, QuerySpec};
-use lazycompass_storage::{ConfigPaths, StorageSnapshot, load_storage};
+use lazycompass_storage::{ConfigPaths, StorageSnapshot, load_storage_with_profile};
+use std::time::Instant;
 
 use super::database::resolve_database_arg;
+use super::history::record_history;
 use crate::cli::QueryArgs;
 use crate::errors::report_warnings;
 use crate::logging::{apply_cli_overrides, init_logging};
@@ -14,10 +16,11 @@ pub(crate) fn run_query(
     _dangerously_enable_write: bool,
     _allow_pipeline_writes: bool,
     allow_insecure: bool,
+    profile: Option<&str>,
 ) -> Result<()> {
     let cwd = std::env::current_dir().context("unable to resolve current directory")?;
     let paths = ConfigPaths::resolve_from(&cwd)?;
-    let storage = load_storage(&paths)?;
+    let storage = load_storage_with_profile(&paths, profile)?;
     let mut config = storage.config.clone();
     apply_cli_overrides(&mut config, allow_insecure);
     init_logging(&paths, &config)?;
@@ -45,8 +48,27 @@ pub(crate) fn run_query(
         collection = spec.collection.as_str(),
         "executing query"
     );
+    let saved_id = match &request.target {
+        QueryTarget::Saved { id, .. } => Some(id.clone()),
+        QueryTarget::Inline { .. } => None,
+    };
+    let history_entry = HistoryEntry::started(
+        Some(connection.name.clone()),
+        spec.database.clone(),
+        spec.collection.clone(),
+        saved_id,
+        HistoryPayload::Query {
+            filter: spec.filter.clone(),
+            projection: spec.projection.clone(),
+            sort: spec.sort.clone(),
+            limit: spec.limit,
+        },
+    );
     let runtime = tokio::runtime::Runtime::new().context("unable to start async runtime")?;
-    let documents = runtime.block_on(executor.execute_query(&config, &spec))?;
+    let started = Instant::now();
+    let result = runtime.block_on(executor.execute_query(&config, &spec));
+    record_history(&paths, &config, history_entry, started, &result);
+    let documents = result?;
     print_documents(request.output, &documents, output_path.as_deref())
 }
 
@@ -172,7 +194,8 @@ fn resolve_query_spec(request: &QueryRequest, storage: &StorageSnapshot) -> Resu
 #[cfg(test)]
 mod tests {
     use lazycompass_core::{
-        Config, ConnectionSpec, OutputFormat, QueryTarget, SavedQuery, SavedScope,
+        Config, ConnectionProtection, ConnectionSpec, OutputFormat, QueryTarget, SavedQuery,
+        SavedScope,
     };
     use lazycompass_storage::StorageSnapshot;
 
@@ -209,7 +232,10 @@ mod tests {
             connections: vec![ConnectionSpec {
                 name: name.to_string(),
                 uri: format!("mongodb://{name}:27017"),
+                uri_command: None,
                 default_database: default_database.map(ToString::to_string),
+                protection: ConnectionProtection::Open,
+                options: None,
             }],
             ..Config::default()
         }
@@ -317,6 +343,7 @@ mod tests {
                 projection: None,
                 sort: None,
                 limit: Some(5),
+                tags: Vec::new(),
             }],
         );
 
@@ -344,6 +371,7 @@ mod tests {
                 projection: None,
                 sort: None,
                 limit: None,
+                tags: Vec::new(),
             }],
         );
 
@@ -373,6 +401,7 @@ mod tests {
                 projection: None,
                 sort: None,
                 limit: None,
+                tags: Vec::new(),
             }],
         );
 
@@ -0,0 +1,364 @@
+use anyhow::{Context, Result};
+use lazycompass_core::{
+    OutputFormat, SavedAggregation, SavedQuery, SavedScope, redact_sensitive_text,
+};
+use lazycompass_mongo::{Bson, Document, normalize_json_text};
+use lazycompass_storage::{
+    ConfigPaths, copy_saved_aggregation, copy_saved_query, delete_saved_aggregation,
+    delete_saved_query, load_saved_aggregation, load_saved_aggregations, load_saved_queries,
+    load_saved_query, parse_saved_aggregation, parse_saved_query, rename_saved_aggregation,
+    rename_saved_query, render_saved_aggregation, render_saved_query, saved_aggregation_path,
+    saved_query_path, write_saved_aggregation, write_saved_query,
+};
+use std::fs;
+
+use crate::cli::{
+    SavedArgs, SavedCommands, SavedKind, SavedListArgs, SavedNewArgs, SavedScopeFilter,
+    SavedTargetArgs, SavedTransferArgs,
+};
+use crate::editor::edit_text_in_editor;
+use crate::output::print_documents;
+
+pub(crate) fn run_saved(args: SavedArgs) -> Result<()> {
+    let cwd = std::env::current_dir().context("unable to resolve current directory")?;
+    let paths = ConfigPaths::resolve_from(&cwd)?;
+
+    match args.command {
+        SavedCommands::List(args) => run_saved_list(&paths, args),
+        SavedCommands::Show(args) => run_saved_show(&paths, args),
+        SavedCommands::New(args) => run_saved_new(&paths, args),
+        SavedCommands::Edit(args) => run_saved_edit(&paths, args),
+        SavedCommands::Rm(args) => run_saved_rm(&paths, args),
+        SavedCommands::Mv(args) => run_saved_transfer(&paths, args, true),
+        SavedCommands::Copy(args) => run_saved_transfer(&paths, args, false),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SavedEntry {
+    kind: SavedKind,
+    id: String,
+    scope: SavedScope,
+    tags: Vec<String>,
+}
+
+impl SavedEntry {
+    fn from_query(query: SavedQuery) -> Self {
+        Self {
+            kind: SavedKind::Query,
+            id: query.id,
+            scope: query.scope,
+            tags: query.tags,
+        }
+    }
+
+    fn from_aggregation(aggregation: SavedAggregation) -> Self {
+        Self {
+            kind: SavedKind::Agg,
+            id: aggregation.id,
+            scope: aggregation.scope,
+            tags: aggregation.tags,
+        }
+    }
+
+    fn to_document(&self, table: bool) -> Document {
+        let (scope, database, collection) = match &self.scope {
+            SavedScope::Shared => ("shared", Bson::Null, Bson::Null),
+            SavedScope::Scoped {
+                database,
+                collection,
+            } => (
+                "scoped",
+                Bson::String(database.clone()),
+                Bson::String(collection.clone()),
+            ),
+        };
+        let mut document = Document::new();
+        document.insert("kind", kind_name(self.kind));
+        document.insert("id", self.id.clone());
+        document.insert("scope", scope);
+        document.insert("database", database);
+        document.insert("collection", collection);
+        // Table output only renders scalar columns, so flatten tags there.
+        let tags = if table {
+            Bson::String(self.tags.join(","))
+        } else {
+            Bson::Array(self.tags.iter().cloned().map(Bson::String).collect())
+        };
+        document.insert("tags", tags);
+        document
+    }
+}
+
+fn run_saved_list(paths: &ConfigPaths, args: SavedListArgs) -> Result<()> {
+    let mut entries = Vec::new();
+    let mut warnings = Vec::new();
+    if args.kind != Some(SavedKind::Agg) {
+        let (queries, query_warnings) = load_saved_queries(paths)?;
+        entries.extend(queries.into_iter().map(SavedEntry::from_query));
+        warnings.extend(query_warnings);
+    }
+    if args.kind != Some(SavedKind::Query) {
+        let (aggregations, aggregation_warnings) = load_saved_aggregations(paths)?;
+        entries.extend(aggregations.into_iter().map(SavedEntry::from_aggregation));
+        warnings.extend(aggregation_warnings);
+    }
+    for warning in &warnings {
+        eprintln!("warning: {}", redact_sensitive_text(warning));
+    }
+
+    let documents: Vec<Document> = entries
+        .iter()
+        .filter(|entry| entry_matches(entry, &args))
+        .map(|entry| entry.to_document(args.table))
+        .collect();
+    let format = if args.table {
+        OutputFormat::Table
+    } else {
+        OutputFormat::JsonPretty
+    };
+    print_documents(format, &documents, None)
+}
+
+fn entry_matches(entry: &SavedEntry, args: &SavedListArgs) -> bool {
+    let scoped = entry.scope.database_collection();
+    let scope_matches = match args.scope {
+        None => true,
+        Some(SavedScopeFilter::Shared) => scoped.is_none(),
+        Some(SavedScopeFilter::Scoped) => scoped.is_some(),
+    };
+    let database_matches = args
+        .db
+        .as_deref()
+        .is_none_or(|db| scoped.is_some_and(|(database, _)| database == db));
+    let collection_matches = args
+        .collection
+        .as_deref()
+        .is_none_or(|wanted| scoped.is_some_and(|(_, collection)| collection == wanted));
+    let tags_match = args
+        .tags
+        .iter()
+        .all(|tag| entry.tags.iter().any(|existing| existing == tag));
+    scope_matches && database_matches && collection_matches && tags_match
+}
+
+fn run_saved_show(paths: &ConfigPaths, args: SavedTargetArgs) -> Result<()> {
+    let contents = match args.kind {
+        SavedKind::Query => render_saved_query(&load_saved_query(paths, &args.id)?)?,
+        SavedKind::Agg => render_saved_aggregation(&load_saved_aggregation(paths, &args.id)?)?,
+    };
+    println!("{contents}");
+    Ok(())
+}
+
+fn run_saved_new(paths: &ConfigPaths, args: SavedNewArgs) -> Result<()> {
+    let existing = match args.kind {
+        SavedKind::Query => saved_query_path(paths, &args.id)?,
+        SavedKind::Agg => saved_aggregation_path(paths, &args.id)?,
+    };
+    if existing.exists() {
+        anyhow::bail!("{} '{}' already exists", kind_label(args.kind), args.id);
+    }
+
+    let contents = match args.file.as_deref() {
+        Some(path) => fs::read_to_string(path)
+            .with_context(|| format!("unable to read saved spec file {path}"))?,
+        None => edit_text_in_editor("saved", &new_template(args.kind, &args.tags)?)?,
+    };
+    let path = match args.kind {
+        SavedKind::Query => {
+            let mut query = parse_query_input(&args.id, &contents)?;
+            merge_tags(&mut query.tags, &args.tags);
+            write_saved_query(paths, &query, false)?
+        }
+        SavedKind::Agg => {
+            let mut aggregation = parse_aggregation_input(&args.id, &contents)?;
+            merge_tags(&mut aggregation.tags, &args.tags);
+            write_saved_aggregation(paths, &aggregation, false)?
+        }
+    };
+    println!("created {} {}", kind_label(args.kind), path.display());
+    Ok(())
+}
+
+fn run_saved_edit(paths: &ConfigPaths, args: SavedTargetArgs) -> Result<()> {
+    let path = match args.kind {
+        SavedKind::Query => {
+            let current = render_saved_query(&load_saved_query(paths, &args.id)?)?;
+            let contents = edit_text_in_editor("saved", &current)?;
+            write_saved_query(paths, &parse_query_input(&args.id, &contents)?, true)?
+        }
+        SavedKind::Agg => {
+            let current = render_saved_aggregation(&load_saved_aggregation(paths, &args.id)?)?;
+            let contents = edit_text_in_editor("saved", &current)?;
+            write_saved_aggregation(paths, &parse_aggregation_input(&args.id, &contents)?, true)?
+        }
+    };
+    println!("updated {} {}", kind_label(args.kind), path.display());
+    Ok(())
+}
+
+fn run_saved_rm(paths: &ConfigPaths, args: SavedTargetArgs) -> Result<()> {
+    let path = match args.kind {
+        SavedKind::Query => delete_saved_query(paths, &args.id)?,
+        SavedKind::Agg => delete_saved_aggregation(paths, &args.id)?,
+    };
+    println!("removed {} {}", kind_label(args.kind), path.display());
+    Ok(())
+}
+
+fn run_saved_transfer(paths: &ConfigPaths, args: SavedTransferArgs, rename: bool) -> Result<()> {
+    let path = match (args.kind, rename) {
+        (SavedKind::Query, true) => rename_saved_query(paths, &args.from, &args.to, args.force)?,
+        (SavedKind::Query, false) => copy_saved_query(paths, &args.from, &args.to, args.force)?,
+        (SavedKind::Agg, true) => {
+            rename_saved_aggregation(paths, &args.from, &args.to, args.force)?
+        }
+        (SavedKind::Agg, false) => copy_saved_aggregation(paths, &args.from, &args.to, args.force)?,
+    };
+    let verb = if rename { "renamed" } else { "copied" };
+    println!(
+        "{verb} {} '{}' to {}",
+        kind_label(args.kind),
+        args.from,
+        path.display()
+    );
+    Ok(())
+}
+
+fn parse_query_input(id: &str, contents: &str) -> Result<SavedQuery> {
+    let contents = normalize_json_text(contents).context("invalid JSON for saved query")?;
+    parse_saved_query(id, &contents)
+}
+
+fn parse_aggregation_input(id: &str, contents: &str) -> Result<SavedAggregation> {
+    let contents = normalize_json_text(contents).context("invalid JSON for saved aggregation")?;
+    parse_saved_aggregation(id, &contents)
+}
+
+fn new_template(kind: SavedKind, tags: &[String]) -> Result<String> {
+    match kind {
+        SavedKind::Query => render_saved_query(&SavedQuery {
+            id: String::new(),
+            scope: SavedScope::Shared,
+            filter: Some("{}".to_string()),
+            projection: None,
+            sort: None,
+            limit: None,
+            tags: tags.to_vec(),
+        }),
+        SavedKind::Agg => render_saved_aggregation(&SavedAggregation {
+            id: String::new(),
+            scope: SavedScope::Shared,
+            pipeline: r#"[{"$match":{}}]"#.to_string(),
+            tags: tags.to_vec(),
+        }),
+    }
+}
+
+fn merge_tags(tags: &mut Vec<String>, extra: &[String]) {
+    for tag in extra {
+        let tag = tag.trim();
+        if !tag.is_empty() && !tags.iter().any(|existing| existing == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+}
+
+fn kind_name(kind: SavedKind) -> &'static str {
+    match kind {
+        SavedKind::Query => "query",
+        SavedKind::Agg => "agg",
+    }
+}
+
+fn kind_label(kind: SavedKind) -> &'static str {
+    match kind {
+        SavedKind::Query => "saved query",
+        SavedKind::Agg => "saved aggregation",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazycompass_core::SavedScope;
+
+    use super::{SavedEntry, entry_matches, merge_tags, parse_query_input};
+    use crate::cli::{SavedKind, SavedListArgs, SavedScopeFilter};
+
+    fn list_args() -> SavedListArgs {
+        SavedListArgs {
+            kind: None,
+            scope: None,
+            db: None,
+            collection: None,
+            tags: Vec::new(),
+            table: false,
+        }
+    }
+
+    fn scoped_entry(tags: &[&str]) -> SavedEntry {
+        SavedEntry {
+            kind: SavedKind::Query,
+            id: "app.orders.recent".to_string(),
+            scope: SavedScope::Scoped {
+                database: "app".to_string(),
+                collection: "orders".to_string(),
+            },
+            tags: tags.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn entry_matches_filters_by_scope_collection_and_tags() {
+        let entry = scoped_entry(&["ops", "reports"]);
+        assert!(entry_matches(&entry, &list_args()));
+
+        let mut args = list_args();
+        args.scope = Some(SavedScopeFilter::Shared);
+        assert!(!entry_matches(&entry, &args));
+
+        let mut args = list_args();
+        args.db = Some("app".to_string());
+        args.collection = Some("orders".to_string());
+        args.tags = vec!["ops".to_string()];
+        assert!(entry_matches(&entry, &args));
+
+        args.tags.push("missing".to_string());
+        assert!(!entry_matches(&entry, &args));
+    }
+
+    #[test]
+    fn entry_matches_excludes_shared_specs_from_collection_filter() {
+        let entry = SavedEntry {
+            kind: SavedKind::Agg,
+            id: "recent".to_string(),
+            scope: SavedScope::Shared,
+            tags: Vec::new(),
+        };
+        let mut args = list_args();
+        args.collection = Some("orders".to_string());
+        assert!(!entry_matches(&entry, &args));
+    }
+
+    #[test]
+    fn parse_query_input_accepts_shell_literals_and_scopes_from_id() {
+        let query = parse_query_input(
+            "app.orders.recent",
+            r#"{ "filter": { "_id": ObjectId("64e1f2b4c2a3e02c9a0a9c10") } }"#,
+        )
+        .expect("parse query");
+        assert!(matches!(query.scope, SavedScope::Scoped { .. }));
+        assert!(query.filter.as_deref().is_some_and(|f| f.contains("$oid")));
+    }
+
+    #[test]
+    fn merge_tags_skips_duplicates_and_blanks() {
+        let mut tags = vec!["ops".to_string()];
+        merge_tags(
+            &mut tags,
+            &["ops".to_string(), " ".to_string(), "daily".to_string()],
+        );
+        assert_eq!(tags, vec!["ops".to_string(), "daily".to_string()]);
+    }
+}
@@ -1,9 +1,10 @@
 use anyhow::{Context, Result};
 use lazycompass_core::WriteGuard;
 use lazycompass_mongo::{Bson, Document, DocumentReplaceSpec, MongoExecutor, parse_json_document};
-use lazycompass_storage::{ConfigPaths, load_storage};
+use lazycompass_storage::{ConfigPaths, load_storage_with_profile};
 
 use super::database::resolve_database_arg;
+use super::protection::ensure_connection_write;
 use crate::cli::UpdateArgs;
 use crate::editor::{parse_json_value, read_document_input};
 use crate::errors::report_warnings;
@@ -15,10 +16,11 @@ pub(crate) fn run_update(
     dangerously_enable_write: bool,
     allow_pipeline_writes: bool,
     allow_insecure: bool,
+    profile: Option<&str>,
 ) -> Result<()> {
     let cwd = std::env::current_dir().context("unable to resolve current directory")?;
     let paths = ConfigPaths::resolve_from(&cwd)?;
-    let storage = load_storage(&paths)?;
+    let storage = load_storage_with_profile(&paths, profile)?;
     let mut config = storage.config.clone();
     apply_cli_overrides(&mut config, allow_insecure);
     let write_guard = WriteGuard::new(dangerously_enable_write, allow_pipeline_writes);
@@ -39,6 +41,7 @@ pub(crate) fn run_update(
         collection = spec.collection.as_str(),
         "replacing document"
     );
+    ensure_connection_write(write_guard, resolved_connection, "replace documents")?;
     let runtime = tokio::runtime::Runtime::new().context("unable to start async runtime")?;
     runtime.block_on(executor.replace_document(&config, write_guard, &spec))?;
     if id_changed {
@@ -93,7 +96,7 @@ fn ensure_document_id(document: &mut Document, id: &Bson) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use lazycompass_core::{Config, ConnectionSpec};
+    use lazycompass_core::{Config, ConnectionProtection, ConnectionSpec};
     use lazycompass_mongo::{Bson, Document};
 
     use super::{build_update_spec, ensure_document_id};
@@ -104,7 +107,10 @@ mod tests {
             connections: vec![ConnectionSpec {
                 name: "local".to_string(),
                 uri: "mongodb://localhost:27017".to_string(),
+                uri_command: None,
                 default_database: Some("app".to_string()),
+                protection: ConnectionProtection::Open,
+                options: None,
             }],
             ..Config::default()
         }
@@ -27,7 +27,7 @@ pub(crate) fn open_in_editor(path: &Path) -> Result<()> {
     Ok(())
 }
 
-fn default_editor() -> String {
+pub(crate) fn default_editor() -> String {
     env::var("VISUAL")
         .ok()
         .filter(|value| !value.trim().is_empty())
@@ -61,15 +61,19 @@ pub(crate) fn read_document_input(
             .with_context(|| format!("unable to read document file {path}"));
     }
 
-    let temp_path = create_secure_temp_file(label, "json", "{}")?;
+    let contents = edit_text_in_editor(label, "{}")?;
+    if contents.trim().is_empty() {
+        anyhow::bail!("document cannot be empty");
+    }
+    Ok(contents)
+}
+
+pub(crate) fn edit_text_in_editor(label: &str, initial: &str) -> Result<String> {
+    let temp_path = create_secure_temp_file(label, "json", initial)?;
     let result = (|| {
         open_in_editor(&temp_path)?;
-        let contents = fs::read_to_string(&temp_path)
-            .with_context(|| format!("unable to read temp file {}", temp_path.display()))?;
-        if contents.trim().is_empty() {
-            anyhow::bail!("document cannot be empty");
-        }
-        Ok(contents)
+        fs::read_to_string(&temp_path)
+            .with_context(|| format!("unable to read temp file {}", temp_path.display()))
     })();
     let _ = fs::remove_file(&temp_path);
     result
@@ -141,7 +145,7 @@ fn write_new_temp_file(path: &Path, contents: &str) -> Result<()> {
     Ok(())
 }
 
-fn parse_editor_command(editor: &str) -> Result<Vec<String>> {
+pub(crate) fn parse_editor_command(editor: &str) -> Result<Vec<String>> {
     let mut args = Vec::new();
     let mut current = String::new();
     let mut chars = editor.chars().peekable();
@@ -1,3 +1,5 @@
+mod uri_command;
+
 pub use mongodb::bson::{Bson, Document};
 
 use anyhow::{Context, Result};
@@ -11,6 +13,7 @@ use mongodb::{
     options::{AggregateOptions, ClientOptions, FindOptions},
 };
 use serde_json::Value;
+use std::time::{Duration, Instant};
 
 const MAX_RESULT_DOCUMENTS: usize = 10_000;
 
@@ -40,6 +43,7 @@ pub struct DocumentListSpec {
     pub collection: String,
     pub skip: u64,
     pub limit: u64,
+    pub sort: Option<Document>,
 }
 
 #[derive(Debug, Clone)]
@@ -59,6 +63,37 @@ pub struct DocumentReplaceSpec {
     pub document: Document,
 }
 
+/// Targeted update of one document by `_id`, such as `{"$set": {"status": "done"}}`.
+#[derive(Debug, Clone)]
+pub struct DocumentUpdateSpec {
+    pub connection: Option<String>,
+    pub database: String,
+    pub collection: String,
+    pub id: Bson,
+    pub update: Document,
+    /// Extra filter conditions the document must still match, so an update computed from a
+    /// loaded copy fails instead of overwriting newer changes. Empty matches on `_id` alone.
+    pub expected: Document,
+}
+
+/// Looks up one document by `_id`.
+#[derive(Debug, Clone)]
+pub struct DocumentFindSpec {
+    pub connection: Option<String>,
+    pub database: String,
+    pub collection: String,
+    pub id: Bson,
+}
+
+/// Counts the documents matching an optional JSON filter.
+#[derive(Debug, Clone)]
+pub struct DocumentCountSpec {
+    pub connection: Option<String>,
+    pub database: String,
+    pub collection: String,
+    pub filter: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct DocumentDeleteSpec {
     pub connection: Option<String>,
@@ -67,6 +102,23 @@ pub struct DocumentDeleteSpec {
     pub id: Bson,
 }
 
+/// Deletes every document whose `_id` is listed.
+#[derive(Debug, Clone)]
+pub struct DocumentBulkDeleteSpec {
+    pub connection: Option<String>,
+    pub database: String,
+    pub collection: String,
+    pub ids: Vec<Bson>,
+}
+
+/// Result of a connectivity check against one connection.
+#[derive(Debug, Clone)]
+pub struct ServerStatus {
+    pub latency: Duration,
+    pub version: Option<String>,
+    pub topology: &'static str,
+}
+
 #[derive(Debug, Default)]
 pub struct MongoExecutor;
 
@@ -170,6 +222,9 @@ impl MongoExecutor {
 
         let pipeline = parse_json_pipeline(&spec.pipeline)?;
         ensure_pipeline_allowed(guard, &pipeline)?;
+        if find_pipeline_write_stage(&pipeline).is_some() {
+            ensure_connection_write_allowed(connection, "aggregation pipeline write stages")?;
+        }
         let options = AggregateOptions::builder()
             .max_time(config.query_timeout())
             .build();
@@ -188,6 +243,33 @@ impl MongoExecutor {
         Ok(documents)
     }
 
+    /// Connects, then times a `ping` after a `hello` warm-up so latency excludes the handshake.
+    pub async fn ping(&self, config: &Config, connection: &ConnectionSpec) -> Result<ServerStatus> {
+        let client = connect(config, connection).await?;
+        let admin = client.database("admin");
+        let hello = admin
+            .run_command(bson::doc! { "hello": 1 })
+            .await
+            .with_context(|| format!("unable to reach connection '{}'", connection.name))?;
+        let started = Instant::now();
+        admin
+            .run_command(bson::doc! { "ping": 1 })
+            .await
+            .with_context(|| format!("failed to ping connection '{}'", connection.name))?;
+        let latency = started.elapsed();
+        // buildInfo can be restricted by roles; a missing version is not a connectivity failure.
+        let version = admin
+            .run_command(bson::doc! { "buildInfo": 1 })
+            .await
+            .ok()
+            .and_then(|info| info.get_str("version").ok().map(ToString::to_string));
+        Ok(ServerStatus {
+            latency,
+            version,
+            topology: topology_label(&hello),
+        })
+    }
+
     pub async fn list_databases(
         &self,
         config: &Config,
@@ -260,6 +342,7 @@ impl MongoExecutor {
         let mut options = FindOptions::default();
         options.skip = Some(spec.skip);
         options.limit = Some(spec.limit as i64);
+        options.sort = spec.sort.clone();
         options.max_time = Some(config.query_timeout());
 
         let cursor = collection
@@ -279,6 +362,52 @@ impl MongoExecutor {
         Ok(documents)
     }
 
+    /// Returns the current server version of one document, or `None` when it no longer exists.
+    pub async fn find_document(
+        &self,
+        config: &Config,
+        spec: &DocumentFindSpec,
+    ) -> Result<Option<Document>> {
+        let connection = self.resolve_connection(config, spec.connection.as_deref())?;
+        let client = connect(config, connection).await?;
+        let database = client.database(&spec.database);
+        let collection = database.collection::<Document>(&spec.collection);
+
+        let filter = bson::doc! { "_id": spec.id.clone() };
+        collection
+            .find_one(filter)
+            .max_time(config.query_timeout())
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to load document from {}.{}",
+                    spec.database, spec.collection
+                )
+            })
+    }
+
+    pub async fn count_documents(&self, config: &Config, spec: &DocumentCountSpec) -> Result<u64> {
+        let connection = self.resolve_connection(config, spec.connection.as_deref())?;
+        let client = connect(config, connection).await?;
+        let database = client.database(&spec.database);
+        let collection = database.collection::<Document>(&spec.collection);
+
+        let filter = match normalize_json_option(spec.filter.clone()) {
+            Some(value) => parse_json_document("filter", &value)?,
+            None => Document::new(),
+        };
+        collection
+            .count_documents(filter)
+            .max_time(config.query_timeout())
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to count documents in {}.{}",
+                    spec.database, spec.collection
+                )
+            })
+    }
+
     pub async fn insert_document(
         &self,
         config: &Config,
@@ -287,6 +416,7 @@ impl MongoExecutor {
     ) -> Result<Bson> {
         ensure_write_allowed(guard, "insert documents")?;
         let connection = self.resolve_connection(config, spec.connection.as_deref())?;
+        ensure_connection_write_allowed(connection, "insert documents")?;
         let client = connect(config, connection).await?;
         let database = client.database(&spec.database);
         let collection = database.collection::<Document>(&spec.collection);
@@ -311,6 +441,7 @@ impl MongoExecutor {
     ) -> Result<()> {
         ensure_write_allowed(guard, "replace documents")?;
         let connection = self.resolve_connection(config, spec.connection.as_deref())?;
+        ensure_connection_write_allowed(connection, "replace documents")?;
         let client = connect(config, connection).await?;
         let database = client.database(&spec.database);
         let collection = database.collection::<Document>(&spec.collection);
@@ -329,6 +460,41 @@ impl MongoExecutor {
         Ok(())
     }
 
+    pub async fn update_document(
+        &self,
+        config: &Config,
+        guard: WriteGuard,
+        spec: &DocumentUpdateSpec,
+    ) -> Result<()> {
+        ensure_write_allowed(guard, "update documents")?;
+        let connection = self.resolve_connection(config, spec.connection.as_deref())?;
+        ensure_connection_write_allowed(connection, "update documents")?;
+        let client = connect(config, connection).await?;
+        let database = client.database(&spec.database);
+        let collection = database.collection::<Document>(&spec.collection);
+
+        let mut filter = bson::doc! { "_id": spec.id.clone() };
+        filter.extend(spec.expected.clone());
+        let result = collection
+            .update_one(filter, spec.update.clone())
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to update document in {}.{}",
+                    spec.database, spec.collection
+                )
+            })?;
+        if result.matched_count == 0 && !spec.expected.is_empty() {
+            anyhow::bail!(
+                "document in {}.{} was removed or changed since it was loaded; reload and try again",
+                spec.database,
+                spec.collection
+            );
+        }
+        ensure_document_matched(result.matched_count, &spec.database, &spec.collection)?;
+        Ok(())
+    }
+
     pub async fn delete_document(
         &self,
         config: &Config,
@@ -337,6 +503,7 @@ impl MongoExecutor {
     ) -> Result<()> {
         ensure_write_allowed(guard, "delete documents")?;
         let connection = self.resolve_connection(config, spec.connection.as_deref())?;
+        ensure_connection_write_allowed(connection, "delete documents")?;
         let client = connect(config, connection).await?;
         let database = client.database(&spec.database);
         let collection = database.collection::<Document>(&spec.collection);
@@ -351,17 +518,90 @@ impl MongoExecutor {
         ensure_document_deleted(result.deleted_count, &spec.database, &spec.collection)?;
         Ok(())
     }
+
+    /// Deletes the listed documents and returns how many were removed.
+    pub async fn delete_documents(
+        &self,
+        config: &Config,
+        guard: WriteGuard,
+        spec: &DocumentBulkDeleteSpec,
+    ) -> Result<u64> {
+        ensure_write_allowed(guard, "delete documents")?;
+        let connection = self.resolve_connection(config, spec.connection.as_deref())?;
+        ensure_connection_write_allowed(connection, "delete documents")?;
+        let client = connect(config, connection).await?;
+        let database = client.database(&spec.database);
+        let collection = database.collection::<Document>(&spec.collection);
+
+        let filter = bson::doc! { "_id": { "$in": spec.ids.clone() } };
+        let result = collection.delete_many(filter).await.with_context(|| {
+            format!(
+                "failed to delete documents from {}.{}",
+                spec.database, spec.collection
+            )
+        })?;
+        ensure_document_deleted(result.deleted_count, &spec.database, &spec.collection)?;
+        Ok(result.deleted_count)
+    }
 }
 
 async fn connect(config: &Config, connection: &ConnectionSpec) -> Result<Client> {
-    let redacted_uri = redact_connection_uri(&connection.uri);
-    ensure_connection_security(config, connection).map_err(|error| anyhow::anyhow!("{error}"))?;
-    let mut options = ClientOptions::parse(&connection.uri)
+    let redacted_uri = redact_connection_uri(&connection.effective_uri());
+    let options = client_options(config, connection).await?;
+    Client::with_options(options).with_context(|| format!("unable to connect to {redacted_uri}"))
+}
+
+async fn client_options(config: &Config, connection: &ConnectionSpec) -> Result<ClientOptions> {
+    let resolved = ConnectionSpec {
+        uri: uri_command::resolve_connection_uri(connection, config.connect_timeout()).await?,
+        uri_command: None,
+        options: None,
+        ..connection.clone()
+    };
+    // Config loading resolves `password_env`; fall back to the process env for
+    // connections added during the session.
+    let password = connection.options.as_ref().and_then(|options| {
+        options.password.clone().or_else(|| {
+            options
+                .password_env
+                .as_deref()
+                .and_then(|name| std::env::var(name).ok())
+        })
+    });
+    if password.is_some()
+        && connection
+            .options
+            .as_ref()
+            .is_some_and(|options| options.username.is_none())
+    {
+        anyhow::bail!(
+            "connection '{}' has a password but no username",
+            connection.name
+        );
+    }
+    let redacted_uri = redact_connection_uri(&resolved.uri);
+    ensure_connection_security(config, &resolved).map_err(|error| anyhow::anyhow!("{error}"))?;
+    let mut options = ClientOptions::parse(&resolved.uri)
         .await
         .with_context(|| format!("unable to parse connection options for {redacted_uri}"))?;
+    if let Some(password) = password
+        && let Some(credential) = options.credential.as_mut()
+    {
+        credential.password = Some(password);
+    }
     options.connect_timeout = Some(config.connect_timeout());
     options.server_selection_timeout = Some(config.connect_timeout());
-    Client::with_options(options).with_context(|| format!("unable to connect to {redacted_uri}"))
+    Ok(options)
+}
+
+fn topology_label(hello: &Document) -> &'static str {
+    if hello.get_str("msg").is_ok_and(|msg| msg == "isdbgrid") {
+        "sharded"
+    } else if hello.contains_key("setName") {
+        "replica set"
+    } else {
+        "standalone"
+    }
 }
 
 async fn collect_result_documents(
@@ -405,6 +645,10 @@ fn ensure_write_allowed(guard: WriteGuard, action: &str) -> Result<()> {
     guard.ensure_write_allowed(action).map_err(Into::into)
 }
 
+fn ensure_connection_write_allowed(connection: &ConnectionSpec, action: &str) -> Result<()> {
+    connection.ensure_write_allowed(action).map_err(Into::into)
+}
+
 fn ensure_pipeline_allowed(guard: WriteGuard, pipeline: &[Document]) -> Result<()> {
     if let Some(stage) = find_pipeline_write_stage(pipeline) {
         guard.ensure_pipeline_allowed(stage)?;
@@ -488,6 +732,67 @@ fn parse_json_pipeline(value: &str) -> Result<Vec<Document>> {
     }
 }
 
+/// Aggregation stage names known to lint and to stage completion in the TUI.
+pub const PIPELINE_STAGES: &[&str] = &[
+    "$addFields",
+    "$bucket",
+    "$bucketAuto",
+    "$changeStream",
+    "$changeStreamSplitLargeEvent",
+    "$collStats",
+    "$count",
+    "$currentOp",
+    "$densify",
+    "$documents",
+    "$facet",
+    "$fill",
+    "$geoNear",
+    "$graphLookup",
+    "$group",
+    "$indexStats",
+    "$limit",
+    "$listLocalSessions",
+    "$listSampledQueries",
+    "$listSearchIndexes",
+    "$listSessions",
+    "$lookup",
+    "$match",
+    "$merge",
+    "$out",
+    "$planCacheStats",
+    "$project",
+    "$querySettings",
+    "$redact",
+    "$replaceRoot",
+    "$replaceWith",
+    "$sample",
+    "$search",
+    "$searchMeta",
+    "$set",
+    "$setWindowFields",
+    "$shardedDataDistribution",
+    "$skip",
+    "$sort",
+    "$sortByCount",
+    "$unionWith",
+    "$unset",
+    "$unwind",
+    "$vectorSearch",
+];
+
+pub fn is_known_pipeline_stage(name: &str) -> bool {
+    PIPELINE_STAGES.contains(&name)
+}
+
+pub fn is_pipeline_write_stage(name: &str) -> bool {
+    matches!(name, "$out" | "$merge")
+}
+
+/// Returns the first `$out`/`$merge` stage in a JSON pipeline, if any.
+pub fn pipeline_write_stage(pipeline: &str) -> Result<Option<&'static str>> {
+    Ok(find_pipeline_write_stage(&parse_json_pipeline(pipeline)?))
+}
+
 fn find_pipeline_write_stage(pipeline: &[Document]) -> Option<&'static str> {
     for stage in pipeline {
         if stage.contains_key("$out") {
@@ -627,7 +932,7 @@ fn parse_quoted_literal(input: &str, start: usize) -> Result<(String, usize)> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use lazycompass_core::ConnectionSpec;
+    use lazycompass_core::{ConnectionProtection, ConnectionSpec};
 
     fn config_with_connections(connections: Vec<ConnectionSpec>) -> Config {
         Config {
@@ -640,7 +945,10 @@ mod tests {
         ConnectionSpec {
             name: name.to_string(),
             uri: format!("mongodb://{name}:27017"),
+            uri_command: None,
             default_database: None,
+            protection: ConnectionProtection::Open,
+            options: None,
         }
     }
 
@@ -857,6 +1165,17 @@ mod tests {
         assert_eq!(find_pipeline_write_stage(&pipeline), None);
     }
 
+    #[test]
+    fn pipeline_stage_helpers_classify_stage_names() {
+        assert!(is_known_pipeline_stage("$match"));
+        assert!(is_known_pipeline_stage("$vectorSearch"));
+        assert!(!is_known_pipeline_stage("$mtach"));
+        assert!(!is_known_pipeline_stage("match"));
+        assert!(is_pipeline_write_stage("$out"));
+        assert!(is_pipeline_write_stage("$merge"));
+        assert!(!is_pipeline_write_stage("$lookup"));
+    }
+
     #[test]
     fn find_pipeline_write_stage_returns_first_matching_stage() {
         let pipeline = vec![
@@ -873,6 +1192,120 @@ mod tests {
         assert!(err.to_string().contains("--dangerously-enable-write"));
     }
 
+    #[test]
+    fn ensure_connection_write_allowed_blocks_read_only_connections() {
+        let mut connection = ConnectionSpec {
+            name: "prod".to_string(),
+            uri: "mongodb://localhost:27017".to_string(),
+            uri_command: None,
+            default_database: None,
+            protection: ConnectionProtection::ReadOnly,
+            options: None,
+        };
+        let err = ensure_connection_write_allowed(&connection, "insert documents")
+            .expect_err("expected protected connection error");
+        assert_eq!(
+            err.to_string(),
+            "connection 'prod' is read-only: insert documents is blocked"
+        );
+
+        connection.protection = ConnectionProtection::Confirm;
+        assert!(ensure_connection_write_allowed(&connection, "insert documents").is_ok());
+    }
+
+    #[test]
+    fn client_options_assembles_structured_fields_with_password() {
+        let connection = ConnectionSpec {
+            name: "cluster".to_string(),
+            uri: String::new(),
+            uri_command: None,
+            default_database: None,
+            protection: ConnectionProtection::Open,
+            options: Some(lazycompass_core::ConnectionOptions {
+                hosts: vec!["db1:27017".to_string(), "db2:27018".to_string()],
+                username: Some("app".to_string()),
+                password_env: Some("APP_PASSWORD".to_string()),
+                password: Some("p@ss".to_string()),
+                auth_source: Some("admin".to_string()),
+                tls: Some(true),
+                replica_set: Some("rs0".to_string()),
+                app_name: Some("lazycompass".to_string()),
+                ..Default::default()
+            }),
+        };
+        let config = config_with_connections(vec![connection.clone()]);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+
+        let options = runtime
+            .block_on(client_options(&config, &connection))
+            .expect("client options");
+        assert_eq!(options.hosts.len(), 2);
+        assert_eq!(options.repl_set_name.as_deref(), Some("rs0"));
+        assert_eq!(options.app_name.as_deref(), Some("lazycompass"));
+        let credential = options.credential.expect("credential");
+        assert_eq!(credential.username.as_deref(), Some("app"));
+        assert_eq!(credential.password.as_deref(), Some("p@ss"));
+        assert_eq!(credential.source.as_deref(), Some("admin"));
+    }
+
+    #[test]
+    fn client_options_rejects_password_without_username() {
+        let connection = ConnectionSpec {
+            name: "cluster".to_string(),
+            uri: String::new(),
+            uri_command: None,
+            default_database: None,
+            protection: ConnectionProtection::Open,
+            options: Some(lazycompass_core::ConnectionOptions {
+                hosts: vec!["db1:27017".to_string()],
+                password: Some("p@ss".to_string()),
+                ..Default::default()
+            }),
+        };
+        let config = config_with_connections(vec![connection.clone()]);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+
+        let error = runtime
+            .block_on(client_options(&config, &connection))
+            .expect_err("password without username");
+        assert_eq!(
+            error.to_string(),
+            "connection 'cluster' has a password but no username"
+        );
+    }
+
+    #[test]
+    fn topology_label_reads_hello_response() {
+        assert_eq!(
+            topology_label(&bson::doc! { "isWritablePrimary": true }),
+            "standalone"
+        );
+        assert_eq!(
+            topology_label(&bson::doc! { "setName": "rs0", "isWritablePrimary": true }),
+            "replica set"
+        );
+        assert_eq!(topology_label(&bson::doc! { "msg": "isdbgrid" }), "sharded");
+    }
+
+    #[test]
+    fn pipeline_write_stage_parses_json_pipelines() {
+        assert_eq!(
+            pipeline_write_stage(r#"[{"$match":{}},{"$merge":{"into":"x"}}]"#)
+                .expect("parse pipeline"),
+            Some("$merge")
+        );
+        assert_eq!(
+            pipeline_write_stage(r#"[{"$match":{}}]"#).expect("parse pipeline"),
+            None
+        );
+    }
+
     #[test]
     fn ensure_pipeline_allowed_blocks_out_without_flag() {
         let pipeline = vec![bson::doc! { "$out": "archive" }];
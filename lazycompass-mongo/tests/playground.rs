@@ -1,6 +1,7 @@
 use anyhow::Result;
 use lazycompass_core::{
-    Config, ConnectionSpec, LoggingConfig, ThemeConfig, TimeoutConfig, WriteGuard,
+    Config, ConnectionProtection, ConnectionSpec, HistoryConfig, LoggingConfig, ThemeConfig,
+    TimeoutConfig, WriteGuard,
 };
 use lazycompass_mongo::{
     AggregationSpec, Bson, DocumentDeleteSpec, DocumentInsertSpec, DocumentReplaceSpec,
@@ -38,7 +39,10 @@ async fn playground_query_and_aggregation() -> Result<()> {
         connections: vec![ConnectionSpec {
             name: "playground".to_string(),
             uri: playground_uri(),
+            uri_command: None,
             default_database: Some("lazycompass".to_string()),
+            protection: ConnectionProtection::Open,
+            options: None,
         }],
         theme: ThemeConfig::default(),
         logging: LoggingConfig::default(),
@@ -47,6 +51,12 @@ async fn playground_query_and_aggregation() -> Result<()> {
             connect_ms: Some(5_000),
             query_ms: Some(5_000),
         },
+        history: HistoryConfig::default(),
+        session: Default::default(),
+        keys: Default::default(),
+        profiles: Default::default(),
+        references: Vec::new(),
+        active_profile: None,
     };
 
     let executor = MongoExecutor::new();
@@ -1,29 +1,59 @@
 use anyhow::{Context, Result};
-use lazycompass_core::{Config, LoggingConfig, TimeoutConfig};
-use std::collections::{HashMap, HashSet};
+use lazycompass_core::{
+    Config, ConnectionSpec, HistoryConfig, LoggingConfig, ProfileConfig, SessionConfig,
+    TimeoutConfig,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Component, Path, PathBuf};
 use toml::Value;
 
 use crate::{ConfigPaths, ensure_not_symlinked_path};
 
+pub const PROFILE_ENV_VAR: &str = "LAZYCOMPASS_PROFILE";
+
 pub fn load_config(paths: &ConfigPaths) -> Result<Config> {
+    load_config_with_profile(paths, None)
+}
+
+/// Loads global and repo config, then layers the named profile on top of both.
+pub fn load_config_with_profile(paths: &ConfigPaths, profile: Option<&str>) -> Result<Config> {
     ensure_not_symlinked_path(&paths.global_root)?;
     let global_path = paths.global_config_path();
-    let global_env = load_dotenv_values_for_config(&global_path)?;
-    let global = read_config(&global_path, &global_env)?;
-    let repo = match paths.repo_config_path() {
+    let global = read_config(&global_path)?;
+    let repo_path = paths.repo_config_path();
+    let repo = match repo_path.as_deref() {
         Some(path) => {
             if let Some(repo_root) = paths.repo_config_root() {
                 ensure_not_symlinked_path(&repo_root)?;
             }
-            let repo_env = load_dotenv_values_for_config(&path)?;
-            read_config(&path, &repo_env)?
+            read_config(path)?
         }
         None => Config::default(),
     };
 
-    Ok(merge_config(global, repo))
+    let mut sources = vec![(global_path.as_path(), &global)];
+    if let Some(path) = repo_path.as_deref() {
+        sources.push((path, &repo));
+    }
+    let profile_env = match profile {
+        Some(name) => load_profile_env(name, &sources)?,
+        None => HashMap::new(),
+    };
+
+    let global = finish_config(&global_path, global, profile, &profile_env)?;
+    let repo = match repo_path.as_deref() {
+        Some(path) => finish_config(path, repo, profile, &profile_env)?,
+        None => repo,
+    };
+    merge_config(global, repo, profile)
+}
+
+/// Picks the profile from `--profile`, falling back to `LAZYCOMPASS_PROFILE`.
+pub fn resolve_profile_name(flag: Option<String>) -> Option<String> {
+    flag.or_else(|| std::env::var(PROFILE_ENV_VAR).ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
 }
 
 pub fn log_file_path(paths: &ConfigPaths, config: &Config) -> PathBuf {
@@ -34,7 +64,7 @@ pub fn log_file_path(paths: &ConfigPaths, config: &Config) -> PathBuf {
     }
 }
 
-fn read_config(path: &Path, dotenv: &HashMap<String, String>) -> Result<Config> {
+fn read_config(path: &Path) -> Result<Config> {
     match fs::symlink_metadata(path) {
         Ok(metadata) if metadata.file_type().is_symlink() => {
             anyhow::bail!("refusing to load symlinked config file {}", path.display());
@@ -57,30 +87,79 @@ fn read_config(path: &Path, dotenv: &HashMap<String, String>) -> Result<Config>
     let value: Value = toml::from_str(&contents)
         .with_context(|| format!("invalid TOML in config file {}", path.display()))?;
     reject_removed_keys(&value)?;
-    let mut config: Config = value
+    value
         .try_into()
-        .with_context(|| format!("invalid TOML in config file {}", path.display()))?;
-    resolve_env_vars(&mut config, path, dotenv)?;
+        .with_context(|| format!("invalid TOML in config file {}", path.display()))
+}
+
+fn finish_config(
+    path: &Path,
+    mut config: Config,
+    profile: Option<&str>,
+    profile_env: &HashMap<String, String>,
+) -> Result<Config> {
+    let mut dotenv = load_dotenv_values_for_config(path)?;
+    dotenv.extend(
+        profile_env
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone())),
+    );
+    resolve_env_vars(&mut config, path, profile, &dotenv)?;
     validate_config(&config)
         .with_context(|| format!("invalid config data in {}", path.display()))?;
     Ok(config)
 }
 
-fn load_dotenv_values_for_config(path: &Path) -> Result<HashMap<String, String>> {
+/// Reads the active profile's `env_file` from each config that defines it. Values apply to
+/// every config file and override `.env`; a repo profile's file wins over the global one.
+fn load_profile_env(name: &str, sources: &[(&Path, &Config)]) -> Result<HashMap<String, String>> {
+    let mut values = HashMap::new();
+    for (path, config) in sources {
+        let Some(env_file) = config
+            .profiles
+            .get(name)
+            .and_then(|profile| profile.env_file.as_deref())
+        else {
+            continue;
+        };
+        let file_values = load_profile_env_file(path, env_file)
+            .with_context(|| format!("unable to load profile '{name}'"))?;
+        values.extend(file_values);
+    }
+    Ok(values)
+}
+
+/// Resolves `env_file` next to the config's default `.env` and requires it to exist.
+pub(crate) fn load_profile_env_file(
+    config_path: &Path,
+    env_file: &str,
+) -> Result<HashMap<String, String>> {
+    let base = dotenv_path_for_config(config_path)
+        .and_then(|dotenv| dotenv.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+    let env_path = base.join(env_file);
+    read_dotenv_file(&env_path)?
+        .ok_or_else(|| anyhow::anyhow!("env_file {} does not exist", env_path.display()))
+}
+
+pub(crate) fn load_dotenv_values_for_config(path: &Path) -> Result<HashMap<String, String>> {
     let Some(dotenv_path) = dotenv_path_for_config(path) else {
         return Ok(HashMap::new());
     };
+    Ok(read_dotenv_file(&dotenv_path)?.unwrap_or_default())
+}
 
-    match fs::symlink_metadata(&dotenv_path) {
+fn read_dotenv_file(dotenv_path: &Path) -> Result<Option<HashMap<String, String>>> {
+    match fs::symlink_metadata(dotenv_path) {
         Ok(metadata) if metadata.file_type().is_symlink() => {
             anyhow::bail!(
                 "refusing to load symlinked .env file {}",
                 dotenv_path.display()
             );
         }
-        Ok(metadata) if !metadata.is_file() => return Ok(HashMap::new()),
+        Ok(metadata) if !metadata.is_file() => return Ok(None),
         Ok(_) => {}
-        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
         Err(error) => {
             return Err(error)
                 .with_context(|| format!("unable to inspect .env file {}", dotenv_path.display()));
@@ -88,17 +167,19 @@ fn load_dotenv_values_for_config(path: &Path) -> Result<HashMap<String, String>>
     }
 
     let mut values = HashMap::new();
-    let iter = dotenvy::from_path_iter(&dotenv_path)
+    let iter = dotenvy::from_path_iter(dotenv_path)
         .with_context(|| format!("unable to read .env file {}", dotenv_path.display()))?;
     for item in iter {
         let (key, value) =
             item.with_context(|| format!("invalid .env entry in {}", dotenv_path.display()))?;
         values.entry(key).or_insert(value);
     }
-    Ok(values)
+    Ok(Some(values))
 }
 
-fn dotenv_path_for_config(path: &Path) -> Option<PathBuf> {
+/// The `.env` file read alongside a config file: the repo root for repo config, otherwise the
+/// config directory.
+pub fn dotenv_path_for_config(path: &Path) -> Option<PathBuf> {
     let parent = path.parent()?;
     if parent.file_name().and_then(|name| name.to_str()) == Some(".lazycompass") {
         return parent.parent().map(|root| root.join(".env"));
@@ -106,7 +187,7 @@ fn dotenv_path_for_config(path: &Path) -> Option<PathBuf> {
     Some(parent.join(".env"))
 }
 
-fn reject_removed_keys(value: &Value) -> Result<()> {
+pub(crate) fn reject_removed_keys(value: &Value) -> Result<()> {
     let Some(table) = value.as_table() else {
         return Ok(());
     };
@@ -125,25 +206,32 @@ fn reject_removed_keys(value: &Value) -> Result<()> {
     Ok(())
 }
 
+/// Only the active profile is resolved, so inactive profiles may reference variables that
+/// exist only in their own `env_file`.
 fn resolve_env_vars(
     config: &mut Config,
     path: &Path,
+    profile: Option<&str>,
     dotenv: &HashMap<String, String>,
 ) -> Result<()> {
     for (index, connection) in config.connections.iter_mut().enumerate() {
-        if connection.uri.contains("${") {
+        let label = if connection.name.trim().is_empty() {
+            format!("connection at index {index}")
+        } else {
+            format!("connection '{}'", connection.name)
+        };
+        resolve_connection_env_vars(connection, &label, path, dotenv)?;
+    }
+    if let Some(name) = profile
+        && let Some(profile) = config.profiles.get_mut(name)
+    {
+        for (index, connection) in profile.connections.iter_mut().enumerate() {
             let label = if connection.name.trim().is_empty() {
-                format!("connection at index {index}")
+                format!("profile '{name}' connection at index {index}")
             } else {
-                format!("connection '{}'", connection.name)
+                format!("profile '{name}' connection '{}'", connection.name)
             };
-            let resolved = interpolate_env_value(&connection.uri, dotenv).map_err(|error| {
-                anyhow::anyhow!(
-                    "config {}: unable to resolve env vars in {label} uri: {error}",
-                    path.display()
-                )
-            })?;
-            connection.uri = resolved;
+            resolve_connection_env_vars(connection, &label, path, dotenv)?;
         }
     }
 
@@ -162,7 +250,49 @@ fn resolve_env_vars(
     Ok(())
 }
 
-fn interpolate_env_value(value: &str, dotenv: &HashMap<String, String>) -> Result<String> {
+fn resolve_connection_env_vars(
+    connection: &mut ConnectionSpec,
+    label: &str,
+    path: &Path,
+    dotenv: &HashMap<String, String>,
+) -> Result<()> {
+    for arg in connection.uri_command.iter_mut().flatten() {
+        if arg.contains("${") {
+            *arg = interpolate_env_value(arg, dotenv).map_err(|error| {
+                anyhow::anyhow!(
+                    "config {}: unable to resolve env vars in {label} uri_command: {error}",
+                    path.display()
+                )
+            })?;
+        }
+    }
+    if let Some(options) = connection.options.as_mut()
+        && let Some(name) = options.password_env.as_deref()
+    {
+        let password = resolve_env_value(name, dotenv).ok_or_else(|| {
+            anyhow::anyhow!(
+                "config {}: missing environment variable '{name}' for {label} options.password_env",
+                path.display()
+            )
+        })?;
+        options.password = Some(password);
+    }
+    if connection.uri.contains("${") {
+        let resolved = interpolate_env_value(&connection.uri, dotenv).map_err(|error| {
+            anyhow::anyhow!(
+                "config {}: unable to resolve env vars in {label} uri: {error}",
+                path.display()
+            )
+        })?;
+        connection.uri = resolved;
+    }
+    Ok(())
+}
+
+pub(crate) fn interpolate_env_value(
+    value: &str,
+    dotenv: &HashMap<String, String>,
+) -> Result<String> {
     let mut output = String::with_capacity(value.len());
     let mut remainder = value;
 
@@ -193,18 +323,38 @@ fn resolve_env_value(name: &str, dotenv: &HashMap<String, String>) -> Option<Str
 }
 
 fn validate_config(config: &Config) -> Result<()> {
+    validate_connections(&config.connections)?;
+    for (name, profile) in &config.profiles {
+        validate_profile(profile).with_context(|| format!("invalid profile '{name}'"))?;
+    }
+    validate_settings(config)
+}
+
+fn validate_profile(profile: &ProfileConfig) -> Result<()> {
+    validate_connections(&profile.connections)?;
+    validate_settings(&Config {
+        timeouts: profile.timeouts.clone(),
+        ..Config::default()
+    })
+}
+
+fn validate_connections(connections: &[ConnectionSpec]) -> Result<()> {
     let mut seen = HashSet::new();
-    for (index, connection) in config.connections.iter().enumerate() {
+    for (index, connection) in connections.iter().enumerate() {
         if connection.name.trim().is_empty() {
             anyhow::bail!("connection at index {} has empty name", index);
         }
-        if connection.uri.trim().is_empty() {
-            anyhow::bail!("connection '{}' has empty uri", connection.name);
+        if let Some(problem) = connection.uri_source_problem() {
+            anyhow::bail!("connection '{}' has {problem}", connection.name);
         }
         if !seen.insert(connection.name.clone()) {
             anyhow::bail!("duplicate connection name '{}'", connection.name);
         }
     }
+    Ok(())
+}
+
+pub(crate) fn validate_settings(config: &Config) -> Result<()> {
     if let Some(timeout) = config.timeouts.connect_ms
         && timeout == 0
     {
@@ -228,10 +378,15 @@ fn validate_config(config: &Config) -> Result<()> {
     if let Some(file) = config.logging.file.as_deref() {
         validate_logging_file(file)?;
     }
+    if let Some(max_entries) = config.history.max_entries
+        && max_entries == 0
+    {
+        anyhow::bail!("history max_entries must be greater than 0");
+    }
     Ok(())
 }
 
-fn validate_logging_file(path: &str) -> Result<()> {
+pub(crate) fn validate_logging_file(path: &str) -> Result<()> {
     let trimmed = path.trim();
     if trimmed.is_empty() {
         anyhow::bail!("logging.file cannot be empty");
@@ -253,24 +408,11 @@ fn validate_logging_file(path: &str) -> Result<()> {
     Ok(())
 }
 
-fn merge_config(global: Config, repo: Config) -> Config {
+fn merge_config(global: Config, repo: Config, profile: Option<&str>) -> Result<Config> {
     let mut connections = global.connections;
-    for repo_connection in repo.connections {
-        if let Some(existing) = connections
-            .iter_mut()
-            .find(|connection| connection.name == repo_connection.name)
-        {
-            *existing = repo_connection;
-        } else {
-            connections.push(repo_connection);
-        }
-    }
+    override_connections(&mut connections, repo.connections);
 
-    let theme = if repo.theme.name.is_some() {
-        repo.theme
-    } else {
-        global.theme
-    };
+    let theme = repo.theme.overlay(global.theme);
     let logging = LoggingConfig {
         level: repo.logging.level.or(global.logging.level),
         file: repo.logging.file.or(global.logging.file),
@@ -278,30 +420,109 @@ fn merge_config(global: Config, repo: Config) -> Config {
         max_backups: repo.logging.max_backups.or(global.logging.max_backups),
     };
     let allow_insecure = repo.allow_insecure.or(global.allow_insecure);
-    let timeouts = TimeoutConfig {
-        connect_ms: repo.timeouts.connect_ms.or(global.timeouts.connect_ms),
-        query_ms: repo.timeouts.query_ms.or(global.timeouts.query_ms),
+    let timeouts = merge_timeouts(repo.timeouts, global.timeouts);
+    let history = HistoryConfig {
+        enabled: repo.history.enabled.or(global.history.enabled),
+        max_entries: repo.history.max_entries.or(global.history.max_entries),
     };
+    let session = SessionConfig {
+        restore: repo.session.restore.or(global.session.restore),
+    };
+    let mut keys = global.keys;
+    keys.extend(repo.keys);
+    // Repo rules come first so they win over global rules for the same field.
+    let mut references = repo.references;
+    references.extend(global.references);
+    let mut profiles = global.profiles;
+    for (name, repo_profile) in repo.profiles {
+        let merged = match profiles.remove(&name) {
+            Some(global_profile) => merge_profile(global_profile, repo_profile),
+            None => repo_profile,
+        };
+        profiles.insert(name, merged);
+    }
 
-    Config {
+    let mut config = Config {
         connections,
         theme,
         logging,
         allow_insecure,
         timeouts,
+        history,
+        session,
+        keys,
+        profiles,
+        references,
+        active_profile: None,
+    };
+    if let Some(name) = profile {
+        apply_profile(&mut config, name)?;
+    }
+    Ok(config)
+}
+
+fn merge_profile(global: ProfileConfig, repo: ProfileConfig) -> ProfileConfig {
+    let mut connections = global.connections;
+    override_connections(&mut connections, repo.connections);
+    ProfileConfig {
+        connections,
+        theme: repo.theme.overlay(global.theme),
+        timeouts: merge_timeouts(repo.timeouts, global.timeouts),
+        env_file: repo.env_file.or(global.env_file),
+    }
+}
+
+fn apply_profile(config: &mut Config, name: &str) -> Result<()> {
+    let Some(profile) = config.profiles.get(name).cloned() else {
+        let available = profile_names(&config.profiles);
+        anyhow::bail!("profile '{name}' is not defined; available profiles: {available}");
+    };
+    override_connections(&mut config.connections, profile.connections);
+    config.theme = profile.theme.overlay(std::mem::take(&mut config.theme));
+    config.timeouts = merge_timeouts(profile.timeouts, config.timeouts.clone());
+    config.active_profile = Some(name.to_string());
+    Ok(())
+}
+
+fn profile_names(profiles: &BTreeMap<String, ProfileConfig>) -> String {
+    if profiles.is_empty() {
+        return "none".to_string();
+    }
+    profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+}
+
+fn override_connections(connections: &mut Vec<ConnectionSpec>, overrides: Vec<ConnectionSpec>) {
+    for override_connection in overrides {
+        if let Some(existing) = connections
+            .iter_mut()
+            .find(|connection| connection.name == override_connection.name)
+        {
+            *existing = override_connection;
+        } else {
+            connections.push(override_connection);
+        }
+    }
+}
+
+fn merge_timeouts(preferred: TimeoutConfig, fallback: TimeoutConfig) -> TimeoutConfig {
+    TimeoutConfig {
+        connect_ms: preferred.connect_ms.or(fallback.connect_ms),
+        query_ms: preferred.query_ms.or(fallback.query_ms),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
-    use lazycompass_core::{Config, LoggingConfig, ThemeConfig, TimeoutConfig};
+    use lazycompass_core::{
+        Config, HistoryConfig, KeyChords, LoggingConfig, ThemeConfig, TimeoutConfig,
+    };
     use std::collections::HashMap;
     use std::fs;
     #[cfg(unix)]
     use std::os::unix::fs::symlink;
 
-    use super::{load_config, log_file_path};
+    use super::{load_config, load_config_with_profile, log_file_path};
     use crate::{
         ConfigPaths,
         test_support::{temp_root, unique_env_suffix, write_file},
@@ -315,7 +536,9 @@ mod tests {
 
         write_file(
             &global_root.join("config.toml"),
-            r#"[timeouts]
+            r#"references = ["orders.userId -> users._id", "orders.itemId -> items._id"]
+
+[timeouts]
 connect_ms = 5000
 query_ms = 25000
 
@@ -334,11 +557,20 @@ name = "classic"
 [logging]
 level = "info"
 file = "global.log"
+
+[keys]
+quit = "ctrl+q"
+go_top = "gg"
 "#,
         );
         write_file(
             &repo_root.join(".lazycompass/config.toml"),
-            r#"[timeouts]
+            r#"references = [
+  "orders.userId -> accounts._id",
+  { collection = "audit.events", field = "actorId", target_collection = "accounts", target_field = "_id" },
+]
+
+[timeouts]
 connect_ms = 8000
 query_ms = 40000
 
@@ -357,6 +589,9 @@ name = "ember"
 [logging]
 level = "debug"
 file = "repo.log"
+
+[keys]
+go_top = ["home", "g g"]
 "#,
         );
 
@@ -388,6 +623,25 @@ file = "repo.log"
         assert_eq!(config.logging.file.as_deref(), Some("repo.log"));
         assert_eq!(config.timeouts.connect_ms, Some(8000));
         assert_eq!(config.timeouts.query_ms, Some(40000));
+        assert_eq!(
+            config.keys.get("quit"),
+            Some(&KeyChords::One("ctrl+q".to_string()))
+        );
+        assert_eq!(
+            config.keys.get("go_top").map(KeyChords::chords),
+            Some(&["home".to_string(), "g g".to_string()][..])
+        );
+        assert_eq!(config.references[1].collection, "audit.events");
+        let references: Vec<String> = config.references.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            references,
+            vec![
+                "orders.userId -> accounts._id",
+                "audit.events.actorId -> accounts._id",
+                "orders.userId -> users._id",
+                "orders.itemId -> items._id",
+            ]
+        );
 
         let _ = fs::remove_dir_all(&root);
         Ok(())
@@ -522,6 +776,12 @@ uri = "${{{missing_var}}}"
             },
             allow_insecure: None,
             timeouts: TimeoutConfig::default(),
+            history: HistoryConfig::default(),
+            session: Default::default(),
+            keys: Default::default(),
+            profiles: Default::default(),
+            references: Vec::new(),
+            active_profile: None,
         };
 
         let resolved = log_file_path(&paths, &config);
@@ -607,6 +867,182 @@ uri = " "
         Ok(())
     }
 
+    #[test]
+    fn load_config_resolves_uri_command_args_and_rejects_both_sources() -> Result<()> {
+        let root = temp_root("config_uri_command");
+        let repo_root = root.join("repo");
+        let suffix = unique_env_suffix();
+        let var_name = format!("LAZYCOMPASS_URI_COMMAND_{suffix}");
+
+        write_file(&repo_root.join(".env"), &format!("{var_name}=mongo/prod\n"));
+        write_file(
+            &repo_root.join(".lazycompass/config.toml"),
+            &format!(
+                r#"[[connections]]
+name = "vault"
+uri_command = ["pass", "show", "${{{var_name}}}"]
+"#
+            ),
+        );
+        let paths = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: Some(repo_root.clone()),
+        };
+        let config = load_config(&paths)?;
+        assert!(config.connections[0].uri.is_empty());
+        assert_eq!(
+            config.connections[0].uri_command,
+            Some(vec![
+                "pass".to_string(),
+                "show".to_string(),
+                "mongo/prod".to_string()
+            ])
+        );
+
+        write_file(
+            &repo_root.join(".lazycompass/config.toml"),
+            r#"[[connections]]
+name = "vault"
+uri = "mongodb://localhost:27017"
+uri_command = ["pass", "show", "mongo/prod"]
+"#,
+        );
+        let err = load_config(&paths).expect_err("expected conflicting uri sources");
+        assert!(format!("{err:#}").contains("both uri and uri_command"));
+
+        unsafe {
+            std::env::remove_var(&var_name);
+        }
+        let _ = fs::remove_dir_all(&root);
+        Ok(())
+    }
+
+    #[test]
+    fn load_config_resolves_structured_password_env() -> Result<()> {
+        let root = temp_root("config_structured_options");
+        let global_root = root.join("global");
+        let suffix = unique_env_suffix();
+        let var_name = format!("LAZYCOMPASS_STRUCTURED_PASSWORD_{suffix}");
+
+        write_file(&global_root.join(".env"), &format!("{var_name}=s3cret\n"));
+        write_file(
+            &global_root.join("config.toml"),
+            &format!(
+                r#"[[connections]]
+name = "cluster"
+
+[connections.options]
+hosts = ["db1:27017", "db2:27017"]
+username = "app"
+password_env = "{var_name}"
+tls = true
+"#
+            ),
+        );
+        let paths = ConfigPaths {
+            global_root: global_root.clone(),
+            repo_root: None,
+        };
+        let config = load_config(&paths)?;
+        let options = config.connections[0].options.as_ref().expect("options");
+        assert_eq!(options.password.as_deref(), Some("s3cret"));
+        assert_eq!(options.hosts.len(), 2);
+
+        fs::remove_file(global_root.join(".env"))?;
+        let err = load_config(&paths).expect_err("expected missing password env");
+        assert!(format!("{err:#}").contains("options.password_env"));
+
+        let _ = fs::remove_dir_all(&root);
+        Ok(())
+    }
+
+    #[test]
+    fn load_config_with_profile_layers_profile_over_repo_and_global() -> Result<()> {
+        let root = temp_root("config_profiles");
+        let global_root = root.join("global");
+        let repo_root = root.join("repo");
+        let suffix = unique_env_suffix();
+        let var_name = format!("LAZYCOMPASS_PROFILE_URI_{suffix}");
+
+        write_file(
+            &global_root.join("config.toml"),
+            &format!(
+                r#"[[connections]]
+name = "app"
+uri = "${{{var_name}}}"
+
+[timeouts]
+connect_ms = 1000
+query_ms = 2000
+
+[profiles.staging]
+env_file = ".env.staging"
+
+[profiles.staging.timeouts]
+connect_ms = 5000
+
+[profiles.prod]
+env_file = ".env.prod"
+"#
+            ),
+        );
+        write_file(
+            &global_root.join(".env"),
+            &format!("{var_name}=mongodb://dev\n"),
+        );
+        write_file(
+            &global_root.join(".env.staging"),
+            &format!("{var_name}=mongodb://staging\n"),
+        );
+        write_file(
+            &repo_root.join(".lazycompass/config.toml"),
+            r#"[profiles.staging]
+[profiles.staging.theme]
+name = "ember"
+
+[[profiles.staging.connections]]
+name = "analytics"
+uri = "mongodb://analytics-staging"
+"#,
+        );
+        let paths = ConfigPaths {
+            global_root,
+            repo_root: Some(repo_root),
+        };
+
+        let base = load_config(&paths)?;
+        assert_eq!(base.active_profile, None);
+        assert_eq!(base.connections.len(), 1);
+        assert_eq!(base.connections[0].uri, "mongodb://dev");
+        assert_eq!(base.profiles.len(), 2);
+
+        let staging = load_config_with_profile(&paths, Some("staging"))?;
+        assert_eq!(staging.active_profile.as_deref(), Some("staging"));
+        let uris: Vec<&str> = staging
+            .connections
+            .iter()
+            .map(|connection| connection.uri.as_str())
+            .collect();
+        assert_eq!(
+            uris,
+            vec!["mongodb://staging", "mongodb://analytics-staging"]
+        );
+        assert_eq!(staging.theme.name.as_deref(), Some("ember"));
+        assert_eq!(staging.timeouts.connect_ms, Some(5000));
+        assert_eq!(staging.timeouts.query_ms, Some(2000));
+
+        let err = load_config_with_profile(&paths, Some("prod")).expect_err("missing env file");
+        assert!(format!("{err:#}").contains(".env.prod does not exist"));
+        let err = load_config_with_profile(&paths, Some("qa")).expect_err("unknown profile");
+        assert!(
+            err.to_string()
+                .contains("profile 'qa' is not defined; available profiles: prod, staging")
+        );
+
+        let _ = fs::remove_dir_all(&root);
+        Ok(())
+    }
+
     #[test]
     fn load_config_rejects_zero_numeric_settings() -> Result<()> {
         let root = temp_root("config_zero_numbers");
@@ -111,20 +111,28 @@ fn read_config_for_update(path: &Path) -> Result<ConfigUpdate> {
     })
 }
 
+/// Serializes the connection as a `[[connections]]` entry. Going through a wrapper keeps
+/// nested tables such as `[connections.options]` attached to the entry.
 fn render_connection_block(connection: &lazycompass_core::ConnectionSpec) -> Result<String> {
-    let mut block = String::from("[[connections]]\n");
-    block.push_str(&toml::to_string_pretty(connection).context("unable to serialize connection")?);
-    Ok(block)
+    #[derive(serde::Serialize)]
+    struct Block<'a> {
+        connections: [&'a lazycompass_core::ConnectionSpec; 1],
+    }
+
+    toml::to_string_pretty(&Block {
+        connections: [connection],
+    })
+    .context("unable to serialize connection")
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
         append_connection_to_global_config, append_connection_to_repo_config,
-        read_config_for_update,
+        read_config_for_update, render_connection_block,
     };
-    use crate::{ConfigPaths, test_support::canonical_temp_dir};
-    use lazycompass_core::ConnectionSpec;
+    use crate::{ConfigPaths, load_config, test_support::canonical_temp_dir};
+    use lazycompass_core::{ConnectionOptions, ConnectionProtection, ConnectionSpec};
     use std::fs;
     use std::future::Future;
     use std::path::PathBuf;
@@ -145,7 +153,10 @@ mod tests {
         ConnectionSpec {
             name: name.to_string(),
             uri: "mongodb://localhost:27017".to_string(),
+            uri_command: None,
             default_database: Some("lazycompass".to_string()),
+            protection: ConnectionProtection::Open,
+            options: None,
         }
     }
 
@@ -192,6 +203,55 @@ mod tests {
         let _ = fs::remove_dir_all(root);
     }
 
+    #[test]
+    fn render_connection_block_only_writes_non_default_protection() {
+        let block = render_connection_block(&sample_connection("local")).expect("render");
+        assert!(!block.contains("protection"));
+
+        let mut connection = sample_connection("prod");
+        connection.protection = ConnectionProtection::ReadOnly;
+        let block = render_connection_block(&connection).expect("render");
+        assert!(block.contains("protection = \"read_only\""));
+        let parsed: ConnectionSpec =
+            toml::from_str(block.trim_start_matches("[[connections]]\n")).expect("parse");
+        assert_eq!(parsed.protection, ConnectionProtection::ReadOnly);
+    }
+
+    #[test]
+    fn append_connection_with_options_round_trips_through_load_config() {
+        let root = temp_dir("global_options");
+        let paths = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: None,
+        };
+        let mut connection = sample_connection("cluster");
+        connection.uri = String::new();
+        connection.protection = ConnectionProtection::Confirm;
+        connection.options = Some(ConnectionOptions {
+            hosts: vec!["db1:27017".to_string(), "db2:27017".to_string()],
+            username: Some("app".to_string()),
+            tls: Some(true),
+            replica_set: Some("rs0".to_string()),
+            ..Default::default()
+        });
+
+        block_on_ready(append_connection_to_global_config(
+            &paths,
+            &sample_connection("local"),
+        ))
+        .expect("append first");
+        let path = block_on_ready(append_connection_to_global_config(&paths, &connection))
+            .expect("append with options");
+        let contents = fs::read_to_string(&path).expect("read config");
+        assert!(contents.contains("[connections.options]"));
+
+        let config = load_config(&paths).expect("load config");
+        assert_eq!(config.connections.len(), 2);
+        assert_eq!(config.connections[1], connection);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
     #[test]
     fn append_connection_to_global_config_rejects_duplicates() {
         let root = temp_dir("global_duplicate");
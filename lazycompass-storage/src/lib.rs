@@ -1,23 +1,45 @@
 mod config;
 mod connections;
+mod history;
+mod lint;
 mod paths;
 mod saved_aggregations;
 mod saved_common;
 mod saved_queries;
 mod security;
+mod session;
 mod snapshot;
+mod themes;
+mod watch;
 
-pub use config::{load_config, log_file_path};
+pub use config::{
+    PROFILE_ENV_VAR, dotenv_path_for_config, load_config, load_config_with_profile, log_file_path,
+    resolve_profile_name,
+};
 pub use connections::{append_connection_to_global_config, append_connection_to_repo_config};
+pub use history::{append_history, clear_history, history_path, load_history};
+pub use lint::{lint_config, saved_aggregation_files, saved_query_files};
 pub use paths::ConfigPaths;
 pub use saved_aggregations::{
-    load_saved_aggregations, saved_aggregation_path, write_saved_aggregation,
+    copy_saved_aggregation, delete_saved_aggregation, load_saved_aggregation,
+    load_saved_aggregations, parse_saved_aggregation, rename_saved_aggregation,
+    render_saved_aggregation, saved_aggregation_path, write_saved_aggregation,
+};
+pub use saved_common::validate_saved_id;
+pub use saved_queries::{
+    copy_saved_query, delete_saved_query, load_saved_queries, load_saved_query, parse_saved_query,
+    rename_saved_query, render_saved_query, saved_query_path, write_saved_query,
 };
-pub use saved_queries::{load_saved_queries, saved_query_path, write_saved_query};
 pub use security::{
-    ensure_not_symlinked_file, ensure_not_symlinked_path, ensure_secure_dir, write_secure_file,
+    ensure_not_symlinked_file, ensure_not_symlinked_path, ensure_secure_dir, permission_warnings,
+    write_secure_file,
+};
+pub use session::{load_session, save_session, sessions_path};
+pub use snapshot::{
+    StorageSnapshot, load_storage, load_storage_with_config, load_storage_with_profile,
 };
-pub use snapshot::{StorageSnapshot, load_storage, load_storage_with_config};
+pub use themes::{load_theme_file, theme_file_paths};
+pub use watch::{StorageFingerprint, storage_fingerprint};
 
 #[cfg(test)]
 pub(crate) mod test_support;
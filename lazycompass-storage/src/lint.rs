@@ -0,0 +1,459 @@
+use lazycompass_core::{Config, ConnectionSpec, LintDiagnostic, line_at_offset, line_of};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::ConfigPaths;
+use crate::config::{
+    interpolate_env_value, load_dotenv_values_for_config, load_profile_env_file,
+    reject_removed_keys, validate_settings,
+};
+use crate::saved_common::collect_json_paths;
+
+/// Lints the global and repo config files independently so each problem points at its own file.
+pub fn lint_config(paths: &ConfigPaths) -> Vec<LintDiagnostic> {
+    let mut diagnostics = lint_config_file(&paths.global_config_path());
+    if let Some(path) = paths.repo_config_path() {
+        diagnostics.extend(lint_config_file(&path));
+    }
+    diagnostics
+}
+
+pub fn saved_query_files(paths: &ConfigPaths) -> anyhow::Result<Vec<PathBuf>> {
+    match paths.repo_queries_dir() {
+        Some(dir) => collect_json_paths(&dir),
+        None => Ok(Vec::new()),
+    }
+}
+
+pub fn saved_aggregation_files(paths: &ConfigPaths) -> anyhow::Result<Vec<PathBuf>> {
+    match paths.repo_aggregations_dir() {
+        Some(dir) => collect_json_paths(&dir),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn lint_config_file(path: &Path) -> Vec<LintDiagnostic> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            return vec![LintDiagnostic::error(
+                path,
+                1,
+                "refusing to load symlinked config file",
+            )];
+        }
+        Ok(metadata) if !metadata.is_file() => return Vec::new(),
+        Ok(_) => {}
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(error) => {
+            return vec![LintDiagnostic::error(
+                path,
+                1,
+                format!("unable to inspect config file: {error}"),
+            )];
+        }
+    }
+
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            return vec![LintDiagnostic::error(
+                path,
+                1,
+                format!("unable to read config file: {error}"),
+            )];
+        }
+    };
+    lint_config_contents(path, &contents)
+}
+
+fn lint_config_contents(path: &Path, contents: &str) -> Vec<LintDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let value: toml::Value = match toml::from_str(contents) {
+        Ok(value) => value,
+        Err(error) => {
+            let line = error
+                .span()
+                .map(|span| line_at_offset(contents, span.start))
+                .unwrap_or(1);
+            diagnostics.push(LintDiagnostic::error(
+                path,
+                line,
+                format!("invalid TOML: {}", error.message()),
+            ));
+            return diagnostics;
+        }
+    };
+    if let Err(error) = reject_removed_keys(&value) {
+        let message = error.to_string();
+        let line = ["read_only", "allow_pipeline_writes"]
+            .into_iter()
+            .find(|key| message.contains(key))
+            .map(|key| line_of(contents, key))
+            .unwrap_or(1);
+        diagnostics.push(LintDiagnostic::error(path, line, message));
+    }
+    let mut config: Config = match toml::from_str(contents) {
+        Ok(config) => config,
+        Err(error) => {
+            let line = error
+                .span()
+                .map(|span| line_at_offset(contents, span.start))
+                .unwrap_or(1);
+            diagnostics.push(LintDiagnostic::error(
+                path,
+                line,
+                format!("invalid config: {}", error.message()),
+            ));
+            return diagnostics;
+        }
+    };
+
+    let dotenv = match load_dotenv_values_for_config(path) {
+        Ok(values) => values,
+        Err(error) => {
+            diagnostics.push(LintDiagnostic::error(path, 1, format!("{error:#}")));
+            HashMap::new()
+        }
+    };
+
+    lint_connections(
+        path,
+        contents,
+        "[[connections]]",
+        "",
+        &config.connections,
+        &dotenv,
+        &mut diagnostics,
+    );
+    for (name, profile) in &config.profiles {
+        let mut profile_dotenv = dotenv.clone();
+        if let Some(env_file) = profile.env_file.as_deref() {
+            match load_profile_env_file(path, env_file) {
+                Ok(values) => profile_dotenv.extend(values),
+                Err(error) => diagnostics.push(LintDiagnostic::error(
+                    path,
+                    line_of(contents, "env_file"),
+                    format!("profile '{name}': {error:#}"),
+                )),
+            }
+        }
+        lint_connections(
+            path,
+            contents,
+            &format!("[[profiles.{name}.connections]]"),
+            &format!("profile '{name}' "),
+            &profile.connections,
+            &profile_dotenv,
+            &mut diagnostics,
+        );
+    }
+
+    if let Some(file) = config.logging.file.clone()
+        && file.contains("${")
+    {
+        match interpolate_env_value(&file, &dotenv) {
+            Ok(resolved) => config.logging.file = Some(resolved),
+            Err(error) => {
+                diagnostics.push(LintDiagnostic::error(
+                    path,
+                    line_of(contents, "file"),
+                    format!("unable to resolve env vars in logging.file: {error}"),
+                ));
+                config.logging.file = None;
+            }
+        }
+    }
+    if let Err(error) = validate_settings(&config) {
+        let message = error.to_string();
+        let section = if message.contains("timeout") {
+            "[timeouts]"
+        } else if message.contains("history") {
+            "[history]"
+        } else {
+            "[logging]"
+        };
+        diagnostics.push(LintDiagnostic::error(
+            path,
+            line_of(contents, section),
+            message,
+        ));
+    }
+
+    diagnostics
+}
+
+fn lint_connections(
+    path: &Path,
+    contents: &str,
+    header_text: &str,
+    label_prefix: &str,
+    connections: &[ConnectionSpec],
+    dotenv: &HashMap<String, String>,
+    diagnostics: &mut Vec<LintDiagnostic>,
+) {
+    let headers: Vec<usize> = contents
+        .match_indices(header_text)
+        .map(|(offset, _)| offset)
+        .collect();
+    let mut seen = HashSet::new();
+
+    for (index, connection) in connections.iter().enumerate() {
+        let header = headers.get(index).copied();
+        let line_of_key = |key: &str| match header {
+            Some(offset) => contents[offset..]
+                .find(key)
+                .map(|found| line_at_offset(contents, offset + found))
+                .unwrap_or_else(|| line_at_offset(contents, offset)),
+            None => line_of(contents, "connections"),
+        };
+        let label = if connection.name.trim().is_empty() {
+            format!("{label_prefix}connection at index {index}")
+        } else {
+            format!("{label_prefix}connection '{}'", connection.name)
+        };
+
+        if connection.name.trim().is_empty() {
+            diagnostics.push(LintDiagnostic::error(
+                path,
+                line_of_key("name"),
+                format!("{label} has empty name"),
+            ));
+        } else if !seen.insert(connection.name.as_str()) {
+            diagnostics.push(LintDiagnostic::error(
+                path,
+                line_of_key("name"),
+                format!(
+                    "{label_prefix}duplicate connection name '{}'",
+                    connection.name
+                ),
+            ));
+        }
+
+        if let Some(problem) = connection.uri_source_problem() {
+            let key = if connection.options.is_some() {
+                "options"
+            } else if connection.uri_command.is_some() {
+                "uri_command"
+            } else {
+                "uri"
+            };
+            diagnostics.push(LintDiagnostic::error(
+                path,
+                line_of_key(key),
+                format!("{label} has {problem}"),
+            ));
+            continue;
+        }
+        if let Some(command) = &connection.uri_command {
+            // The command itself only runs at connect time; lint just checks its placeholders.
+            for arg in command.iter().filter(|arg| arg.contains("${")) {
+                if let Err(error) = interpolate_env_value(arg, dotenv) {
+                    diagnostics.push(LintDiagnostic::error(
+                        path,
+                        line_of_key("uri_command"),
+                        format!("unable to resolve env vars in {label} uri_command: {error}"),
+                    ));
+                }
+            }
+            continue;
+        }
+        if let Some(name) = connection
+            .options
+            .as_ref()
+            .and_then(|options| options.password_env.as_deref())
+            && !dotenv.contains_key(name)
+            && std::env::var_os(name).is_none()
+        {
+            diagnostics.push(LintDiagnostic::error(
+                path,
+                line_of_key("password_env"),
+                format!("missing environment variable '{name}' for {label} options.password_env"),
+            ));
+        }
+        if connection.options.is_some() {
+            continue;
+        }
+        let uri = if connection.uri.contains("${") {
+            match interpolate_env_value(&connection.uri, dotenv) {
+                Ok(uri) => uri,
+                Err(error) => {
+                    diagnostics.push(LintDiagnostic::error(
+                        path,
+                        line_of_key("uri"),
+                        format!("unable to resolve env vars in {label} uri: {error}"),
+                    ));
+                    continue;
+                }
+            }
+        } else {
+            connection.uri.clone()
+        };
+        if !uri.starts_with("mongodb://") && !uri.starts_with("mongodb+srv://") {
+            diagnostics.push(LintDiagnostic::error(
+                path,
+                line_of_key("uri"),
+                format!("{label} uri must start with mongodb:// or mongodb+srv://"),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazycompass_core::LintSeverity;
+    use std::fs;
+    use std::path::Path;
+
+    use super::{lint_config, lint_config_contents};
+    use crate::{
+        ConfigPaths,
+        test_support::{temp_root, unique_env_suffix, write_file},
+    };
+
+    #[test]
+    fn lint_config_reports_toml_errors_with_lines() {
+        let diagnostics = lint_config_contents(
+            Path::new("config.toml"),
+            "[theme]\nname = \"classic\"\n[logging\n",
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 3);
+        assert!(diagnostics[0].message.starts_with("invalid TOML"));
+    }
+
+    #[test]
+    fn lint_config_reports_connection_problems() {
+        let var = format!("LAZYCOMPASS_LINT_MISSING_{}", unique_env_suffix());
+        let contents = format!(
+            r#"[[connections]]
+name = "local"
+uri = "mongodb://localhost:27017"
+
+[[connections]]
+name = "local"
+uri = "postgres://localhost"
+
+[[connections]]
+name = "remote"
+uri = "${{{var}}}"
+"#
+        );
+        let diagnostics = lint_config_contents(Path::new("config.toml"), &contents);
+
+        let summary: Vec<(usize, &str)> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.message.as_str()))
+            .collect();
+        assert_eq!(summary.len(), 3, "{summary:?}");
+        assert_eq!(summary[0], (6, "duplicate connection name 'local'"));
+        assert_eq!(
+            summary[1],
+            (
+                7,
+                "connection 'local' uri must start with mongodb:// or mongodb+srv://"
+            )
+        );
+        assert_eq!(summary[2].0, 11);
+        assert!(summary[2].1.contains(&var));
+        assert!(
+            diagnostics
+                .iter()
+                .all(|diagnostic| diagnostic.severity == LintSeverity::Error)
+        );
+    }
+
+    #[test]
+    fn lint_config_checks_uri_command_connections() {
+        let var = format!("LAZYCOMPASS_LINT_COMMAND_{}", unique_env_suffix());
+        let contents = format!(
+            r#"[[connections]]
+name = "vault"
+uri_command = ["pass", "show", "mongo/prod"]
+
+[[connections]]
+name = "both"
+uri = "mongodb://localhost:27017"
+uri_command = ["pass", "show", "mongo/both"]
+
+[[connections]]
+name = "missing"
+uri_command = ["pass", "show", "${{{var}}}"]
+"#
+        );
+        let diagnostics = lint_config_contents(Path::new("config.toml"), &contents);
+
+        let summary: Vec<(usize, &str)> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.message.as_str()))
+            .collect();
+        assert_eq!(summary.len(), 2, "{summary:?}");
+        assert_eq!(
+            summary[0],
+            (8, "connection 'both' has both uri and uri_command")
+        );
+        assert_eq!(summary[1].0, 12);
+        assert!(summary[1].1.contains("uri_command"));
+        assert!(summary[1].1.contains(&var));
+    }
+
+    #[test]
+    fn lint_config_checks_profile_connections() {
+        let contents = r#"[profiles.staging]
+env_file = ".env.missing-staging"
+
+[[profiles.staging.connections]]
+name = "app"
+uri = "postgres://staging"
+"#;
+        let diagnostics = lint_config_contents(Path::new("config.toml"), contents);
+
+        let summary: Vec<(usize, &str)> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.message.as_str()))
+            .collect();
+        assert_eq!(summary.len(), 2, "{summary:?}");
+        assert_eq!(summary[0].0, 2);
+        assert!(summary[0].1.starts_with("profile 'staging': env_file"));
+        assert_eq!(
+            summary[1],
+            (
+                6,
+                "profile 'staging' connection 'app' uri must start with mongodb:// or mongodb+srv://"
+            )
+        );
+    }
+
+    #[test]
+    fn lint_config_reports_invalid_settings() {
+        let diagnostics =
+            lint_config_contents(Path::new("config.toml"), "[timeouts]\nconnect_ms = 0\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 1);
+        assert!(diagnostics[0].message.contains("connect timeout"));
+    }
+
+    #[test]
+    fn lint_config_checks_repo_config_and_uses_repo_dotenv() {
+        let root = temp_root("lint_config_repo");
+        let repo_root = root.join("repo");
+        let var = format!("LAZYCOMPASS_LINT_REPO_{}", unique_env_suffix());
+        write_file(
+            &repo_root.join(".lazycompass/config.toml"),
+            &format!("[[connections]]\nname = \"repo\"\nuri = \"${{{var}}}\"\n"),
+        );
+        write_file(
+            &repo_root.join(".env"),
+            &format!("{var}=mongodb://localhost:27017\n"),
+        );
+        let paths = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: Some(repo_root),
+        };
+
+        assert!(lint_config(&paths).is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -7,7 +7,8 @@ use std::path::PathBuf;
 use crate::{
     ConfigPaths,
     saved_common::{
-        collect_json_paths, parse_scope_from_saved_id, saved_id_from_path, validate_saved_id,
+        collect_json_paths, json_field_as_tags, parse_scope_from_saved_id, read_saved_file,
+        remove_saved_file, saved_id_from_path, validate_saved_id,
     },
     security::write_secure_file,
 };
@@ -49,18 +50,79 @@ pub fn write_saved_aggregation(
     if path.exists() && !overwrite {
         anyhow::bail!("saved aggregation '{}' already exists", aggregation.id);
     }
+    let contents = render_saved_aggregation(aggregation)?;
+    write_secure_file(&path, &contents, overwrite)
+        .with_context(|| format!("unable to write saved aggregation {}", path.display()))?;
+    Ok(path)
+}
+
+pub fn load_saved_aggregation(paths: &ConfigPaths, id: &str) -> Result<SavedAggregation> {
+    let path = saved_aggregation_path(paths, id)?;
+    let contents = read_saved_file(&path, "saved aggregation", id)?;
+    parse_saved_aggregation(id, &contents)
+        .with_context(|| format!("invalid saved aggregation {}", path.display()))
+}
+
+pub fn parse_saved_aggregation(id: &str, contents: &str) -> Result<SavedAggregation> {
+    let scope = parse_scope_from_saved_id(id)
+        .with_context(|| format!("invalid saved aggregation id '{id}'"))?;
+    let json: Value =
+        serde_json::from_str(contents).context("invalid JSON in saved aggregation")?;
+    parse_saved_aggregation_payload(&json, id.to_string(), scope)
+}
+
+/// Renders the on-disk payload: a bare pipeline array, or an object when tags are set.
+pub fn render_saved_aggregation(aggregation: &SavedAggregation) -> Result<String> {
     let pipeline_json: Value = serde_json::from_str(&aggregation.pipeline)
         .context("saved aggregation pipeline must be valid JSON")?;
     if !pipeline_json.is_array() {
         anyhow::bail!("saved aggregation pipeline must be a JSON array");
     }
-    let contents = serde_json::to_string_pretty(&pipeline_json)
-        .context("unable to serialize saved aggregation")?;
-    write_secure_file(&path, &contents, overwrite)
-        .with_context(|| format!("unable to write saved aggregation {}", path.display()))?;
+    let payload = if aggregation.tags.is_empty() {
+        pipeline_json
+    } else {
+        serde_json::json!({
+            "pipeline": pipeline_json,
+            "tags": aggregation.tags,
+        })
+    };
+    serde_json::to_string_pretty(&payload).context("unable to serialize saved aggregation")
+}
+
+pub fn delete_saved_aggregation(paths: &ConfigPaths, id: &str) -> Result<PathBuf> {
+    let path = saved_aggregation_path(paths, id)?;
+    remove_saved_file(&path, "saved aggregation", id)?;
     Ok(path)
 }
 
+/// Moves a saved aggregation to a new ID, rewriting its scope to match the new ID.
+pub fn rename_saved_aggregation(
+    paths: &ConfigPaths,
+    from: &str,
+    to: &str,
+    overwrite: bool,
+) -> Result<PathBuf> {
+    let path = copy_saved_aggregation(paths, from, to, overwrite)?;
+    delete_saved_aggregation(paths, from)?;
+    Ok(path)
+}
+
+pub fn copy_saved_aggregation(
+    paths: &ConfigPaths,
+    from: &str,
+    to: &str,
+    overwrite: bool,
+) -> Result<PathBuf> {
+    validate_saved_id(to)?;
+    if from == to {
+        anyhow::bail!("source and destination saved aggregation ids are the same");
+    }
+    let mut aggregation = load_saved_aggregation(paths, from)?;
+    aggregation.scope = parse_scope_from_saved_id(to)?;
+    aggregation.id = to.to_string();
+    write_saved_aggregation(paths, &aggregation, overwrite)
+}
+
 fn load_aggregations_from_dir(
     dir: &std::path::Path,
 ) -> Result<(Vec<SavedAggregation>, Vec<String>)> {
@@ -100,13 +162,27 @@ fn parse_saved_aggregation_payload(
     id: String,
     scope: lazycompass_core::SavedScope,
 ) -> Result<SavedAggregation> {
-    if !json.is_array() {
-        anyhow::bail!("saved aggregation payload must be a JSON array");
-    }
+    let (pipeline, tags) = match json {
+        Value::Array(_) => (json, Vec::new()),
+        Value::Object(object) => {
+            for key in object.keys() {
+                if !matches!(key.as_str(), "pipeline" | "tags") {
+                    anyhow::bail!("unknown field '{key}' in saved aggregation payload");
+                }
+            }
+            let pipeline = object
+                .get("pipeline")
+                .filter(|pipeline| pipeline.is_array())
+                .ok_or_else(|| anyhow::anyhow!("field 'pipeline' must be a JSON array"))?;
+            (pipeline, json_field_as_tags(object, "tags")?)
+        }
+        _ => anyhow::bail!("saved aggregation payload must be a JSON array or object"),
+    };
     let aggregation = SavedAggregation {
         id,
         scope,
-        pipeline: serde_json::to_string(json).context("unable to serialize pipeline JSON")?,
+        pipeline: serde_json::to_string(pipeline).context("unable to serialize pipeline JSON")?,
+        tags,
     };
     aggregation
         .validate()
@@ -120,7 +196,10 @@ mod tests {
     use lazycompass_core::{SavedAggregation, SavedScope};
     use std::fs;
 
-    use super::{load_saved_aggregations, write_saved_aggregation};
+    use super::{
+        load_saved_aggregation, load_saved_aggregations, rename_saved_aggregation,
+        write_saved_aggregation,
+    };
     use crate::{
         ConfigPaths,
         test_support::{temp_root, write_file},
@@ -167,6 +246,7 @@ mod tests {
             id: "orders_by_user".to_string(),
             scope: SavedScope::Shared,
             pipeline: "[]".to_string(),
+            tags: Vec::new(),
         };
 
         let _ = write_saved_aggregation(&paths, &aggregation, false)?;
@@ -215,6 +295,7 @@ mod tests {
                 collection: "orders".to_string(),
             },
             pipeline: "[]".to_string(),
+            tags: Vec::new(),
         };
 
         let err =
@@ -236,6 +317,7 @@ mod tests {
             id: "orders_by_user".to_string(),
             scope: SavedScope::Shared,
             pipeline: "{invalid".to_string(),
+            tags: Vec::new(),
         };
 
         let err = write_saved_aggregation(&paths, &aggregation, false)
@@ -248,4 +330,54 @@ mod tests {
         let _ = fs::remove_dir_all(&root);
         Ok(())
     }
+
+    #[test]
+    fn write_saved_aggregation_uses_object_payload_when_tagged() -> Result<()> {
+        let root = temp_root("write_saved_aggregation_tags");
+        let paths = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: Some(root.join("repo")),
+        };
+        let aggregation = SavedAggregation {
+            id: "orders_by_user".to_string(),
+            scope: SavedScope::Shared,
+            pipeline: r#"[{"$limit":1}]"#.to_string(),
+            tags: vec!["reports".to_string()],
+        };
+
+        let path = write_saved_aggregation(&paths, &aggregation, false)?;
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+        assert!(json.get("pipeline").is_some_and(|value| value.is_array()));
+
+        let loaded = load_saved_aggregation(&paths, "orders_by_user")?;
+        assert_eq!(loaded.tags, vec!["reports".to_string()]);
+        assert_eq!(loaded.pipeline, r#"[{"$limit":1}]"#);
+
+        let _ = fs::remove_dir_all(&root);
+        Ok(())
+    }
+
+    #[test]
+    fn rename_saved_aggregation_rewrites_scope() -> Result<()> {
+        let root = temp_root("rename_saved_aggregation");
+        let repo_root = root.join("repo");
+        write_file(
+            &repo_root.join(".lazycompass/aggregations/app.orders.by_user.json"),
+            "[]",
+        );
+        let paths = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: Some(repo_root),
+        };
+
+        rename_saved_aggregation(&paths, "app.orders.by_user", "by_user", false)?;
+        assert_eq!(
+            load_saved_aggregation(&paths, "by_user")?.scope,
+            SavedScope::Shared
+        );
+        assert!(load_saved_aggregation(&paths, "app.orders.by_user").is_err());
+
+        let _ = fs::remove_dir_all(&root);
+        Ok(())
+    }
 }
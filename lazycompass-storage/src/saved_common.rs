@@ -1,8 +1,11 @@
 use anyhow::{Context, Result};
 use lazycompass_core::SavedScope;
+use serde_json::Value;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::security::ensure_not_symlinked_file;
+
 pub(crate) fn collect_json_paths(dir: &Path) -> Result<Vec<PathBuf>> {
     let Ok(metadata) = fs::symlink_metadata(dir) else {
         return Ok(Vec::new());
@@ -42,7 +45,7 @@ pub(crate) fn saved_id_from_path(path: &Path) -> Result<String> {
     Ok(stem.to_string())
 }
 
-pub(crate) fn validate_saved_id(id: &str) -> Result<()> {
+pub fn validate_saved_id(id: &str) -> Result<()> {
     let trimmed = id.trim();
     if trimmed.is_empty() {
         anyhow::bail!("saved id cannot be empty");
@@ -92,12 +95,56 @@ pub(crate) fn parse_scope_from_saved_id(id: &str) -> Result<SavedScope> {
     })
 }
 
+pub(crate) fn json_field_as_tags(
+    object: &serde_json::Map<String, Value>,
+    field: &str,
+) -> Result<Vec<String>> {
+    let Some(value) = object.get(field) else {
+        return Ok(Vec::new());
+    };
+    if value.is_null() {
+        return Ok(Vec::new());
+    }
+    let items = value
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("field '{field}' must be an array of strings"))?;
+    let mut tags = Vec::with_capacity(items.len());
+    for item in items {
+        let tag = item
+            .as_str()
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("field '{field}' must contain non-empty strings"))?;
+        if !tags.iter().any(|existing| existing == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    Ok(tags)
+}
+
+pub(crate) fn read_saved_file(path: &Path, label: &str, id: &str) -> Result<String> {
+    ensure_not_symlinked_file(path)?;
+    if !path.is_file() {
+        anyhow::bail!("{label} '{id}' not found");
+    }
+    fs::read_to_string(path)
+        .with_context(|| format!("unable to read {label} file {}", path.display()))
+}
+
+pub(crate) fn remove_saved_file(path: &Path, label: &str, id: &str) -> Result<()> {
+    ensure_not_symlinked_file(path)?;
+    if !path.is_file() {
+        anyhow::bail!("{label} '{id}' not found");
+    }
+    fs::remove_file(path).with_context(|| format!("unable to remove {label} {}", path.display()))
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
     use lazycompass_core::SavedScope;
 
-    use super::{parse_scope_from_saved_id, validate_saved_id};
+    use super::{json_field_as_tags, parse_scope_from_saved_id, validate_saved_id};
 
     #[test]
     fn parse_scope_from_saved_id_supports_multidot_collection() -> Result<()> {
@@ -139,4 +186,20 @@ mod tests {
         let err = validate_saved_id("app..recent").expect_err("should reject empty segment");
         assert!(err.to_string().contains("empty segments"));
     }
+
+    #[test]
+    fn json_field_as_tags_trims_and_deduplicates() -> Result<()> {
+        let object = serde_json::json!({ "tags": [" ops ", "reports", "ops"] });
+        let tags = json_field_as_tags(object.as_object().expect("object"), "tags")?;
+        assert_eq!(tags, vec!["ops".to_string(), "reports".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn json_field_as_tags_rejects_non_string_entries() {
+        let object = serde_json::json!({ "tags": ["ops", 1] });
+        let err = json_field_as_tags(object.as_object().expect("object"), "tags")
+            .expect_err("should reject number");
+        assert!(err.to_string().contains("non-empty strings"));
+    }
 }
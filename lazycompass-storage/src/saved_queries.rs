@@ -7,7 +7,8 @@ use std::path::PathBuf;
 use crate::{
     ConfigPaths,
     saved_common::{
-        collect_json_paths, parse_scope_from_saved_id, saved_id_from_path, validate_saved_id,
+        collect_json_paths, json_field_as_tags, parse_scope_from_saved_id, read_saved_file,
+        remove_saved_file, saved_id_from_path, validate_saved_id,
     },
     security::write_secure_file,
 };
@@ -42,13 +43,65 @@ pub fn write_saved_query(
     if path.exists() && !overwrite {
         anyhow::bail!("saved query '{}' already exists", query.id);
     }
-    let contents = serde_json::to_string_pretty(&saved_query_payload(query)?)
-        .context("unable to serialize saved query")?;
+    let contents = render_saved_query(query)?;
     write_secure_file(&path, &contents, overwrite)
         .with_context(|| format!("unable to write saved query {}", path.display()))?;
     Ok(path)
 }
 
+pub fn load_saved_query(paths: &ConfigPaths, id: &str) -> Result<SavedQuery> {
+    let path = saved_query_path(paths, id)?;
+    let contents = read_saved_file(&path, "saved query", id)?;
+    parse_saved_query(id, &contents)
+        .with_context(|| format!("invalid saved query {}", path.display()))
+}
+
+pub fn parse_saved_query(id: &str, contents: &str) -> Result<SavedQuery> {
+    let scope =
+        parse_scope_from_saved_id(id).with_context(|| format!("invalid saved query id '{id}'"))?;
+    let json: Value = serde_json::from_str(contents).context("invalid JSON in saved query")?;
+    parse_saved_query_payload(&json, id.to_string(), scope)
+}
+
+pub fn render_saved_query(query: &SavedQuery) -> Result<String> {
+    serde_json::to_string_pretty(&saved_query_payload(query)?)
+        .context("unable to serialize saved query")
+}
+
+pub fn delete_saved_query(paths: &ConfigPaths, id: &str) -> Result<PathBuf> {
+    let path = saved_query_path(paths, id)?;
+    remove_saved_file(&path, "saved query", id)?;
+    Ok(path)
+}
+
+/// Moves a saved query to a new ID, rewriting its scope to match the new ID.
+pub fn rename_saved_query(
+    paths: &ConfigPaths,
+    from: &str,
+    to: &str,
+    overwrite: bool,
+) -> Result<PathBuf> {
+    let path = copy_saved_query(paths, from, to, overwrite)?;
+    delete_saved_query(paths, from)?;
+    Ok(path)
+}
+
+pub fn copy_saved_query(
+    paths: &ConfigPaths,
+    from: &str,
+    to: &str,
+    overwrite: bool,
+) -> Result<PathBuf> {
+    validate_saved_id(to)?;
+    if from == to {
+        anyhow::bail!("source and destination saved query ids are the same");
+    }
+    let mut query = load_saved_query(paths, from)?;
+    query.scope = parse_scope_from_saved_id(to)?;
+    query.id = to.to_string();
+    write_saved_query(paths, &query, overwrite)
+}
+
 fn load_queries_from_dir(dir: &std::path::Path) -> Result<(Vec<SavedQuery>, Vec<String>)> {
     let paths = collect_json_paths(dir)?;
     let mut queries = Vec::with_capacity(paths.len());
@@ -85,7 +138,10 @@ fn parse_saved_query_payload(json: &Value, id: String, scope: SavedScope) -> Res
         .as_object()
         .ok_or_else(|| anyhow::anyhow!("saved query payload must be a JSON object"))?;
     for key in object.keys() {
-        if !matches!(key.as_str(), "filter" | "projection" | "sort" | "limit") {
+        if !matches!(
+            key.as_str(),
+            "filter" | "projection" | "sort" | "limit" | "tags"
+        ) {
             anyhow::bail!("unknown field '{key}' in saved query payload");
         }
     }
@@ -101,6 +157,7 @@ fn parse_saved_query_payload(json: &Value, id: String, scope: SavedScope) -> Res
                 .ok_or_else(|| anyhow::anyhow!("field 'limit' must be a non-negative integer"))?,
         ),
     };
+    let tags = json_field_as_tags(object, "tags")?;
 
     let query = SavedQuery {
         id,
@@ -109,6 +166,7 @@ fn parse_saved_query_payload(json: &Value, id: String, scope: SavedScope) -> Res
         projection,
         sort,
         limit,
+        tags,
     };
     query.validate().context("invalid saved query data")?;
     Ok(query)
@@ -138,6 +196,9 @@ fn saved_query_payload(query: &SavedQuery) -> Result<Value> {
     if let Some(limit) = query.limit {
         object.insert("limit".to_string(), Value::from(limit));
     }
+    if !query.tags.is_empty() {
+        object.insert("tags".to_string(), Value::from(query.tags.clone()));
+    }
     Ok(Value::Object(object))
 }
 
@@ -162,7 +223,10 @@ mod tests {
     use lazycompass_core::{SavedQuery, SavedScope};
     use std::fs;
 
-    use super::{load_saved_queries, write_saved_query};
+    use super::{
+        copy_saved_query, delete_saved_query, load_saved_queries, load_saved_query,
+        rename_saved_query, write_saved_query,
+    };
     use crate::{
         ConfigPaths,
         test_support::{canonical_temp_dir, temp_root, write_file},
@@ -256,6 +320,7 @@ mod tests {
             projection: None,
             sort: None,
             limit: Some(50),
+            tags: Vec::new(),
         };
 
         let path = write_saved_query(&paths, &query, false)?;
@@ -285,6 +350,7 @@ mod tests {
             projection: None,
             sort: None,
             limit: None,
+            tags: Vec::new(),
         };
 
         let err = write_saved_query(&paths, &query, false).expect_err("expected mismatch");
@@ -308,6 +374,7 @@ mod tests {
             projection: None,
             sort: None,
             limit: None,
+            tags: Vec::new(),
         };
 
         let err = write_saved_query(&paths, &query, false).expect_err("expected invalid json");
@@ -329,6 +396,7 @@ mod tests {
             projection: None,
             sort: None,
             limit: None,
+            tags: Vec::new(),
         };
         let paths = ConfigPaths {
             global_root: canonical_temp_dir(),
@@ -338,4 +406,101 @@ mod tests {
         let err = write_saved_query(&paths, &query, false).expect_err("expected repo error");
         assert!(err.to_string().contains("repository config not found"));
     }
+
+    #[test]
+    fn load_saved_query_reads_tags() -> Result<()> {
+        let root = temp_root("saved_query_tags");
+        let repo_root = root.join("repo");
+        write_file(
+            &repo_root.join(".lazycompass/queries/recent_orders.json"),
+            r#"{ "filter": {}, "tags": ["ops", "reports"] }"#,
+        );
+        let paths = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: Some(repo_root),
+        };
+
+        let query = load_saved_query(&paths, "recent_orders")?;
+        assert_eq!(query.tags, vec!["ops".to_string(), "reports".to_string()]);
+
+        let _ = fs::remove_dir_all(&root);
+        Ok(())
+    }
+
+    #[test]
+    fn rename_saved_query_rewrites_scope_and_removes_source() -> Result<()> {
+        let root = temp_root("rename_saved_query");
+        let repo_root = root.join("repo");
+        write_file(
+            &repo_root.join(".lazycompass/queries/recent_orders.json"),
+            r#"{ "filter": { "status": "open" }, "tags": ["ops"] }"#,
+        );
+        let paths = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: Some(repo_root.clone()),
+        };
+
+        let path = rename_saved_query(&paths, "recent_orders", "app.orders.recent", false)?;
+        assert!(path.ends_with("app.orders.recent.json"));
+        assert!(
+            !repo_root
+                .join(".lazycompass/queries/recent_orders.json")
+                .exists()
+        );
+        let query = load_saved_query(&paths, "app.orders.recent")?;
+        assert_eq!(
+            query.scope,
+            SavedScope::Scoped {
+                database: "app".to_string(),
+                collection: "orders".to_string(),
+            }
+        );
+        assert_eq!(query.tags, vec!["ops".to_string()]);
+
+        let _ = fs::remove_dir_all(&root);
+        Ok(())
+    }
+
+    #[test]
+    fn copy_saved_query_refuses_to_overwrite_without_flag() -> Result<()> {
+        let root = temp_root("copy_saved_query");
+        let repo_root = root.join("repo");
+        write_file(
+            &repo_root.join(".lazycompass/queries/first.json"),
+            r#"{ "limit": 1 }"#,
+        );
+        write_file(
+            &repo_root.join(".lazycompass/queries/second.json"),
+            r#"{ "limit": 2 }"#,
+        );
+        let paths = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: Some(repo_root),
+        };
+
+        let err = copy_saved_query(&paths, "first", "second", false).expect_err("exists");
+        assert!(err.to_string().contains("already exists"));
+
+        copy_saved_query(&paths, "first", "second", true)?;
+        assert_eq!(load_saved_query(&paths, "second")?.limit, Some(1));
+        assert_eq!(load_saved_query(&paths, "first")?.limit, Some(1));
+
+        let _ = fs::remove_dir_all(&root);
+        Ok(())
+    }
+
+    #[test]
+    fn delete_saved_query_reports_missing_ids() -> Result<()> {
+        let root = temp_root("delete_saved_query");
+        let paths = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: Some(root.join("repo")),
+        };
+
+        let err = delete_saved_query(&paths, "missing").expect_err("missing");
+        assert!(err.to_string().contains("saved query 'missing' not found"));
+
+        let _ = fs::remove_dir_all(&root);
+        Ok(())
+    }
 }
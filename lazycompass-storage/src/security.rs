@@ -292,7 +292,7 @@ fn normalize_json_files_in_dir(path: &Path) -> Result<()> {
 }
 
 #[cfg(unix)]
-pub(crate) fn permission_warnings(paths: &ConfigPaths) -> Vec<String> {
+pub fn permission_warnings(paths: &ConfigPaths) -> Vec<String> {
     let mut warnings = Vec::new();
     append_permission_warning_dir(&paths.global_root, &mut warnings);
     append_permission_warning_file(&paths.global_config_path(), &mut warnings);
@@ -311,7 +311,7 @@ pub(crate) fn permission_warnings(paths: &ConfigPaths) -> Vec<String> {
 }
 
 #[cfg(not(unix))]
-pub(crate) fn permission_warnings(_paths: &ConfigPaths) -> Vec<String> {
+pub fn permission_warnings(_paths: &ConfigPaths) -> Vec<String> {
     Vec::new()
 }
 
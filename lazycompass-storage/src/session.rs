@@ -0,0 +1,116 @@
+use anyhow::{Context, Result};
+use lazycompass_core::SessionState;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{ConfigPaths, ensure_not_symlinked_file, write_secure_file};
+
+const GLOBAL_SESSION_KEY: &str = "global";
+
+pub fn sessions_path(paths: &ConfigPaths) -> PathBuf {
+    paths.global_root.join("sessions.json")
+}
+
+/// Loads the last session recorded for the current repo, or for runs outside any repo.
+pub fn load_session(paths: &ConfigPaths) -> Result<Option<SessionState>> {
+    Ok(read_sessions(paths)?.remove(&session_key(paths)))
+}
+
+/// Records the session for the current repo, keeping other repos' sessions intact.
+pub fn save_session(paths: &ConfigPaths, session: &SessionState) -> Result<()> {
+    // A corrupt file only loses other repos' sessions; it should never block saving this one.
+    let mut sessions = read_sessions(paths).unwrap_or_default();
+    sessions.insert(session_key(paths), session.clone());
+    let contents =
+        serde_json::to_string_pretty(&sessions).context("unable to serialize session state")?;
+    write_secure_file(&sessions_path(paths), &contents, true)
+}
+
+fn session_key(paths: &ConfigPaths) -> String {
+    paths
+        .repo_root
+        .as_ref()
+        .map(|root| root.display().to_string())
+        .unwrap_or_else(|| GLOBAL_SESSION_KEY.to_string())
+}
+
+fn read_sessions(paths: &ConfigPaths) -> Result<BTreeMap<String, SessionState>> {
+    let path = sessions_path(paths);
+    ensure_not_symlinked_file(&path)?;
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(BTreeMap::new());
+        }
+        Err(error) => {
+            return Err(error)
+                .with_context(|| format!("unable to read session file {}", path.display()));
+        }
+    };
+    serde_json::from_str(&contents)
+        .with_context(|| format!("unable to parse session file {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use lazycompass_core::{SessionDraft, SessionScreen, SessionState};
+    use std::fs;
+
+    use super::{load_session, save_session, sessions_path};
+    use crate::{ConfigPaths, test_support::temp_root};
+
+    fn session(connection: &str) -> SessionState {
+        SessionState {
+            connection: connection.to_string(),
+            database: Some("app".to_string()),
+            collection: Some("users".to_string()),
+            screen: SessionScreen::Documents,
+            document_page: 2,
+            inline_query: Some(r#"{"filter":{"active":true}}"#.to_string()),
+            inline_aggregation: None,
+            active_inline_draft: Some(SessionDraft::Query),
+        }
+    }
+
+    #[test]
+    fn save_session_keeps_one_session_per_repo() -> Result<()> {
+        let root = temp_root("sessions_per_repo");
+        let global = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: None,
+        };
+        let repo = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: Some(root.join("repo")),
+        };
+        assert_eq!(load_session(&repo)?, None);
+
+        save_session(&global, &session("local"))?;
+        save_session(&repo, &session("staging"))?;
+        save_session(&repo, &session("prod"))?;
+
+        assert_eq!(load_session(&global)?, Some(session("local")));
+        assert_eq!(load_session(&repo)?, Some(session("prod")));
+        Ok(())
+    }
+
+    #[test]
+    fn save_session_replaces_corrupt_file() -> Result<()> {
+        let root = temp_root("sessions_corrupt");
+        let paths = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: None,
+        };
+        fs::create_dir_all(&paths.global_root)?;
+        fs::write(sessions_path(&paths), "not json")?;
+
+        let err = load_session(&paths).expect_err("expected parse error");
+        assert!(err.to_string().contains("unable to parse session file"));
+
+        save_session(&paths, &session("local"))?;
+        assert_eq!(load_session(&paths)?, Some(session("local")));
+        Ok(())
+    }
+}
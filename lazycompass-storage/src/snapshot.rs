@@ -2,7 +2,7 @@ use anyhow::Result;
 use lazycompass_core::{Config, SavedAggregation, SavedQuery, connection_security_warnings};
 
 use crate::{
-    ConfigPaths, load_config, load_saved_aggregations, load_saved_queries,
+    ConfigPaths, load_config_with_profile, load_saved_aggregations, load_saved_queries,
     security::{normalize_permissions, permission_warnings},
 };
 
@@ -15,7 +15,14 @@ pub struct StorageSnapshot {
 }
 
 pub fn load_storage(paths: &ConfigPaths) -> Result<StorageSnapshot> {
-    let config = load_config(paths)?;
+    load_storage_with_profile(paths, None)
+}
+
+pub fn load_storage_with_profile(
+    paths: &ConfigPaths,
+    profile: Option<&str>,
+) -> Result<StorageSnapshot> {
+    let config = load_config_with_profile(paths, profile)?;
     load_storage_with_config(paths, config)
 }
 
@@ -39,7 +46,7 @@ pub fn load_storage_with_config(paths: &ConfigPaths, config: Config) -> Result<S
 mod tests {
     use super::load_storage_with_config;
     use crate::{ConfigPaths, test_support::canonical_temp_dir};
-    use lazycompass_core::{Config, ConnectionSpec};
+    use lazycompass_core::{Config, ConnectionProtection, ConnectionSpec};
     use std::fs;
     #[cfg(unix)]
     use std::os::unix::fs::PermissionsExt;
@@ -116,7 +123,10 @@ mod tests {
             connections: vec![ConnectionSpec {
                 name: "insecure".to_string(),
                 uri: "mongodb://localhost:27017".to_string(),
+                uri_command: None,
                 default_database: None,
+                protection: ConnectionProtection::Open,
+                options: None,
             }],
             ..Config::default()
         };
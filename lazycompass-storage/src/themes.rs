@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use lazycompass_core::ThemeFile;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{ConfigPaths, ensure_not_symlinked_file};
+
+/// Theme file candidates for `name`, repo first.
+pub fn theme_file_paths(paths: &ConfigPaths, name: &str) -> Vec<PathBuf> {
+    let file_name = format!("{name}.toml");
+    paths
+        .repo_config_root()
+        .map(|root| root.join("themes").join(&file_name))
+        .into_iter()
+        .chain(std::iter::once(
+            paths.global_root.join("themes").join(&file_name),
+        ))
+        .collect()
+}
+
+/// Loads `themes/<name>.toml` from the repo or global config dir; `Ok(None)` if neither exists.
+pub fn load_theme_file(paths: &ConfigPaths, name: &str) -> Result<Option<ThemeFile>> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+    {
+        anyhow::bail!("theme name '{name}' may only contain letters, digits, '-' and '_'");
+    }
+    for path in theme_file_paths(paths, name) {
+        ensure_not_symlinked_file(&path)?;
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(error) => {
+                return Err(error)
+                    .with_context(|| format!("unable to read theme file {}", path.display()));
+            }
+        };
+        let theme = toml::from_str(&contents)
+            .with_context(|| format!("unable to parse theme file {}", path.display()))?;
+        return Ok(Some(theme));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::load_theme_file;
+    use crate::{
+        ConfigPaths,
+        test_support::{temp_root, write_file},
+    };
+
+    #[test]
+    fn load_theme_file_prefers_repo_theme() -> Result<()> {
+        let root = temp_root("themes_repo_first");
+        let paths = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: Some(root.join("repo")),
+        };
+        write_file(
+            &paths.global_root.join("themes/nord.toml"),
+            "accent = \"#88c0d0\"\n",
+        );
+        write_file(
+            &root.join("repo/.lazycompass/themes/nord.toml"),
+            "base = \"ember\"\naccent = \"208\"\n",
+        );
+
+        let theme = load_theme_file(&paths, "nord")?.expect("theme file");
+        assert_eq!(theme.base.as_deref(), Some("ember"));
+        assert_eq!(theme.colors.accent.as_deref(), Some("208"));
+        assert!(load_theme_file(&paths, "missing")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn load_theme_file_rejects_path_like_names() {
+        let paths = ConfigPaths {
+            global_root: temp_root("themes_names").join("global"),
+            repo_root: None,
+        };
+        let err = load_theme_file(&paths, "../config").expect_err("expected invalid name");
+        assert!(err.to_string().contains("may only contain letters"));
+    }
+}
@@ -0,0 +1,113 @@
+use lazycompass_core::Config;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::{
+    ConfigPaths, dotenv_path_for_config, saved_aggregation_files, saved_query_files,
+    theme_file_paths,
+};
+
+/// Size and modification time of every file that feeds a [`crate::StorageSnapshot`]. Two
+/// fingerprints differ when any of those files was created, removed, or rewritten.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageFingerprint {
+    files: Vec<(PathBuf, Option<FileStamp>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+/// Stat config files, their `.env` files (including the active profile's `env_file`), theme
+/// files, and repo saved specs. Missing files are recorded too so that creating one is noticed.
+pub fn storage_fingerprint(paths: &ConfigPaths, config: &Config) -> StorageFingerprint {
+    let env_file = config
+        .active_profile
+        .as_ref()
+        .and_then(|name| config.profiles.get(name))
+        .and_then(|profile| profile.env_file.as_deref());
+
+    let mut files = Vec::new();
+    for config_path in std::iter::once(paths.global_config_path()).chain(paths.repo_config_path()) {
+        if let Some(dotenv) = dotenv_path_for_config(&config_path) {
+            if let (Some(env_file), Some(dir)) = (env_file, dotenv.parent()) {
+                files.push(dir.join(env_file));
+            }
+            files.push(dotenv);
+        }
+        files.push(config_path);
+    }
+    for name in [&config.theme.name, &config.theme.light, &config.theme.dark]
+        .into_iter()
+        .flatten()
+    {
+        files.extend(theme_file_paths(paths, name));
+    }
+    files.extend(saved_query_files(paths).unwrap_or_default());
+    files.extend(saved_aggregation_files(paths).unwrap_or_default());
+    files.sort();
+    files.dedup();
+
+    let files = files
+        .into_iter()
+        .map(|path| {
+            let stamp = fs::metadata(&path).ok().map(|metadata| FileStamp {
+                modified: metadata.modified().ok(),
+                len: metadata.len(),
+            });
+            (path, stamp)
+        })
+        .collect();
+    StorageFingerprint { files }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::storage_fingerprint;
+    use crate::{ConfigPaths, test_support::canonical_temp_dir};
+    use lazycompass_core::Config;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let nonce = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let path = canonical_temp_dir().join(format!("lazycompass_watch_{prefix}_{nonce}"));
+        fs::create_dir_all(&path).expect("create temp dir");
+        path
+    }
+
+    #[test]
+    fn storage_fingerprint_changes_when_inputs_change() {
+        let root = temp_dir("changes");
+        let repo_root = root.join("repo");
+        let queries = repo_root.join(".lazycompass/queries");
+        fs::create_dir_all(&queries).expect("create queries");
+        let paths = ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: Some(repo_root.clone()),
+        };
+        let config = Config::default();
+
+        let initial = storage_fingerprint(&paths, &config);
+        assert_eq!(initial, storage_fingerprint(&paths, &config));
+
+        fs::write(queries.join("active.json"), "{}").expect("write query");
+        let with_query = storage_fingerprint(&paths, &config);
+        assert_ne!(initial, with_query);
+
+        fs::write(queries.join("active.json"), r#"{ "filter": {} }"#).expect("rewrite query");
+        let rewritten = storage_fingerprint(&paths, &config);
+        assert_ne!(with_query, rewritten);
+
+        fs::write(repo_root.join(".env"), "MONGO_URI=mongodb://localhost").expect("write env");
+        assert_ne!(rewritten, storage_fingerprint(&paths, &config));
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
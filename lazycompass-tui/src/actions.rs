@@ -7,13 +7,25 @@ impl App {
 
     pub(crate) fn block_if_db_write_disabled(&mut self, action: &str) -> bool {
         let guard = self.write_guard();
-        if let Err(error) = guard.ensure_write_allowed(action) {
+        let result = guard.ensure_write_allowed(action).and_then(|()| {
+            self.selected_connection()
+                .map_or(Ok(()), |connection| connection.ensure_write_allowed(action))
+        });
+        if let Err(error) = result {
             self.message = Some(error.to_string());
             return true;
         }
         false
     }
 
+    /// Returns the text a write must be confirmed with when the selected connection uses
+    /// `protection = "confirm"`.
+    pub(crate) fn connection_confirmation(&self) -> Option<String> {
+        self.selected_connection()
+            .filter(|connection| connection.protection.requires_confirmation())
+            .map(|connection| connection.name.clone())
+    }
+
     pub(crate) fn show_indexes(&mut self) -> Result<()> {
         if self.screen != Screen::Collections {
             return Ok(());
@@ -32,6 +44,12 @@ impl App {
         if self.block_if_db_write_disabled("delete documents") {
             return Ok(());
         }
+        if !self.selected_document_indexes().is_empty() {
+            if let Err(error) = self.request_delete_selected_documents() {
+                self.set_error_message(&error);
+            }
+            return Ok(());
+        }
         let result = (|| -> Result<()> {
             let (connection, database, collection) = self.selected_context()?;
             let document = self.selected_document()?;
@@ -40,6 +58,9 @@ impl App {
                 "delete document {} (Conn: {connection}, Db: {database}, Coll: {collection})",
                 format_bson(&id)
             );
+            let required = self
+                .connection_confirmation()
+                .unwrap_or_else(|| "delete".to_string());
             let spec = DocumentDeleteSpec {
                 connection: Some(connection),
                 database,
@@ -53,7 +74,7 @@ impl App {
                     return_to_documents: self.screen == Screen::DocumentView,
                 },
                 input: String::new(),
-                required: Some("delete"),
+                required: Some(required),
             });
             Ok(())
         })();
@@ -100,7 +121,7 @@ impl App {
         if !matches!(self.screen, Screen::Documents | Screen::DocumentView) {
             return Ok(());
         }
-        if self.screen == Screen::Documents {
+        if self.screen == Screen::Documents && self.selected_document_indexes().is_empty() {
             match self.active_inline_draft {
                 Some(InlineDraftKind::Query) => {
                     return self.edit_inline_query_draft(terminal);
@@ -116,12 +137,21 @@ impl App {
         }
         let result = (|| -> Result<()> {
             let (connection, database, collection) = self.selected_context()?;
-            let document = self.selected_document()?.clone();
-            let action = PendingEditorAction::Edit {
-                connection,
-                database,
-                collection,
-                document,
+            let selected = self.selected_documents();
+            let action = if selected.is_empty() {
+                PendingEditorAction::Edit {
+                    connection,
+                    database,
+                    collection,
+                    document: self.selected_document()?.clone(),
+                }
+            } else {
+                PendingEditorAction::EditMany {
+                    connection,
+                    database,
+                    collection,
+                    documents: selected,
+                }
             };
             let Some(_) = self.ensure_editor_command(action.clone())? else {
                 return Ok(());
@@ -180,6 +210,7 @@ impl App {
                     projection: None,
                     sort: None,
                     limit: None,
+                    tags: Vec::new(),
                 };
                 PendingEditorAction::SaveQuery { template }
             }
@@ -236,6 +267,7 @@ impl App {
                     id: default_saved_id("aggregation", &scope),
                     scope,
                     pipeline: "[]".to_string(),
+                    tags: Vec::new(),
                 };
                 PendingEditorAction::SaveAggregation { template }
             }
@@ -255,26 +287,15 @@ impl App {
             return Ok(());
         }
         let _ = self.selected_context()?;
-        self.quick_query_modal = Some(QuickQueryModalState::from_draft(
-            self.inline_query_draft.as_ref(),
-        ));
+        let mut modal = QuickQueryModalState::from_draft(self.inline_query_draft.as_ref());
+        modal.fields = sample_fields(&self.documents);
+        self.quick_query_modal = Some(modal);
         self.message = None;
         Ok(())
     }
 
-    pub(crate) fn run_inline_aggregation(
-        &mut self,
-        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-    ) -> Result<()> {
-        if self.screen != Screen::Documents {
-            return Ok(());
-        }
-        let _ = self.selected_context()?;
-        let action = PendingEditorAction::RunInlineAggregation;
-        let Some(_) = self.ensure_editor_command(action.clone())? else {
-            return Ok(());
-        };
-        self.perform_editor_action(action, terminal)
+    pub(crate) fn run_inline_aggregation(&mut self) -> Result<()> {
+        self.open_quick_aggregation_modal()
     }
 
     pub(crate) fn run_saved_query(&mut self) -> Result<()> {
@@ -313,6 +334,139 @@ impl App {
         Ok(())
     }
 
+    pub(crate) fn show_history(&mut self) -> Result<()> {
+        if self.screen != Screen::Documents {
+            return Ok(());
+        }
+        let (connection, database, collection) = self.selected_context()?;
+        let entries = match load_history(&self.paths) {
+            Ok(entries) => entries,
+            Err(error) => {
+                self.set_error_message(&error);
+                return Ok(());
+            }
+        };
+        self.history_items = entries
+            .into_iter()
+            .rev()
+            .filter(|entry| {
+                entry.connection.as_deref() == Some(connection.as_str())
+                    && entry.database == database
+                    && entry.collection == collection
+            })
+            .collect();
+        if self.history_items.is_empty() {
+            self.message = Some("no history for this collection".to_string());
+            return Ok(());
+        }
+        self.history_index = Some(0);
+        self.screen = Screen::History;
+        Ok(())
+    }
+
+    pub(crate) fn rerun_history_entry(&mut self) -> Result<()> {
+        match self.load_history_draft()? {
+            InlineDraftKind::Query => {
+                let payload = self
+                    .inline_query_draft
+                    .as_ref()
+                    .and_then(|draft| draft.parsed.clone())
+                    .ok_or_else(|| anyhow::anyhow!("history entry is not a valid inline query"))?;
+                self.start_execute_inline_query(payload)
+            }
+            InlineDraftKind::Aggregation => {
+                let payload = self
+                    .inline_aggregation_draft
+                    .as_ref()
+                    .and_then(|draft| draft.parsed.clone())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("history entry is not a valid inline aggregation")
+                    })?;
+                self.start_execute_inline_aggregation(payload)
+            }
+        }
+    }
+
+    pub(crate) fn edit_history_entry(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<()> {
+        match self.load_history_draft() {
+            Ok(InlineDraftKind::Query) => self.edit_inline_query_draft(terminal),
+            Ok(InlineDraftKind::Aggregation) => self.edit_inline_aggregation_draft(terminal),
+            Err(error) => {
+                self.set_error_message(&error);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn promote_history_entry(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<()> {
+        match self.load_history_draft() {
+            Ok(InlineDraftKind::Query) => self.save_query(terminal),
+            Ok(InlineDraftKind::Aggregation) => self.save_aggregation(terminal),
+            Err(error) => {
+                self.set_error_message(&error);
+                Ok(())
+            }
+        }
+    }
+
+    /// Copies the selected history entry into the matching inline draft and returns to the
+    /// documents screen so the existing run, edit and save flows can pick it up.
+    fn load_history_draft(&mut self) -> Result<InlineDraftKind> {
+        let entry = self
+            .history_index
+            .and_then(|index| self.history_items.get(index))
+            .ok_or_else(|| anyhow::anyhow!("select a history entry"))?;
+        let raw = render_history_payload(&entry.payload);
+        let kind = match entry.payload {
+            HistoryPayload::Query { .. } => {
+                self.inline_query_draft = Some(InlineQueryDraft {
+                    parsed: parse_inline_query_payload(&raw).ok(),
+                    raw,
+                });
+                InlineDraftKind::Query
+            }
+            HistoryPayload::Aggregation { .. } => {
+                self.inline_aggregation_draft = Some(InlineAggregationDraft {
+                    parsed: parse_inline_aggregation_payload(&raw).ok(),
+                    raw,
+                });
+                InlineDraftKind::Aggregation
+            }
+        };
+        self.active_inline_draft = Some(kind);
+        self.screen = Screen::Documents;
+        Ok(kind)
+    }
+
+    pub(crate) fn begin_history(&mut self, request_id: u64, entry: HistoryEntry) {
+        self.pending_history
+            .insert(request_id, (entry, Instant::now()));
+    }
+
+    /// Records a finished run. History is best effort, so failures only surface as a warning.
+    pub(crate) fn finish_history(&mut self, request_id: u64, result: &Result<Vec<Document>>) {
+        let Some((mut entry, started)) = self.pending_history.remove(&request_id) else {
+            return;
+        };
+        let outcome = match result {
+            Ok(documents) => Ok(documents.len()),
+            Err(error) => Err(format_error(error)),
+        };
+        entry.finish(started.elapsed(), outcome);
+        if let Err(error) = append_history(&self.paths, &self.storage.config.history, entry) {
+            self.warnings.push_back(format!(
+                "unable to record query history: {}",
+                format_error(&error)
+            ));
+        }
+    }
+
     pub(crate) fn export_results(&mut self) -> Result<()> {
         self.start_export_action(ExportAction::File)
     }
@@ -322,6 +476,9 @@ impl App {
     }
 
     pub(crate) fn start_export_action(&mut self, action: ExportAction) -> Result<()> {
+        if self.screen == Screen::DocumentDiff {
+            return self.export_document_diff(action);
+        }
         if self.export_target().is_err() {
             self.message = Some("export only available for query/aggregation results".to_string());
             return Ok(());
@@ -417,7 +574,7 @@ impl App {
             }
             DocumentResultSource::InlineQuery => ExportNameSource::InlineQuery,
             DocumentResultSource::InlineAggregation => ExportNameSource::InlineAggregation,
-            DocumentResultSource::Collection => {
+            DocumentResultSource::Collection | DocumentResultSource::Reference { .. } => {
                 anyhow::bail!("export only available for query/aggregation results")
             }
         };
@@ -426,11 +583,22 @@ impl App {
             .export_screen()
             .ok_or_else(|| anyhow::anyhow!("export only available for query/aggregation results"))?
         {
-            Screen::Documents => Ok(ExportTarget {
-                documents: self.documents.clone(),
-                source,
-                single_document: false,
-            }),
+            Screen::Documents => {
+                let selected = self.selected_documents();
+                if selected.is_empty() {
+                    Ok(ExportTarget {
+                        documents: self.documents.clone(),
+                        source,
+                        single_document: false,
+                    })
+                } else {
+                    Ok(ExportTarget {
+                        single_document: selected.len() == 1,
+                        documents: selected,
+                        source,
+                    })
+                }
+            }
             Screen::DocumentView => Ok(ExportTarget {
                 documents: vec![self.selected_document()?.clone()],
                 source,
@@ -471,7 +639,10 @@ impl App {
         let template = ConnectionSpec {
             name: "new_connection".to_string(),
             uri: "mongodb://localhost:27017".to_string(),
+            uri_command: None,
             default_database: Some("test".to_string()),
+            protection: ConnectionProtection::Open,
+            options: None,
         };
 
         let action = PendingEditorAction::AddConnection { scope, template };
@@ -506,8 +677,8 @@ impl App {
         if connection.name.trim().is_empty() {
             anyhow::bail!("connection name cannot be empty");
         }
-        if connection.uri.trim().is_empty() {
-            anyhow::bail!("connection uri cannot be empty");
+        if let Some(problem) = connection.uri_source_problem() {
+            anyhow::bail!("connection has {problem}");
         }
 
         if matches!(scope, ConnectionPersistenceScope::SessionOnly)
@@ -524,6 +695,7 @@ impl App {
         match scope {
             ConnectionPersistenceScope::SessionOnly => {
                 self.storage.config.connections.push(connection.clone());
+                self.session_connections.push(connection.clone());
                 self.connection_index = Some(self.storage.config.connections.len() - 1);
                 self.message = Some(format!(
                     "added connection '{}' (session only)",
@@ -624,6 +796,13 @@ impl App {
                 document,
             } => self
                 .edit_document_with_context(terminal, connection, database, collection, document),
+            PendingEditorAction::EditMany {
+                connection,
+                database,
+                collection,
+                documents,
+            } => self
+                .edit_documents_with_context(terminal, connection, database, collection, documents),
             PendingEditorAction::SaveQuery { template } => {
                 self.save_query_with_template(terminal, template)
             }
@@ -634,6 +813,9 @@ impl App {
             PendingEditorAction::RunInlineAggregation => {
                 self.run_inline_aggregation_with_editor(terminal)
             }
+            PendingEditorAction::EditPipelineStage { insert } => {
+                self.edit_pipeline_stage_with_editor(terminal, insert)
+            }
             PendingEditorAction::SaveInlineQuery { scope, draft } => {
                 self.save_inline_query_with_scope(terminal, scope, draft)
             }
@@ -670,6 +852,22 @@ impl App {
             collection,
             document,
         };
+        if let Some(required) = self.connection_confirmation() {
+            self.confirm = Some(ConfirmState {
+                prompt: format!(
+                    "insert document into {}.{} on protected connection '{required}'",
+                    spec.database, spec.collection
+                ),
+                action: ConfirmAction::InsertDocument { spec },
+                input: String::new(),
+                required: Some(required),
+            });
+            return Ok(());
+        }
+        self.perform_insert_document(spec)
+    }
+
+    pub(crate) fn perform_insert_document(&mut self, spec: DocumentInsertSpec) -> Result<()> {
         let inserted_id = self.runtime.block_on(self.executor.insert_document(
             &self.storage.config,
             self.write_guard(),
@@ -715,6 +913,28 @@ impl App {
             id: original_id,
             document: updated,
         };
+        if let Some(required) = self.connection_confirmation() {
+            self.confirm = Some(ConfirmState {
+                prompt: format!(
+                    "replace document {} in {}.{} on protected connection '{required}'",
+                    format_bson(&spec.id),
+                    spec.database,
+                    spec.collection
+                ),
+                action: ConfirmAction::ReplaceDocument { spec, id_changed },
+                input: String::new(),
+                required: Some(required),
+            });
+            return Ok(());
+        }
+        self.perform_replace_document(spec, id_changed)
+    }
+
+    pub(crate) fn perform_replace_document(
+        &mut self,
+        spec: DocumentReplaceSpec,
+        id_changed: bool,
+    ) -> Result<()> {
         self.runtime.block_on(self.executor.replace_document(
             &self.storage.config,
             self.write_guard(),
@@ -959,11 +1179,14 @@ impl App {
             .editor_command
             .as_deref()
             .ok_or_else(|| anyhow::anyhow!("editor command missing"))?;
-        let initial = self
-            .inline_aggregation_draft
-            .as_ref()
-            .map(|draft| draft.raw.clone())
-            .unwrap_or(render_inline_aggregation_template()?);
+        let initial = if let Some(modal) = &self.quick_aggregation_modal {
+            modal.text.clone()
+        } else {
+            self.inline_aggregation_draft
+                .as_ref()
+                .map(|draft| draft.raw.clone())
+                .unwrap_or(render_inline_aggregation_template()?)
+        };
         let contents = self.open_editor(terminal, editor, "inline_aggregation", &initial)?;
         if is_editor_cancelled(&contents, &initial) {
             self.message = Some("cancelled".to_string());
@@ -976,6 +1199,7 @@ impl App {
                     parsed: Some(payload.clone()),
                 });
                 self.active_inline_draft = Some(InlineDraftKind::Aggregation);
+                self.quick_aggregation_modal = None;
                 self.start_execute_inline_aggregation(payload)?;
             }
             Err(error) => {
@@ -1118,6 +1342,7 @@ impl App {
         self.document_result_source = DocumentResultSource::Collection;
         self.active_inline_draft = None;
         self.document_page = 0;
+        self.document_grid.sort = None;
         if let Err(error) = self.start_load_documents(None, DocumentLoadReason::Refresh) {
             self.set_error_message(&error);
             return Ok(());
@@ -1129,7 +1354,9 @@ impl App {
 
 #[cfg(test)]
 mod tests {
-    use lazycompass_core::{Config, ConnectionSpec, SavedAggregation, SavedQuery, SavedScope};
+    use lazycompass_core::{
+        Config, ConnectionProtection, ConnectionSpec, SavedAggregation, SavedQuery, SavedScope,
+    };
     use lazycompass_storage::StorageSnapshot;
     use std::fs;
     use std::path::PathBuf;
@@ -1145,9 +1372,8 @@ mod tests {
         StorageSnapshot {
             config: Config {
                 connections: vec![ConnectionSpec {
-                    name: "local".to_string(),
-                    uri: "mongodb://localhost:27017".to_string(),
                     default_database: Some("app".to_string()),
+                    ..App::test_connection("local")
                 }],
                 ..Config::default()
             },
@@ -1184,13 +1410,36 @@ mod tests {
 
         match app.confirm {
             Some(ConfirmState {
-                required: Some("delete"),
+                required: Some(ref required),
                 ..
-            }) => {}
+            }) if required == "delete" => {}
             _ => panic!("expected delete confirmation"),
         }
     }
 
+    #[test]
+    fn request_delete_document_respects_connection_protection() {
+        let mut app = app_with_document_context();
+        app.storage.config.connections[0].protection = ConnectionProtection::ReadOnly;
+        app.request_delete_document().expect("request delete");
+        assert!(app.confirm.is_none());
+        assert_eq!(
+            app.message.as_deref(),
+            Some("connection 'local' is read-only: delete documents is blocked")
+        );
+
+        app.message = None;
+        app.storage.config.connections[0].protection = ConnectionProtection::Confirm;
+        app.request_delete_document().expect("request delete");
+        match app.confirm {
+            Some(ConfirmState {
+                required: Some(ref required),
+                ..
+            }) if required == "local" => {}
+            _ => panic!("expected connection-name confirmation"),
+        }
+    }
+
     #[test]
     fn request_delete_document_is_blocked_without_dangerous_flag() {
         let mut app = App::test_app_with_storage(storage_with_context());
@@ -1315,6 +1564,78 @@ mod tests {
         assert_eq!(app.message.as_deref(), Some("cleared applied results"));
     }
 
+    fn history_entry(collection: &str, filter: &str) -> HistoryEntry {
+        HistoryEntry::started(
+            Some("local".to_string()),
+            "app".to_string(),
+            collection.to_string(),
+            None,
+            HistoryPayload::Query {
+                filter: Some(filter.to_string()),
+                projection: None,
+                sort: None,
+                limit: Some(5),
+            },
+        )
+    }
+
+    #[test]
+    fn show_history_lists_current_collection_newest_first() {
+        let mut app = app_with_document_context();
+        app.show_history().expect("show history");
+        assert_eq!(app.screen, Screen::Documents);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("no history for this collection")
+        );
+
+        let config = app.storage.config.history.clone();
+        for entry in [
+            history_entry("users", r#"{"n":1}"#),
+            history_entry("orders", r#"{"n":2}"#),
+            history_entry("users", r#"{"n":3}"#),
+        ] {
+            append_history(&app.paths, &config, entry).expect("append history");
+        }
+
+        app.show_history().expect("show history");
+        assert_eq!(app.screen, Screen::History);
+        assert_eq!(app.history_index, Some(0));
+        let filters: Vec<&HistoryPayload> = app
+            .history_items
+            .iter()
+            .map(|entry| &entry.payload)
+            .collect();
+        assert_eq!(filters.len(), 2);
+        assert!(matches!(
+            filters[0],
+            HistoryPayload::Query { filter: Some(filter), .. } if filter == r#"{"n":3}"#
+        ));
+    }
+
+    #[test]
+    fn promote_history_entry_saves_it_as_inline_draft() {
+        let mut app = app_with_document_context();
+        app.history_items = vec![history_entry("users", r#"{"active":true}"#)];
+        app.history_index = Some(0);
+        app.screen = Screen::History;
+        let mut terminal = test_terminal();
+
+        app.promote_history_entry(&mut terminal)
+            .expect("promote history entry");
+
+        assert_eq!(app.screen, Screen::SaveQueryScopeSelect);
+        assert_eq!(app.active_inline_draft, Some(InlineDraftKind::Query));
+        assert!(matches!(
+            app.query_save_source,
+            QuerySaveSource::InlineDraft(InlineQueryPayload {
+                filter: Some(ref filter),
+                limit: Some(5),
+                ..
+            }) if filter == r#"{"active":true}"#
+        ));
+    }
+
     #[test]
     fn run_saved_query_handles_empty_and_present_queries() {
         let mut app = app_with_document_context();
@@ -1328,6 +1649,7 @@ mod tests {
             projection: None,
             sort: None,
             limit: None,
+            tags: Vec::new(),
         });
         app.message = None;
         app.run_saved_query().expect("run saved query");
@@ -1345,6 +1667,7 @@ mod tests {
             id: "orders_by_user".to_string(),
             scope: SavedScope::Shared,
             pipeline: "[]".to_string(),
+            tags: Vec::new(),
         });
         app.message = None;
         app.run_saved_aggregation().expect("run saved agg");
@@ -1370,7 +1693,10 @@ mod tests {
         app.upsert_connection(ConnectionSpec {
             name: "local".to_string(),
             uri: "mongodb://override".to_string(),
+            uri_command: None,
             default_database: Some("override".to_string()),
+            protection: ConnectionProtection::Open,
+            options: None,
         });
 
         assert_eq!(app.storage.config.connections.len(), 1);
@@ -1530,6 +1856,60 @@ mod tests {
         let _ = fs::remove_file(path);
     }
 
+    #[test]
+    fn typed_confirmation_accepts_names_with_q_and_requires_exact_case() {
+        let mut app = app_with_document_context();
+        let mut terminal = test_terminal();
+        let path = canonical_temp_dir().join(format!(
+            "lazycompass_confirm_name_{}_{}.json",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        ));
+        app.confirm = Some(ConfirmState {
+            prompt: "type the connection name".to_string(),
+            action: ConfirmAction::OverwriteExport {
+                path: path.clone(),
+                rendered: "[]".to_string(),
+            },
+            input: String::new(),
+            required: Some("sql-qa".to_string()),
+        });
+        let mut type_text = |app: &mut App, text: &str| {
+            for ch in text.chars() {
+                app.handle_confirm_key(
+                    KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE),
+                    &mut terminal,
+                )
+                .expect("type");
+            }
+            app.handle_confirm_key(
+                KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+                &mut terminal,
+            )
+            .expect("enter");
+        };
+
+        type_text(&mut app, "SQL-QA");
+        assert_eq!(
+            app.message.as_deref(),
+            Some("must type 'sql-qa' to confirm")
+        );
+        app.confirm
+            .as_mut()
+            .expect("still confirming")
+            .input
+            .clear();
+
+        type_text(&mut app, "sql-qa");
+        assert!(app.confirm.is_none());
+        assert_eq!(fs::read_to_string(&path).expect("read export"), "[]");
+
+        let _ = fs::remove_file(path);
+    }
+
     fn test_terminal() -> Terminal<CrosstermBackend<Stdout>> {
         Terminal::with_options(
             CrosstermBackend::new(stdout()),
@@ -9,11 +9,11 @@ impl App {
     ) -> Result<Self> {
         let runtime = Runtime::new().context("unable to start async runtime")?;
         let (load_tx, load_rx) = mpsc::channel();
-        let (theme, theme_warning) = resolve_theme(&storage.config);
+        let (theme, theme_warnings) = resolve_theme(&storage.config, &paths);
         let mut warnings = VecDeque::from(storage.warnings.clone());
-        if let Some(warning) = theme_warning {
-            warnings.push_back(warning);
-        }
+        warnings.extend(theme_warnings);
+        let (keymap, key_warnings) = KeyMap::resolve(&storage.config.keys);
+        warnings.extend(key_warnings);
         let connection_index = if storage.config.connections.is_empty() {
             None
         } else {
@@ -25,14 +25,22 @@ impl App {
             None
         };
 
+        let storage_fingerprint = storage_fingerprint(&paths, &storage.config);
+
         Ok(Self {
             paths,
             storage,
+            storage_fingerprint,
+            storage_checked_at: Instant::now(),
+            storage_reload_pending: None,
+            session_connections: Vec::new(),
             executor: MongoExecutor::new(),
             runtime,
             theme,
+            keymap,
             write_enabled,
             allow_pipeline_writes,
+            allow_insecure: false,
             screen: Screen::Connections,
             connection_index,
             database_items: Vec::new(),
@@ -45,15 +53,33 @@ impl App {
             index_scroll: 0,
             documents: Vec::new(),
             document_index: None,
+            document_selection: DocumentSelection::default(),
             document_page: 0,
+            page_size: DEFAULT_PAGE_SIZE,
             document_lines: Vec::new(),
             document_scroll: 0,
-            last_g: false,
+            document_tree: DocumentTree::default(),
+            document_json_view: false,
+            date_hints: true,
+            document_grid: DocumentGrid::default(),
+            diff_mark: None,
+            document_diff: None,
+            pending_keys: Vec::new(),
+            count_prefix: None,
+            pending_mark: None,
+            marks: HashMap::new(),
+            jump_list: JumpList::default(),
+            pending_jump_index: None,
+            reference_stack: Vec::new(),
+            search: None,
+            command_line: None,
+            command_history: Vec::new(),
             help_visible: false,
             message,
             confirm: None,
             editor_prompt: None,
             path_prompt: None,
+            field_prompt: None,
             editor_command: None,
             warnings,
             load_tx,
@@ -67,6 +93,8 @@ impl App {
             saved_agg_load_id: None,
             inline_query_load_id: None,
             inline_agg_load_id: None,
+            count_load_id: None,
+            reference_load_id: None,
             database_state: LoadState::Idle,
             collection_state: LoadState::Idle,
             index_state: LoadState::Idle,
@@ -86,16 +114,23 @@ impl App {
             add_connection_scope_index: Some(0),
             inline_query_draft: None,
             inline_aggregation_draft: None,
+            pipeline_builder: None,
             active_inline_draft: None,
             quick_query_modal: None,
+            quick_aggregation_modal: None,
             query_save_source: QuerySaveSource::EmptyTemplate,
             aggregation_save_source: AggregationSaveSource::EmptyTemplate,
+            history_items: Vec::new(),
+            history_index: None,
+            pending_history: HashMap::new(),
+            pending_session: None,
         })
     }
 
     pub(crate) fn run(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
         loop {
             self.drain_load_results();
+            self.poll_storage_changes();
             terminal.draw(|frame| self.draw(frame))?;
             if self.cursor_visible() {
                 terminal.show_cursor().context("unable to show cursor")?;
@@ -126,6 +161,13 @@ impl App {
     }
 
     pub(crate) fn apply_load_result(&mut self, result: LoadResult) {
+        match &result {
+            LoadResult::SavedQuery { id, result, .. }
+            | LoadResult::SavedAggregation { id, result, .. }
+            | LoadResult::InlineQuery { id, result }
+            | LoadResult::InlineAggregation { id, result } => self.finish_history(*id, result),
+            _ => {}
+        }
         match result {
             LoadResult::Databases { id, result } => {
                 if self.database_load_id != Some(id) {
@@ -142,8 +184,10 @@ impl App {
                             Some(0)
                         };
                         self.database_state = LoadState::Idle;
+                        self.resume_session_after_databases();
                     }
                     Err(error) => {
+                        self.pending_session = None;
                         let message = format_error(&error);
                         self.database_state = LoadState::Failed(message.clone());
                         self.message = Some(message);
@@ -165,8 +209,10 @@ impl App {
                             Some(0)
                         };
                         self.collection_state = LoadState::Idle;
+                        self.resume_session_after_collections();
                     }
                     Err(error) => {
+                        self.pending_session = None;
                         let message = format_error(&error);
                         self.collection_state = LoadState::Failed(message.clone());
                         self.message = Some(message);
@@ -208,6 +254,7 @@ impl App {
                 match result {
                     Ok(documents) => {
                         self.documents = documents;
+                        self.document_selection.clear();
                         self.active_inline_draft = None;
                         if self.documents.is_empty() && self.document_page > 0 {
                             if self.document_load_reason == DocumentLoadReason::NavigateNext {
@@ -255,6 +302,7 @@ impl App {
                 match result {
                     Ok(documents) => {
                         self.documents = documents;
+                        self.document_selection.clear();
                         self.document_index = if self.documents.is_empty() {
                             None
                         } else {
@@ -287,6 +335,7 @@ impl App {
                 match result {
                     Ok(documents) => {
                         self.documents = documents;
+                        self.document_selection.clear();
                         self.document_index = if self.documents.is_empty() {
                             None
                         } else {
@@ -319,6 +368,7 @@ impl App {
                 match result {
                     Ok(documents) => {
                         self.documents = documents;
+                        self.document_selection.clear();
                         self.document_index = if self.documents.is_empty() {
                             None
                         } else {
@@ -342,6 +392,14 @@ impl App {
                     }
                 }
             }
+            LoadResult::PipelinePreview { id, result } => self.apply_pipeline_preview(id, result),
+            LoadResult::Count { id, label, result } => self.apply_count_result(id, label, result),
+            LoadResult::Reference {
+                id,
+                target,
+                label,
+                result,
+            } => self.apply_reference_result(id, target, label, result),
             LoadResult::InlineAggregation { id, result } => {
                 if self.inline_agg_load_id != Some(id) {
                     return;
@@ -350,6 +408,7 @@ impl App {
                 match result {
                     Ok(documents) => {
                         self.documents = documents;
+                        self.document_selection.clear();
                         self.document_index = if self.documents.is_empty() {
                             None
                         } else {
@@ -387,12 +446,21 @@ impl App {
         if self.path_prompt.is_some() {
             return self.handle_path_prompt_key(key);
         }
+        if self.field_prompt.is_some() {
+            return self.handle_field_prompt_key(key);
+        }
         if self.confirm.is_some() {
             return self.handle_confirm_key(key, terminal);
         }
         if self.quick_query_modal.is_some() {
             return self.handle_quick_query_modal_key(key, terminal);
         }
+        if self.quick_aggregation_modal.is_some() {
+            return self.handle_quick_aggregation_modal_key(key, terminal);
+        }
+        if self.command_line.is_some() {
+            return self.handle_command_line_key(key, terminal);
+        }
 
         // Clear warnings and messages on any non-confirm keypress
         if !self.warnings.is_empty() {
@@ -400,13 +468,18 @@ impl App {
         }
         self.message = None;
 
+        if self.handle_search_key(key) {
+            self.pending_keys.clear();
+            return Ok(false);
+        }
+
         if self.help_visible {
             if key.code == KeyCode::Esc {
                 self.help_visible = false;
-                self.last_g = false;
+                self.pending_keys.clear();
                 return Ok(false);
             }
-            if let Some(action) = action_for_key(key) {
+            if let Some(action) = self.keymap.action_for_key(key) {
                 match action {
                     KeyAction::ToggleHelp => {
                         self.help_visible = false;
@@ -415,29 +488,56 @@ impl App {
                     _ => {}
                 }
             }
-            self.last_g = false;
+            self.pending_keys.clear();
+            return Ok(false);
+        }
+
+        if let Some(pending) = self.pending_mark.take() {
+            self.pending_keys.clear();
+            self.count_prefix = None;
+            if let Err(error) = self.handle_mark_key(pending, key) {
+                self.set_error_message(&error);
+            }
+            return Ok(false);
+        }
+
+        if self.push_count_digit(key) {
             return Ok(false);
         }
 
         if let Some(action) = self.resolve_action(key) {
-            return self.apply_action(action, terminal);
+            let count = self.count_prefix.take();
+            let quit = self.apply_counted_action(action, count, terminal)?;
+            if self.active_search().is_none() {
+                self.search = None;
+            }
+            return Ok(quit);
+        }
+        if self.pending_keys.is_empty() {
+            self.count_prefix = None;
         }
 
         Ok(false)
     }
 
     pub(crate) fn resolve_action(&mut self, key: KeyEvent) -> Option<KeyAction> {
-        if key.code == KeyCode::Char('g') && key.modifiers == KeyModifiers::NONE {
-            if self.last_g {
-                self.last_g = false;
-                return Some(KeyAction::GoTop);
+        match self
+            .keymap
+            .lookup(&self.pending_keys, KeyChord::from_event(key))
+        {
+            KeyMatch::Action(action) => {
+                self.pending_keys.clear();
+                Some(action)
+            }
+            KeyMatch::Pending(sequence) => {
+                self.pending_keys = sequence;
+                None
+            }
+            KeyMatch::None => {
+                self.pending_keys.clear();
+                None
             }
-            self.last_g = true;
-            return None;
         }
-
-        self.last_g = false;
-        action_for_key(key)
     }
 
     pub(crate) fn apply_action(
@@ -453,23 +553,87 @@ impl App {
             KeyAction::Forward => self.go_forward(terminal)?,
             KeyAction::GoTop => self.go_top(),
             KeyAction::GoBottom => self.go_bottom(),
-            KeyAction::NextPage => self.next_page()?,
-            KeyAction::PreviousPage => self.previous_page()?,
+            KeyAction::NextPage => self.next_page(1)?,
+            KeyAction::PreviousPage => self.previous_page(1)?,
+            KeyAction::Insert | KeyAction::Edit if self.screen == Screen::PipelineBuilder => {
+                self.edit_pipeline_stage(terminal, action == KeyAction::Insert)?
+            }
+            KeyAction::Delete | KeyAction::ToggleSelect
+                if self.screen == Screen::PipelineBuilder =>
+            {
+                self.apply_pipeline_action(action)
+            }
+            KeyAction::RunInlineAggregation if self.screen == Screen::PipelineBuilder => {
+                self.run_pipeline_builder()?
+            }
+            KeyAction::SaveAggregation if self.screen == Screen::PipelineBuilder => {
+                self.save_pipeline_builder()?
+            }
             KeyAction::Insert => self.insert_document(terminal)?,
+            KeyAction::Edit if self.screen == Screen::History => {
+                self.edit_history_entry(terminal)?
+            }
             KeyAction::Edit => self.edit_document(terminal)?,
             KeyAction::Delete => self.request_delete_document()?,
+            KeyAction::MarkDiff => self.toggle_diff_mark()?,
+            KeyAction::ShowDiff => self.show_document_diff()?,
             KeyAction::ExportResults => self.export_results()?,
             KeyAction::CopyResults => self.copy_results()?,
+            KeyAction::SaveQuery | KeyAction::SaveAggregation if self.screen == Screen::History => {
+                self.promote_history_entry(terminal)?
+            }
             KeyAction::SaveQuery => self.save_query(terminal)?,
             KeyAction::SaveAggregation => self.save_aggregation(terminal)?,
             KeyAction::RunInlineQuery => self.run_inline_query()?,
-            KeyAction::RunInlineAggregation => self.run_inline_aggregation(terminal)?,
+            KeyAction::RunInlineAggregation => self.run_inline_aggregation()?,
             KeyAction::RunSavedQuery => self.run_saved_query()?,
             KeyAction::RunSavedAggregation => self.run_saved_aggregation()?,
             KeyAction::ShowIndexes => self.show_indexes()?,
             KeyAction::ClearApplied => self.clear_applied_documents()?,
             KeyAction::ToggleHelp => self.help_visible = !self.help_visible,
             KeyAction::AddConnection => self.start_add_connection()?,
+            KeyAction::ShowHistory => self.show_history()?,
+            KeyAction::Search => self.start_search(),
+            KeyAction::SearchNext => self.jump_to_match(true),
+            KeyAction::SearchPrevious => self.jump_to_match(false),
+            KeyAction::ClearSearch => self.search = None,
+            KeyAction::CommandLine => self.start_command_line(),
+            KeyAction::SetMark => self.start_mark(PendingMark::Set),
+            KeyAction::JumpToMark => self.start_mark(PendingMark::Jump),
+            KeyAction::JumpBack => self.jump_back()?,
+            KeyAction::JumpForward => self.jump_forward()?,
+            KeyAction::FollowReference if self.screen == Screen::DocumentView => {
+                self.follow_reference()?
+            }
+            KeyAction::FollowReference => {}
+            KeyAction::ReferenceBack => {
+                if !self.reference_back() {
+                    self.message = Some("no followed reference to go back from".to_string());
+                }
+            }
+            KeyAction::ToggleTree if self.screen == Screen::DocumentDiff => {
+                self.toggle_diff_layout()
+            }
+            KeyAction::ToggleTree => self.toggle_document_view_mode(),
+            KeyAction::ToggleGrid
+            | KeyAction::ColumnLeft
+            | KeyAction::ColumnRight
+            | KeyAction::MoveColumnLeft
+            | KeyAction::MoveColumnRight
+            | KeyAction::NarrowColumn
+            | KeyAction::WidenColumn
+            | KeyAction::HideColumn
+            | KeyAction::ShowColumns
+            | KeyAction::SortColumn => self.apply_grid_action(action),
+            KeyAction::EditField => self.edit_field()?,
+            KeyAction::AddField => self.add_field()?,
+            KeyAction::RemoveField => self.request_remove_field()?,
+            KeyAction::OpenPipelineBuilder => self.open_pipeline_builder()?,
+            KeyAction::MoveStageUp | KeyAction::MoveStageDown => self.apply_pipeline_action(action),
+            KeyAction::VisualSelect
+            | KeyAction::ToggleSelect
+            | KeyAction::SelectAll
+            | KeyAction::ClearSelection => self.apply_selection_action(action),
         }
 
         Ok(false)
@@ -484,9 +648,10 @@ impl App {
             return Ok(false);
         };
 
-        if let Some(required) = confirm.required {
+        if let Some(required) = confirm.required.clone() {
+            // Only Esc cancels here: the required word may be a connection name containing `q`.
             match key.code {
-                KeyCode::Esc | KeyCode::Char('q') => {
+                KeyCode::Esc => {
                     self.message = Some("cancelled".to_string());
                 }
                 KeyCode::Backspace => {
@@ -494,7 +659,7 @@ impl App {
                     self.confirm = Some(confirm);
                 }
                 KeyCode::Enter => {
-                    if confirm.input.trim().eq_ignore_ascii_case(required) {
+                    if confirm.input.trim() == required {
                         if let Err(error) = self.perform_confirm_action(confirm.action) {
                             self.set_error_message(&error);
                         }
@@ -514,7 +679,7 @@ impl App {
                 }
             }
 
-            self.last_g = false;
+            self.pending_keys.clear();
             return Ok(false);
         }
 
@@ -532,7 +697,7 @@ impl App {
             }
         }
 
-        self.last_g = false;
+        self.pending_keys.clear();
         Ok(false)
     }
 
@@ -575,7 +740,7 @@ impl App {
             }
         }
 
-        self.last_g = false;
+        self.pending_keys.clear();
         Ok(false)
     }
 
@@ -611,7 +776,7 @@ impl App {
             }
         }
 
-        self.last_g = false;
+        self.pending_keys.clear();
         Ok(false)
     }
 
@@ -624,12 +789,35 @@ impl App {
             return Ok(false);
         };
 
+        if modal.completion.is_some() {
+            match key.code {
+                KeyCode::Esc => modal.completion = None,
+                KeyCode::Tab | KeyCode::Enter => modal.accept_completion(),
+                KeyCode::Up => modal.move_completion(-1),
+                KeyCode::Down => modal.move_completion(1),
+                KeyCode::Backspace => {
+                    modal.backspace();
+                    modal.refresh_completion();
+                }
+                KeyCode::Char(ch) if !ch.is_control() => {
+                    modal.insert_char(ch);
+                    modal.refresh_completion();
+                }
+                _ => modal.completion = None,
+            }
+            self.quick_query_modal = Some(modal);
+            self.pending_keys.clear();
+            return Ok(false);
+        }
+
         match key.code {
             KeyCode::Esc => {
                 self.message = Some("cancelled".to_string());
             }
             KeyCode::Tab => {
-                modal.focus_next();
+                if !modal.start_completion() {
+                    modal.focus_next();
+                }
                 self.quick_query_modal = Some(modal);
             }
             KeyCode::BackTab => {
@@ -654,7 +842,7 @@ impl App {
                 self.quick_query_modal = Some(modal);
                 let action = PendingEditorAction::RunInlineQuery;
                 let Some(_) = self.ensure_editor_command(action.clone())? else {
-                    self.last_g = false;
+                    self.pending_keys.clear();
                     return Ok(false);
                 };
                 if let Err(error) = self.perform_editor_action(action, terminal) {
@@ -696,14 +884,17 @@ impl App {
             }
         }
 
-        self.last_g = false;
+        self.pending_keys.clear();
         Ok(false)
     }
 
     pub(crate) fn cursor_visible(&self) -> bool {
         self.quick_query_modal.is_some()
+            || self.quick_aggregation_modal.is_some()
+            || self.command_line.is_some()
             || self.editor_prompt.is_some()
             || self.path_prompt.is_some()
+            || self.field_prompt.is_some()
     }
 
     pub(crate) fn perform_confirm_action(&mut self, action: ConfirmAction) -> Result<()> {
@@ -742,6 +933,19 @@ impl App {
                 write_rendered_output(&path, &rendered)?;
                 self.message = Some(format!("exported results to {}", path.display()));
             }
+            ConfirmAction::InsertDocument { spec } => self.perform_insert_document(spec)?,
+            ConfirmAction::ReplaceDocument { spec, id_changed } => {
+                self.perform_replace_document(spec, id_changed)?
+            }
+            ConfirmAction::UpdateDocument { spec, summary } => {
+                self.perform_update_document(spec, summary)?
+            }
+            ConfirmAction::DeleteDocuments { spec } => self.perform_delete_documents(spec)?,
+            ConfirmAction::ReplaceDocuments { specs } => self.perform_replace_documents(specs)?,
+            ConfirmAction::RunAggregation { spec, saved_name } => {
+                self.spawn_aggregation(spec, saved_name)
+            }
+            ConfirmAction::RestoreSession { session } => self.restore_session(session),
         }
         Ok(())
     }
@@ -767,9 +971,8 @@ mod tests {
         let storage = StorageSnapshot {
             config: Config {
                 connections: vec![ConnectionSpec {
-                    name: "local".to_string(),
-                    uri: "mongodb://localhost:27017".to_string(),
                     default_database: Some("app".to_string()),
+                    ..App::test_connection("local")
                 }],
                 ..Config::default()
             },
@@ -814,6 +1017,21 @@ mod tests {
         assert_eq!(modal.focus, QuickQueryField::Filter);
     }
 
+    #[test]
+    fn run_inline_aggregation_opens_quick_aggregation_modal_with_draft() {
+        let mut app = app_with_document_context();
+        app.screen = Screen::Documents;
+        app.inline_aggregation_draft = Some(InlineAggregationDraft {
+            raw: r#"[{"$match": {}}]"#.to_string(),
+            parsed: None,
+        });
+        app.run_inline_aggregation().expect("open modal");
+
+        let modal = app.quick_aggregation_modal.expect("modal");
+        assert_eq!(modal.text, r#"[{"$match": {}}]"#);
+        assert_eq!(modal.validation, Ok(1));
+    }
+
     #[test]
     fn apply_load_result_ignores_stale_database_payloads() {
         let mut app = test_app();
@@ -896,6 +1114,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn apply_load_result_records_pending_history_entry() {
+        let mut app = test_app();
+        app.inline_query_load_id = Some(5);
+        app.begin_history(
+            5,
+            HistoryEntry::started(
+                Some("local".to_string()),
+                "app".to_string(),
+                "users".to_string(),
+                None,
+                HistoryPayload::Query {
+                    filter: Some("{}".to_string()),
+                    projection: None,
+                    sort: None,
+                    limit: Some(20),
+                },
+            ),
+        );
+
+        app.apply_load_result(LoadResult::InlineQuery {
+            id: 5,
+            result: Ok(vec![Document::new(), Document::new()]),
+        });
+
+        let entries = load_history(&app.paths).expect("load history");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].result_count, Some(2));
+        assert_eq!(entries[0].error, None);
+        assert!(app.pending_history.is_empty());
+    }
+
     #[test]
     fn apply_load_result_records_document_load_errors() {
         let mut app = test_app();
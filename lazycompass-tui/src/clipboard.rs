@@ -32,6 +32,24 @@ pub(crate) fn copy_to_clipboard(contents: &str) -> Result<()> {
     anyhow::bail!("clipboard copy failed: {}", errors.join("; "))
 }
 
+/// Clipboard strategy for this environment: native commands in the order they are tried,
+/// and whether OSC52 goes first. Reported by `lazycompass doctor`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardMethods {
+    pub prefers_osc52: bool,
+    pub native_commands: Vec<&'static str>,
+}
+
+pub fn clipboard_methods() -> ClipboardMethods {
+    ClipboardMethods {
+        prefers_osc52: prefers_osc52(),
+        native_commands: native_commands()
+            .into_iter()
+            .map(|(program, _)| program)
+            .collect(),
+    }
+}
+
 fn prefers_osc52() -> bool {
     std::env::var_os("SSH_TTY").is_some()
 }
@@ -0,0 +1,607 @@
+use super::*;
+
+use lazycompass_core::SessionScreen;
+use lazycompass_mongo::DocumentCountSpec;
+
+const MAX_COMMAND_HISTORY: usize = 100;
+const MAX_PAGE_SIZE: u64 = 1000;
+
+/// Built-in commands; every key action is also available under its `[keys]` name.
+const COMMANDS: &[&str] = &["use", "coll", "find", "sort", "export", "count", "set"];
+const SETTINGS: &[&str] = &["page_size", "date_hints"];
+const EXPORT_FORMATS: &[OutputFormat] = &[
+    OutputFormat::JsonPretty,
+    OutputFormat::Csv,
+    OutputFormat::Table,
+];
+
+/// A parsed `:` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Command {
+    Use(String),
+    Collection(String),
+    Find(String),
+    Sort(String),
+    Export {
+        format: Option<OutputFormat>,
+        path: Option<String>,
+    },
+    Count,
+    Set {
+        name: Option<String>,
+        value: Option<String>,
+    },
+    Action(KeyAction),
+}
+
+pub(crate) fn parse_command(input: &str) -> Result<Command> {
+    let input = input.trim().trim_start_matches(':').trim_start();
+    let (name, rest) = input
+        .split_once(char::is_whitespace)
+        .map_or((input, ""), |(name, rest)| (name, rest.trim()));
+    let required = |what: &str| -> Result<String> {
+        if rest.is_empty() {
+            anyhow::bail!(":{name} needs {what}");
+        }
+        Ok(rest.to_string())
+    };
+    let command = match name {
+        "" => anyhow::bail!("enter a command"),
+        "use" => Command::Use(required("a database name")?),
+        "coll" => Command::Collection(required("a collection name")?),
+        "find" => Command::Find(required("a filter")?),
+        "sort" => Command::Sort(required("a sort document")?),
+        "count" => Command::Count,
+        "export" => {
+            let mut words = rest.split_whitespace();
+            let format = words
+                .next()
+                .map(|label| {
+                    EXPORT_FORMATS
+                        .iter()
+                        .copied()
+                        .find(|format| format.label() == label)
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("unknown export format '{label}' (json, csv, table)")
+                        })
+                })
+                .transpose()?;
+            let path = words.collect::<Vec<_>>().join(" ");
+            Command::Export {
+                format,
+                path: (!path.is_empty()).then_some(path),
+            }
+        }
+        "set" => {
+            let mut words = rest.split_whitespace();
+            Command::Set {
+                name: words.next().map(str::to_string),
+                value: words.next().map(str::to_string),
+            }
+        }
+        "q" => Command::Action(KeyAction::Quit),
+        _ => Command::Action(
+            KeyAction::from_name(name)
+                .ok_or_else(|| anyhow::anyhow!("unknown command ':{name}'"))?,
+        ),
+    };
+    Ok(command)
+}
+
+/// Text typed after `:`, with history recall and Tab completion of the last word.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct CommandLineState {
+    pub(crate) input: String,
+    history_index: Option<usize>,
+    /// Input typed before stepping into history, restored when stepping back out.
+    draft: String,
+    pub(crate) completion: Option<CommandCompletion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CommandCompletion {
+    start: usize,
+    pub(crate) candidates: Vec<String>,
+    pub(crate) selected: usize,
+}
+
+impl CommandLineState {
+    fn recall(&mut self, history: &[String], older: bool) {
+        let index = match (self.history_index, older) {
+            (None, true) if !history.is_empty() => {
+                self.draft = self.input.clone();
+                history.len() - 1
+            }
+            (None, _) => return,
+            (Some(index), true) => index.saturating_sub(1),
+            (Some(index), false) if index + 1 < history.len() => index + 1,
+            (Some(_), false) => {
+                self.history_index = None;
+                self.input = std::mem::take(&mut self.draft);
+                return;
+            }
+        };
+        self.history_index = Some(index);
+        self.input = history[index].clone();
+    }
+
+    fn cycle_completion(&mut self, candidates: impl FnOnce(&str) -> Vec<String>, delta: isize) {
+        let completion = match self.completion.take() {
+            Some(mut completion) => {
+                let len = completion.candidates.len() as isize;
+                completion.selected =
+                    (completion.selected as isize + delta).rem_euclid(len) as usize;
+                completion
+            }
+            None => {
+                let start = self
+                    .input
+                    .rfind(char::is_whitespace)
+                    .map_or(0, |index| index + 1);
+                let candidates = candidates(&self.input);
+                if candidates.is_empty() {
+                    return;
+                }
+                CommandCompletion {
+                    start,
+                    candidates,
+                    selected: 0,
+                }
+            }
+        };
+        self.input.truncate(completion.start);
+        self.input
+            .push_str(&completion.candidates[completion.selected]);
+        self.completion = Some(completion);
+    }
+}
+
+impl App {
+    pub(crate) fn start_command_line(&mut self) {
+        self.command_line = Some(CommandLineState::default());
+    }
+
+    pub(crate) fn handle_command_line_key(
+        &mut self,
+        key: KeyEvent,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<bool> {
+        let Some(mut line) = self.command_line.take() else {
+            return Ok(false);
+        };
+        self.pending_keys.clear();
+        if !matches!(key.code, KeyCode::Tab | KeyCode::BackTab) {
+            line.completion = None;
+        }
+
+        match key.code {
+            KeyCode::Esc => return Ok(false),
+            KeyCode::Backspace if line.input.is_empty() => return Ok(false),
+            KeyCode::Enter => {
+                let input = line.input.trim().to_string();
+                if input.is_empty() {
+                    return Ok(false);
+                }
+                if self.command_history.last() != Some(&input) {
+                    self.command_history.push(input.clone());
+                    if self.command_history.len() > MAX_COMMAND_HISTORY {
+                        self.command_history.remove(0);
+                    }
+                }
+                return match self.run_command(&input, terminal) {
+                    Ok(quit) => Ok(quit),
+                    Err(error) => {
+                        self.set_error_message(&error);
+                        Ok(false)
+                    }
+                };
+            }
+            KeyCode::Backspace => {
+                line.input.pop();
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                let delta = if key.code == KeyCode::Tab { 1 } else { -1 };
+                line.cycle_completion(|input| self.command_candidates(input), delta);
+            }
+            KeyCode::Up => line.recall(&self.command_history, true),
+            KeyCode::Down => line.recall(&self.command_history, false),
+            KeyCode::Char(ch)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                line.input.push(ch);
+            }
+            _ => {}
+        }
+        self.command_line = Some(line);
+        Ok(false)
+    }
+
+    /// Completions for the last word of `input`: command names first, then the argument the
+    /// command expects.
+    pub(crate) fn command_candidates(&self, input: &str) -> Vec<String> {
+        let (head, word) = input
+            .rsplit_once(char::is_whitespace)
+            .map_or(("", input), |(head, word)| (head.trim(), word));
+        let options: Vec<String> = match head {
+            "" => COMMANDS
+                .iter()
+                .map(|name| name.to_string())
+                .chain(KeyAction::all().map(|action| action.name().to_string()))
+                .collect(),
+            "use" => self.database_items.clone(),
+            "coll" => self.collection_items.clone(),
+            "export" => EXPORT_FORMATS
+                .iter()
+                .map(|format| format.label().to_string())
+                .collect(),
+            "set" => SETTINGS.iter().map(|name| name.to_string()).collect(),
+            _ => Vec::new(),
+        };
+        let mut candidates: Vec<String> = options
+            .into_iter()
+            .filter(|option| option.starts_with(word))
+            .collect();
+        candidates.dedup();
+        candidates
+    }
+
+    /// Runs one command line. Returns `true` when the command quits the app.
+    pub(crate) fn run_command(
+        &mut self,
+        input: &str,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<bool> {
+        match parse_command(input)? {
+            Command::Use(database) => {
+                let before = self.current_location();
+                self.open_namespace(database, None)?;
+                if let Some(before) = before {
+                    self.record_jump(before);
+                }
+            }
+            Command::Collection(collection) => {
+                let database = self
+                    .selected_database()
+                    .ok_or_else(|| anyhow::anyhow!("select a database first with :use"))?
+                    .to_string();
+                let before = self.current_location();
+                self.open_namespace(database, Some(collection))?;
+                if let Some(before) = before {
+                    self.record_jump(before);
+                }
+            }
+            Command::Find(filter) => self.run_query_command(Some(filter), None)?,
+            Command::Sort(sort) => self.run_query_command(None, Some(sort))?,
+            Command::Export { format, path } => self.run_export_command(format, path)?,
+            Command::Count => self.start_count_documents()?,
+            Command::Set { name, value } => self.run_set_command(name, value)?,
+            Command::Action(action) => return self.apply_action(action, terminal),
+        }
+        Ok(false)
+    }
+
+    /// Walks down to a database (and collection) of the current connection as each list loads,
+    /// the same way a restored session does. Names missing from a list that is already loaded
+    /// fail here, without leaving the current screen.
+    fn open_namespace(&mut self, database: String, collection: Option<String>) -> Result<()> {
+        let connection = self
+            .selected_connection()
+            .ok_or_else(|| anyhow::anyhow!("select a connection"))?
+            .name
+            .clone();
+        if !self.database_items.is_empty() && !self.database_items.contains(&database) {
+            anyhow::bail!("unknown database '{database}'");
+        }
+        if let Some(collection) = &collection
+            && !self.collection_items.is_empty()
+            && !self.collection_items.contains(collection)
+        {
+            anyhow::bail!("unknown collection '{collection}'");
+        }
+        let screen = if collection.is_some() {
+            SessionScreen::Documents
+        } else {
+            SessionScreen::Collections
+        };
+        self.restore_session(lazycompass_core::SessionState {
+            connection,
+            database: Some(database),
+            collection,
+            screen,
+            document_page: 0,
+            inline_query: None,
+            inline_aggregation: None,
+            active_inline_draft: None,
+        });
+        Ok(())
+    }
+
+    /// Re-runs the inline query with a new filter or sort, keeping the rest of the draft.
+    fn run_query_command(&mut self, filter: Option<String>, sort: Option<String>) -> Result<()> {
+        let _ = self.selected_context()?;
+        let mut modal = QuickQueryModalState::from_draft(self.inline_query_draft.as_ref());
+        if let Some(filter) = filter {
+            modal.filter = filter;
+        }
+        if let Some(sort) = sort {
+            modal.sort = sort;
+        }
+        let payload = modal.build_payload()?;
+        self.inline_query_draft = Some(InlineQueryDraft {
+            raw: modal.rendered_contents(),
+            parsed: Some(payload.clone()),
+        });
+        self.active_inline_draft = Some(InlineDraftKind::Query);
+        self.start_execute_inline_query(payload)?;
+        self.screen = Screen::Documents;
+        Ok(())
+    }
+
+    /// `:export` alone opens the format list; with a format it renders straight away and
+    /// writes to the given path, or asks for one.
+    fn run_export_command(
+        &mut self,
+        format: Option<OutputFormat>,
+        path: Option<String>,
+    ) -> Result<()> {
+        let Some(format) = format else {
+            return self.export_results();
+        };
+        let target = self.export_target()?;
+        let rendered = render_documents(format, &target.documents)?;
+        let prompt = PathPromptState {
+            prompt: "export path".to_string(),
+            input: path.clone().unwrap_or_else(|| {
+                suggested_export_filename(&target.source, format, target.single_document)
+            }),
+            rendered,
+        };
+        if path.is_some() {
+            return self.submit_export_path(prompt);
+        }
+        self.path_prompt = Some(prompt);
+        self.message = Some("enter export path".to_string());
+        Ok(())
+    }
+
+    /// Counts the documents matching the inline query filter, or the whole collection.
+    fn start_count_documents(&mut self) -> Result<()> {
+        let (connection, database, collection) = self.selected_context()?;
+        let filter = match self.document_result_source {
+            DocumentResultSource::InlineQuery => self
+                .inline_query_draft
+                .as_ref()
+                .and_then(|draft| draft.parsed.as_ref())
+                .and_then(|payload| payload.filter.clone()),
+            _ => None,
+        };
+        let label = match &filter {
+            Some(filter) => format!("{database}.{collection} matching {filter}"),
+            None => format!("{database}.{collection}"),
+        };
+        let spec = DocumentCountSpec {
+            connection: Some(connection),
+            database,
+            collection,
+            filter,
+        };
+        let config = self.storage.config.clone();
+        let request_id = self.next_load_id();
+        self.count_load_id = Some(request_id);
+        self.message = Some(format!("counting {label}..."));
+        let sender = self.load_tx.clone();
+        self.runtime.spawn(async move {
+            let executor = MongoExecutor::new();
+            let result = executor.count_documents(&config, &spec).await;
+            let _ = sender.send(LoadResult::Count {
+                id: request_id,
+                label,
+                result,
+            });
+        });
+        Ok(())
+    }
+
+    pub(crate) fn apply_count_result(&mut self, id: u64, label: String, result: Result<u64>) {
+        if self.count_load_id != Some(id) {
+            return;
+        }
+        self.count_load_id = None;
+        match result {
+            Ok(count) => self.message = Some(format!("{count} documents in {label}")),
+            Err(error) => self.set_error_message(&error),
+        }
+    }
+
+    fn run_set_command(&mut self, name: Option<String>, value: Option<String>) -> Result<()> {
+        match (name.as_deref(), value) {
+            (None, _) => {
+                self.message = Some(format!(
+                    "page_size={} date_hints={}",
+                    self.page_size,
+                    on_off(self.date_hints)
+                ));
+                Ok(())
+            }
+            (Some("page_size"), None) => {
+                self.message = Some(format!("page_size={}", self.page_size));
+                Ok(())
+            }
+            (Some("page_size"), Some(value)) => self.set_page_size(&value),
+            (Some("date_hints"), value) => {
+                self.date_hints = match value.as_deref() {
+                    None => self.date_hints,
+                    Some("on" | "true") => true,
+                    Some("off" | "false") => false,
+                    Some(_) => anyhow::bail!("date_hints must be on or off"),
+                };
+                self.message = Some(format!("date_hints={}", on_off(self.date_hints)));
+                Ok(())
+            }
+            (Some(name), _) => anyhow::bail!("unknown setting '{name}'"),
+        }
+    }
+
+    fn set_page_size(&mut self, value: &str) -> Result<()> {
+        let page_size = value
+            .parse::<u64>()
+            .ok()
+            .filter(|size| (1..=MAX_PAGE_SIZE).contains(size))
+            .ok_or_else(|| anyhow::anyhow!("page_size must be between 1 and {MAX_PAGE_SIZE}"))?;
+        self.page_size = page_size;
+        self.message = Some(format!("page_size={page_size}"));
+        if self.screen == Screen::Documents
+            && self.document_result_source == DocumentResultSource::Collection
+        {
+            self.document_page = 0;
+            self.start_load_documents(None, DocumentLoadReason::Refresh)?;
+        }
+        Ok(())
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "on" } else { "off" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_builtin_commands_and_action_names() {
+        assert_eq!(
+            parse_command(":use  shop").expect("use"),
+            Command::Use("shop".to_string())
+        );
+        assert_eq!(
+            parse_command(r#"find {"status": "open"}"#).expect("find"),
+            Command::Find(r#"{"status": "open"}"#.to_string())
+        );
+        assert_eq!(
+            parse_command("export csv out dir/orders.csv").expect("export"),
+            Command::Export {
+                format: Some(OutputFormat::Csv),
+                path: Some("out dir/orders.csv".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_command("show_history").expect("action"),
+            Command::Action(KeyAction::ShowHistory)
+        );
+        assert_eq!(
+            parse_command("q").expect("quit"),
+            Command::Action(KeyAction::Quit)
+        );
+        assert_eq!(
+            parse_command("coll").expect_err("missing").to_string(),
+            ":coll needs a collection name"
+        );
+        assert!(parse_command("export xml").is_err());
+        assert!(parse_command("frobnicate").is_err());
+    }
+
+    #[test]
+    fn tab_completes_commands_and_arguments_and_history_recalls() {
+        let mut app = App::test_app();
+        app.database_items = vec!["shop".to_string(), "staging".to_string()];
+        let mut line = CommandLineState {
+            input: "us".to_string(),
+            ..CommandLineState::default()
+        };
+        line.cycle_completion(|input| app.command_candidates(input), 1);
+        assert_eq!(line.input, "use");
+
+        line.completion = None;
+        line.input = "use s".to_string();
+        line.cycle_completion(|input| app.command_candidates(input), 1);
+        assert_eq!(line.input, "use shop");
+        line.cycle_completion(|input| app.command_candidates(input), 1);
+        assert_eq!(line.input, "use staging");
+
+        let history = vec!["count".to_string(), "set page_size 50".to_string()];
+        let mut line = CommandLineState {
+            input: "fi".to_string(),
+            ..CommandLineState::default()
+        };
+        line.recall(&history, true);
+        assert_eq!(line.input, "set page_size 50");
+        line.recall(&history, true);
+        line.recall(&history, true);
+        assert_eq!(line.input, "count");
+        line.recall(&history, false);
+        line.recall(&history, false);
+        assert_eq!(line.input, "fi");
+    }
+
+    #[test]
+    fn set_page_size_validates_and_find_runs_the_inline_query() {
+        let mut app = App::test_app();
+        app.run_set_command(Some("page_size".to_string()), Some("100".to_string()))
+            .expect("set");
+        assert_eq!(app.page_size, 100);
+        assert!(
+            app.run_set_command(Some("page_size".to_string()), Some("0".to_string()))
+                .is_err()
+        );
+        assert!(
+            app.run_set_command(Some("theme".to_string()), Some("x".to_string()))
+                .is_err()
+        );
+        assert_eq!(app.page_size, 100);
+        app.run_set_command(Some("date_hints".to_string()), Some("off".to_string()))
+            .expect("set");
+        assert!(!app.date_hints);
+        assert!(app.date_hint_now().is_none());
+        assert!(
+            app.run_set_command(Some("date_hints".to_string()), Some("maybe".to_string()))
+                .is_err()
+        );
+
+        app.select_test_namespace("shop", "orders");
+        assert!(
+            app.run_query_command(Some(r#"{"status": "#.to_string()), None)
+                .is_err()
+        );
+        assert!(app.inline_query_draft.is_none());
+        app.run_query_command(Some(r#"{"status": "open"}"#.to_string()), None)
+            .expect("find");
+        let payload = app
+            .inline_query_draft
+            .as_ref()
+            .and_then(|draft| draft.parsed.clone())
+            .expect("draft");
+        assert!(payload.filter.expect("filter").contains("open"));
+        assert_eq!(payload.sort.as_deref(), Some(r#"{"_id":-1}"#));
+        assert_eq!(app.active_inline_draft, Some(InlineDraftKind::Query));
+        assert_eq!(app.screen, Screen::Documents);
+    }
+
+    #[test]
+    fn namespace_commands_check_loaded_lists_before_leaving() {
+        let mut app = App::test_app();
+        app.select_test_namespace("shop", "orders");
+        app.screen = Screen::Documents;
+
+        assert_eq!(
+            app.open_namespace("shop".to_string(), Some("ordrs".to_string()))
+                .expect_err("unknown collection")
+                .to_string(),
+            "unknown collection 'ordrs'"
+        );
+        assert_eq!(
+            app.open_namespace("shp".to_string(), None)
+                .expect_err("unknown database")
+                .to_string(),
+            "unknown database 'shp'"
+        );
+        assert_eq!(app.screen, Screen::Documents);
+        assert_eq!(app.selected_collection(), Some("orders"));
+
+        app.open_namespace("shop".to_string(), Some("orders".to_string()))
+            .expect("known collection");
+        assert_eq!(app.screen, Screen::Databases);
+    }
+}
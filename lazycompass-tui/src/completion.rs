@@ -0,0 +1,410 @@
+use super::*;
+use lazycompass_mongo::PIPELINE_STAGES;
+
+const MAX_SAMPLED_FIELDS: usize = 200;
+const MAX_COMPLETIONS: usize = 50;
+
+const QUERY_OPERATORS: &[&str] = &[
+    "$eq",
+    "$ne",
+    "$gt",
+    "$gte",
+    "$lt",
+    "$lte",
+    "$in",
+    "$nin",
+    "$and",
+    "$or",
+    "$nor",
+    "$not",
+    "$exists",
+    "$type",
+    "$regex",
+    "$options",
+    "$elemMatch",
+    "$size",
+    "$all",
+    "$expr",
+    "$mod",
+    "$text",
+    "$search",
+    "$where",
+    "$slice",
+];
+
+const ACCUMULATORS: &[&str] = &[
+    "$sum",
+    "$avg",
+    "$min",
+    "$max",
+    "$push",
+    "$addToSet",
+    "$first",
+    "$last",
+];
+
+/// A dotted field path seen in the sampled documents and the types it held.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FieldSample {
+    pub(crate) path: String,
+    pub(crate) types: Vec<&'static str>,
+}
+
+impl FieldSample {
+    fn hint(&self) -> String {
+        self.types.join("|")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CompletionItem {
+    pub(crate) label: String,
+    pub(crate) hint: String,
+}
+
+/// Completion popup for the word ending at the cursor of a quick query field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CompletionPopup {
+    /// Byte offset where the completed word starts.
+    pub(crate) start: usize,
+    pub(crate) items: Vec<CompletionItem>,
+    pub(crate) selected: usize,
+}
+
+/// Field paths of `documents` with their BSON types, in first-seen order. Embedded documents are
+/// listed themselves and flattened into dotted paths.
+pub(crate) fn sample_fields(documents: &[Document]) -> Vec<FieldSample> {
+    fn collect(document: &Document, prefix: &str, fields: &mut Vec<FieldSample>) {
+        for (key, value) in document {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            let type_name = bson_type_name(value);
+            if let Some(field) = fields.iter_mut().find(|field| field.path == path) {
+                if !field.types.contains(&type_name) {
+                    field.types.push(type_name);
+                }
+            } else if fields.len() < MAX_SAMPLED_FIELDS {
+                fields.push(FieldSample {
+                    path: path.clone(),
+                    types: vec![type_name],
+                });
+            }
+            if let Bson::Document(inner) = value {
+                collect(inner, &path, fields);
+            }
+        }
+    }
+
+    let mut fields = Vec::new();
+    for document in documents {
+        collect(document, "", &mut fields);
+    }
+    fields
+}
+
+/// Start of the field name or operator being typed before `cursor`, if any.
+fn word_start(text: &str, cursor: usize) -> Option<usize> {
+    let start = text[..cursor]
+        .char_indices()
+        .rev()
+        .take_while(|(_, ch)| ch.is_alphanumeric() || matches!(ch, '_' | '.' | '$'))
+        .last()
+        .map(|(index, _)| index)?;
+    Some(start)
+}
+
+/// Whether the word starting at `start` sits where a key goes: at the start, or after `{` or
+/// `,` with an optional opening quote. Values such as `"Ada"` or `"$amount"` are left alone.
+fn in_key_position(text: &str, start: usize) -> bool {
+    let before = text[..start].strip_suffix('"').unwrap_or(&text[..start]);
+    let before = before.trim_end();
+    before.is_empty() || before.ends_with('{') || before.ends_with(',')
+}
+
+/// Field paths matching `word` (prefix matches first), or operators when it starts with `$`.
+/// Stages and accumulators are only offered in the pipeline editor.
+fn completions(word: &str, fields: &[FieldSample], pipeline: bool) -> Vec<CompletionItem> {
+    let needle = word.to_lowercase();
+    let mut prefixed = Vec::new();
+    let mut contained = Vec::new();
+    let mut push = |label: &str, hint: String| {
+        let lower = label.to_lowercase();
+        if lower == needle {
+            return;
+        }
+        let item = CompletionItem {
+            label: label.to_string(),
+            hint,
+        };
+        if lower.starts_with(&needle) {
+            prefixed.push(item);
+        } else if lower.contains(&needle) {
+            contained.push(item);
+        }
+    };
+    if word.starts_with('$') {
+        let groups = [
+            (QUERY_OPERATORS, "query operator"),
+            (PIPELINE_STAGES, "stage"),
+            (ACCUMULATORS, "accumulator"),
+        ];
+        let groups = if pipeline { &groups[..] } else { &groups[..1] };
+        for &(names, hint) in groups {
+            for name in names {
+                push(name, hint.to_string());
+            }
+        }
+    } else {
+        for field in fields {
+            push(&field.path, field.hint());
+        }
+    }
+    prefixed.extend(contained);
+    prefixed.truncate(MAX_COMPLETIONS);
+    prefixed
+}
+
+impl QuickQueryModalState {
+    fn completion_source(&self) -> Option<(usize, Vec<CompletionItem>)> {
+        if self.focus == QuickQueryField::Limit {
+            return None;
+        }
+        let text = self.field_text(self.focus);
+        let cursor = self.cursor(self.focus);
+        let start = word_start(text, cursor).filter(|start| in_key_position(text, *start))?;
+        let items = completions(&text[start..cursor], &self.fields, false);
+        (!items.is_empty()).then_some((start, items))
+    }
+
+    /// Opens the completion popup for the word before the cursor, accepting a single candidate
+    /// right away. Returns `false` when there is nothing to complete.
+    pub(crate) fn start_completion(&mut self) -> bool {
+        let Some((start, items)) = self.completion_source() else {
+            return false;
+        };
+        self.completion = Some(CompletionPopup {
+            start,
+            items,
+            selected: 0,
+        });
+        if self
+            .completion
+            .as_ref()
+            .is_some_and(|popup| popup.items.len() == 1)
+        {
+            self.accept_completion();
+        }
+        true
+    }
+
+    /// Re-filters an open popup after an edit, closing it when nothing matches.
+    pub(crate) fn refresh_completion(&mut self) {
+        if self.completion.is_none() {
+            return;
+        }
+        self.completion = self
+            .completion_source()
+            .map(|(start, items)| CompletionPopup {
+                start,
+                items,
+                selected: 0,
+            });
+    }
+
+    pub(crate) fn move_completion(&mut self, delta: isize) {
+        if let Some(popup) = &mut self.completion {
+            let len = popup.items.len() as isize;
+            popup.selected = (popup.selected as isize + delta).rem_euclid(len) as usize;
+        }
+    }
+
+    /// Replaces the word before the cursor with the selected candidate, quoting it when the word
+    /// was typed without an opening quote.
+    pub(crate) fn accept_completion(&mut self) {
+        let Some(popup) = self.completion.take() else {
+            return;
+        };
+        let Some(item) = popup.items.get(popup.selected) else {
+            return;
+        };
+        let field = self.focus;
+        let cursor = self.cursor(field);
+        let text = self.field_text_mut(field);
+        let quoted = text[..popup.start].ends_with('"');
+        let replacement = if quoted {
+            item.label.clone()
+        } else {
+            format!("\"{}\"", item.label)
+        };
+        text.replace_range(popup.start..cursor, &replacement);
+        *self.cursor_mut(field) = popup.start + replacement.len();
+    }
+}
+
+impl QuickAggregationModalState {
+    /// Completes the `$` name before the cursor: a single candidate is inserted quoted, several
+    /// are extended to their shared prefix. Returns the candidates, empty when there are none.
+    pub(crate) fn complete_operator(&mut self) -> Vec<String> {
+        let Some(start) =
+            word_start(&self.text, self.cursor).filter(|start| in_key_position(&self.text, *start))
+        else {
+            return Vec::new();
+        };
+        let word = &self.text[start..self.cursor];
+        if !word.starts_with('$') {
+            return Vec::new();
+        }
+        let needle = word.to_lowercase();
+        let labels: Vec<String> = completions(word, &[], true)
+            .into_iter()
+            .map(|item| item.label)
+            .filter(|label| label.to_lowercase().starts_with(&needle))
+            .collect();
+        let replacement = match labels.as_slice() {
+            [] => return labels,
+            [label] if self.text[..start].ends_with('"') => label.clone(),
+            [label] => format!("\"{label}\""),
+            [first, rest @ ..] => {
+                let shared = rest.iter().fold(first.len(), |shared, label| {
+                    first
+                        .bytes()
+                        .zip(label.bytes())
+                        .take(shared)
+                        .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+                        .count()
+                });
+                first[..shared.max(word.len())].to_string()
+            }
+        };
+        self.replace(start, &replacement);
+        labels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn sample() -> Vec<FieldSample> {
+        let first = parse_json_document(
+            "--",
+            r#"{"_id": 1, "name": "Ada", "address": {"city": "London"}}"#,
+        )
+        .expect("document");
+        let second =
+            parse_json_document("--", r#"{"_id": 2, "name": null, "age": 36}"#).expect("document");
+        sample_fields(&[first, second])
+    }
+
+    fn modal(filter: &str) -> QuickQueryModalState {
+        let mut modal = QuickQueryModalState::from_draft(None);
+        modal.filter = filter.to_string();
+        modal.filter_cursor = filter.len();
+        modal.fields = sample();
+        modal
+    }
+
+    #[test]
+    fn sampled_fields_carry_dotted_paths_and_types() {
+        let fields = sample();
+        let hints: Vec<(&str, String)> = fields
+            .iter()
+            .map(|field| (field.path.as_str(), field.hint()))
+            .collect();
+        assert_eq!(
+            hints,
+            vec![
+                ("_id", "int".to_string()),
+                ("name", "string|null".to_string()),
+                ("address", "object".to_string()),
+                ("address.city", "string".to_string()),
+                ("age", "int".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tab_completes_fields_and_operators() {
+        let mut query = modal(r#"{"addr"#);
+        assert!(query.start_completion());
+        let popup = query.completion.as_ref().expect("popup");
+        let labels: Vec<&str> = popup.items.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(labels, vec!["address", "address.city"]);
+        query.move_completion(1);
+        query.accept_completion();
+        assert_eq!(query.filter, r#"{"address.city"#);
+
+        let mut query = modal(r#"{"age": {$elem"#);
+        assert!(query.start_completion());
+        assert!(query.completion.is_none());
+        assert_eq!(query.filter, r#"{"age": {"$elemMatch""#);
+
+        let mut query = modal(r#"{"$gr"#);
+        assert!(!query.start_completion());
+        assert_eq!(query.filter, r#"{"$gr"#);
+
+        assert!(!modal("{}").start_completion());
+        assert!(!modal(r#"{"name": "Ad"#).start_completion());
+        assert!(!modal(r#"{"zzz"#).start_completion());
+    }
+
+    #[test]
+    fn pipeline_editor_completes_stages_and_accumulators() {
+        let mut pipeline = QuickAggregationModalState {
+            text: r#"[{ $gr"#.to_string(),
+            cursor: 6,
+            validation: Ok(0),
+        };
+        assert_eq!(pipeline.complete_operator(), vec!["$graphLookup", "$group"]);
+        assert_eq!(pipeline.text, r#"[{ $gr"#);
+
+        pipeline.text = r#"[{ $grou"#.to_string();
+        pipeline.cursor = pipeline.text.len();
+        assert_eq!(pipeline.complete_operator(), vec!["$group"]);
+        assert_eq!(pipeline.text, r#"[{ "$group""#);
+
+        pipeline.text = r#"[{"$so"#.to_string();
+        pipeline.cursor = pipeline.text.len();
+        assert_eq!(pipeline.complete_operator(), vec!["$sort", "$sortByCount"]);
+        assert_eq!(pipeline.text, r#"[{"$sort"#);
+
+        pipeline.text = r#"[{"$unio"#.to_string();
+        pipeline.cursor = pipeline.text.len();
+        assert_eq!(pipeline.complete_operator(), vec!["$unionWith"]);
+        assert_eq!(pipeline.text, r#"[{"$unionWith"#);
+        assert!(pipeline.complete_operator().is_empty());
+
+        pipeline.text = r#"[{"$me"#.to_string();
+        pipeline.cursor = pipeline.text.len();
+        assert_eq!(pipeline.complete_operator(), vec!["$merge"]);
+
+        pipeline.text = r#"[{"$group": {"total": {"$sum": "$a"#.to_string();
+        pipeline.cursor = pipeline.text.len();
+        assert!(pipeline.complete_operator().is_empty());
+    }
+
+    #[test]
+    fn tab_moves_focus_when_nothing_completes() {
+        let mut app = App::test_app();
+        let mut query = modal(r#"{"name": "Ad"#);
+        query.focus = QuickQueryField::Filter;
+        app.quick_query_modal = Some(query);
+        let mut terminal = Terminal::with_options(
+            CrosstermBackend::new(std::io::stdout()),
+            ratatui::TerminalOptions {
+                viewport: ratatui::Viewport::Fixed(Rect::new(0, 0, 80, 24)),
+            },
+        )
+        .expect("terminal");
+        app.handle_quick_query_modal_key(
+            KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE),
+            &mut terminal,
+        )
+        .expect("tab");
+        let query = app.quick_query_modal.as_ref().expect("modal");
+        assert!(query.completion.is_none());
+        assert_eq!(query.focus, QuickQueryField::Projection);
+    }
+}
@@ -0,0 +1,529 @@
+use super::*;
+use lazycompass_mongo::{DocumentFindSpec, render_relaxed_extjson};
+use serde_json::{Value, json};
+
+/// Snapshot of a document marked with `M`, kept across reloads so it can be compared with a
+/// later version of the same record or with a different one.
+#[derive(Debug, Clone)]
+pub(crate) struct DiffMark {
+    document: Document,
+    label: String,
+}
+
+impl DiffMark {
+    pub(crate) fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum DiffChange {
+    Added(Bson),
+    Removed(Bson),
+    Changed { from: Bson, to: Bson },
+}
+
+/// One differing field, addressed by its path segments (`items`, `0`, `sku`).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DiffEntry {
+    path: Vec<String>,
+    change: DiffChange,
+}
+
+impl DiffEntry {
+    fn dotted_path(&self) -> String {
+        self.path.join(".")
+    }
+
+    /// RFC 6901 pointer for the field.
+    fn pointer(&self) -> String {
+        self.path
+            .iter()
+            .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+            .collect()
+    }
+
+    /// Old and new BSON type names when a changed field also changed type.
+    fn type_change(&self) -> Option<(&'static str, &'static str)> {
+        match &self.change {
+            DiffChange::Changed { from, to } => {
+                let (from, to) = (bson_type_name(from), bson_type_name(to));
+                (from != to).then_some((from, to))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Comparison shown on the diff screen: `left` is the earlier or marked version.
+#[derive(Debug, Clone)]
+pub(crate) struct DocumentDiff {
+    pub(crate) left_label: String,
+    pub(crate) right_label: String,
+    entries: Vec<DiffEntry>,
+    pub(crate) side_by_side: bool,
+    pub(crate) scroll: u16,
+    return_screen: Screen,
+}
+
+impl DocumentDiff {
+    pub(crate) fn summary(&self) -> String {
+        if self.entries.is_empty() {
+            return "no differences".to_string();
+        }
+        let count = |kind: fn(&DiffChange) -> bool| {
+            self.entries
+                .iter()
+                .filter(|entry| kind(&entry.change))
+                .count()
+        };
+        format!(
+            "{} added, {} removed, {} changed",
+            count(|change| matches!(change, DiffChange::Added(_))),
+            count(|change| matches!(change, DiffChange::Removed(_))),
+            count(|change| matches!(change, DiffChange::Changed { .. })),
+        )
+    }
+
+    pub(crate) fn title(&self) -> String {
+        format!("{} → {}", self.left_label, self.right_label)
+    }
+
+    pub(crate) fn max_scroll(&self) -> u16 {
+        let max = self.entries.len().saturating_sub(1);
+        max.min(u16::MAX as usize) as u16
+    }
+
+    pub(crate) fn scroll_by(&mut self, delta: i16) {
+        let next = if delta < 0 {
+            self.scroll.saturating_sub(delta.unsigned_abs())
+        } else {
+            self.scroll.saturating_add(delta as u16)
+        };
+        self.scroll = next.min(self.max_scroll());
+    }
+
+    /// The changes as a JSON Patch that turns the left document into the right one.
+    pub(crate) fn json_patch(&self) -> Result<String> {
+        let operations = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let path = entry.pointer();
+                match &entry.change {
+                    DiffChange::Added(value) => {
+                        json!({"op": "add", "path": path, "value": render_relaxed_extjson(value)})
+                    }
+                    DiffChange::Removed(_) => json!({"op": "remove", "path": path}),
+                    DiffChange::Changed { to, .. } => {
+                        json!({"op": "replace", "path": path, "value": render_relaxed_extjson(to)})
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        serde_json::to_string_pretty(&Value::Array(operations))
+            .context("unable to serialize JSON Patch")
+    }
+}
+
+impl App {
+    /// Marks the current document as the left side of the next diff, or clears the mark when
+    /// the same unchanged document is marked again.
+    pub(crate) fn toggle_diff_mark(&mut self) -> Result<()> {
+        if !matches!(self.screen, Screen::Documents | Screen::DocumentView) {
+            return Ok(());
+        }
+        let document = self.selected_document()?.clone();
+        if self
+            .diff_mark
+            .as_ref()
+            .is_some_and(|mark| mark.document == document)
+        {
+            self.diff_mark = None;
+            self.message = Some("diff mark cleared".to_string());
+            return Ok(());
+        }
+        let (_, _, collection) = self.selected_context()?;
+        let label = format!("{collection} _id {}", format_bson(&document_id(&document)?));
+        self.message = Some(format!("marked {label} for diff"));
+        self.diff_mark = Some(DiffMark { document, label });
+        Ok(())
+    }
+
+    /// Diffs two selected documents, the marked document against the current one, or the
+    /// current document against its server version, in that order of preference.
+    pub(crate) fn show_document_diff(&mut self) -> Result<()> {
+        if !matches!(self.screen, Screen::Documents | Screen::DocumentView) {
+            return Ok(());
+        }
+        let (connection, database, collection) = self.selected_context()?;
+        let selected = self.selected_documents();
+        let (left, left_label, right, right_label) = if selected.len() == 2 {
+            let labels = selected
+                .iter()
+                .map(|document| {
+                    Ok(format!(
+                        "{collection} _id {}",
+                        format_bson(&document_id(document)?)
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            (
+                selected[0].clone(),
+                labels[0].clone(),
+                selected[1].clone(),
+                labels[1].clone(),
+            )
+        } else if selected.len() > 2 {
+            anyhow::bail!("select exactly two documents to diff them");
+        } else {
+            let current = self.selected_document()?.clone();
+            let current_label =
+                format!("{collection} _id {}", format_bson(&document_id(&current)?));
+            match &self.diff_mark {
+                Some(mark) => (
+                    mark.document.clone(),
+                    format!("marked {}", mark.label),
+                    current,
+                    current_label,
+                ),
+                None => {
+                    let spec = DocumentFindSpec {
+                        connection: Some(connection),
+                        database,
+                        collection,
+                        id: document_id(&current)?,
+                    };
+                    let server = self
+                        .runtime
+                        .block_on(self.executor.find_document(&self.storage.config, &spec))?
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "_id {} no longer exists on the server",
+                                format_bson(&spec.id)
+                            )
+                        })?;
+                    (
+                        current,
+                        format!("loaded {current_label}"),
+                        server,
+                        "server version".to_string(),
+                    )
+                }
+            }
+        };
+        let mut entries = Vec::new();
+        diff_documents(&left, &right, &mut Vec::new(), &mut entries);
+        self.document_diff = Some(DocumentDiff {
+            left_label,
+            right_label,
+            entries,
+            side_by_side: false,
+            scroll: 0,
+            return_screen: self.screen,
+        });
+        self.screen = Screen::DocumentDiff;
+        Ok(())
+    }
+
+    pub(crate) fn toggle_diff_layout(&mut self) {
+        if let Some(diff) = &mut self.document_diff {
+            diff.side_by_side = !diff.side_by_side;
+        }
+    }
+
+    pub(crate) fn close_document_diff(&mut self) {
+        let diff = self.document_diff.take();
+        self.screen = diff
+            .map(|diff| diff.return_screen)
+            .unwrap_or(Screen::Documents);
+    }
+
+    pub(crate) fn export_document_diff(&mut self, action: ExportAction) -> Result<()> {
+        let diff = self
+            .document_diff
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("no diff to export"))?;
+        let rendered = diff.json_patch()?;
+        match action {
+            ExportAction::File => {
+                self.path_prompt = Some(PathPromptState {
+                    prompt: "JSON Patch path".to_string(),
+                    input: "diff.patch.json".to_string(),
+                    rendered,
+                });
+                self.message = Some("enter export path".to_string());
+            }
+            ExportAction::Clipboard => {
+                clipboard::copy_to_clipboard(&rendered)?;
+                self.message = Some("copied JSON Patch to clipboard".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// One line per change: `+` added, `-` removed, `~` changed with the type change noted.
+    pub(crate) fn diff_unified_lines(&self, diff: &DocumentDiff) -> Vec<Line<'static>> {
+        let dim = self.theme.text_style().add_modifier(Modifier::DIM);
+        diff.entries
+            .iter()
+            .map(|entry| {
+                let path = Span::styled(
+                    entry.dotted_path(),
+                    self.theme.text_style().add_modifier(Modifier::BOLD),
+                );
+                let mut spans = match &entry.change {
+                    DiffChange::Added(value) => vec![
+                        Span::styled("+ ", self.theme.title_style()),
+                        path,
+                        Span::raw(format!(": {}", leaf_text(value, false).0)),
+                    ],
+                    DiffChange::Removed(value) => vec![
+                        Span::styled("- ", self.theme.error_style()),
+                        path,
+                        Span::raw(format!(": {}", leaf_text(value, false).0)),
+                    ],
+                    DiffChange::Changed { from, to } => vec![
+                        Span::styled("~ ", self.theme.warning_style()),
+                        path,
+                        Span::raw(format!(
+                            ": {} → {}",
+                            leaf_text(from, false).0,
+                            leaf_text(to, false).0
+                        )),
+                    ],
+                };
+                if let Some((from, to)) = entry.type_change() {
+                    spans.push(Span::styled(format!("  {from} → {to}"), dim));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    /// Left and right columns for the side-by-side view, aligned one change per row.
+    pub(crate) fn diff_side_lines(
+        &self,
+        diff: &DocumentDiff,
+    ) -> (Vec<Line<'static>>, Vec<Line<'static>>) {
+        let cell = |entry: &DiffEntry, value: &Bson, style: Style| {
+            Line::from(vec![
+                Span::styled(entry.dotted_path(), style.add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    format!(
+                        ": {} ({})",
+                        leaf_text(value, false).0,
+                        bson_type_name(value)
+                    ),
+                    style,
+                ),
+            ])
+        };
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        for entry in &diff.entries {
+            match &entry.change {
+                DiffChange::Added(value) => {
+                    left.push(Line::from(""));
+                    right.push(cell(entry, value, self.theme.title_style()));
+                }
+                DiffChange::Removed(value) => {
+                    left.push(cell(entry, value, self.theme.error_style()));
+                    right.push(Line::from(""));
+                }
+                DiffChange::Changed { from, to } => {
+                    left.push(cell(entry, from, self.theme.warning_style()));
+                    right.push(cell(entry, to, self.theme.warning_style()));
+                }
+            }
+        }
+        (left, right)
+    }
+}
+
+/// Collects the differences between two documents, descending into subdocuments and into
+/// arrays item by item.
+fn diff_documents(
+    left: &Document,
+    right: &Document,
+    path: &mut Vec<String>,
+    entries: &mut Vec<DiffEntry>,
+) {
+    for (key, value) in left {
+        path.push(key.clone());
+        match right.get(key) {
+            Some(other) => diff_values(value, other, path, entries),
+            None => entries.push(DiffEntry {
+                path: path.clone(),
+                change: DiffChange::Removed(value.clone()),
+            }),
+        }
+        path.pop();
+    }
+    for (key, value) in right {
+        if !left.contains_key(key) {
+            path.push(key.clone());
+            entries.push(DiffEntry {
+                path: path.clone(),
+                change: DiffChange::Added(value.clone()),
+            });
+            path.pop();
+        }
+    }
+}
+
+fn diff_values(left: &Bson, right: &Bson, path: &mut Vec<String>, entries: &mut Vec<DiffEntry>) {
+    match (left, right) {
+        (Bson::Document(left), Bson::Document(right)) => diff_documents(left, right, path, entries),
+        (Bson::Array(left), Bson::Array(right)) => {
+            for (index, (left, right)) in left.iter().zip(right).enumerate() {
+                path.push(index.to_string());
+                diff_values(left, right, path, entries);
+                path.pop();
+            }
+            for (index, value) in right.iter().enumerate().skip(left.len()) {
+                path.push(index.to_string());
+                entries.push(DiffEntry {
+                    path: path.clone(),
+                    change: DiffChange::Added(value.clone()),
+                });
+                path.pop();
+            }
+            // Highest index first so the patch stays valid when applied in order.
+            for (index, value) in left.iter().enumerate().skip(right.len()).rev() {
+                path.push(index.to_string());
+                entries.push(DiffEntry {
+                    path: path.clone(),
+                    change: DiffChange::Removed(value.clone()),
+                });
+                path.pop();
+            }
+        }
+        _ if left != right => entries.push(DiffEntry {
+            path: path.clone(),
+            change: DiffChange::Changed {
+                from: left.clone(),
+                to: right.clone(),
+            },
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lazycompass_mongo::parse_json_document;
+
+    fn diff(left: &str, right: &str) -> Vec<DiffEntry> {
+        let mut entries = Vec::new();
+        diff_documents(
+            &parse_json_document("left", left).expect("left"),
+            &parse_json_document("right", right).expect("right"),
+            &mut Vec::new(),
+            &mut entries,
+        );
+        entries
+    }
+
+    fn documents_app() -> App {
+        let mut app = App::test_app();
+        app.select_test_namespace("app", "orders");
+        app.screen = Screen::Documents;
+        app.documents = vec![
+            parse_json_document("doc", r#"{"_id": 1, "status": "open", "total": 5}"#).expect("doc"),
+            parse_json_document("doc", r#"{"_id": 2, "status": "paid", "total": 5}"#).expect("doc"),
+        ];
+        app.document_index = Some(0);
+        app
+    }
+
+    #[test]
+    fn diff_walks_nested_documents_and_arrays() {
+        let entries = diff(
+            r#"{"_id": 1, "name": "a", "address": {"city": "Oslo", "zip": "0150"}, "tags": ["x", "y", "z"], "qty": "3"}"#,
+            r#"{"_id": 1, "name": "a", "address": {"city": "Bergen"}, "tags": ["x"], "qty": 3, "paid": true}"#,
+        );
+        let summary = entries
+            .iter()
+            .map(|entry| {
+                let kind = match entry.change {
+                    DiffChange::Added(_) => "+",
+                    DiffChange::Removed(_) => "-",
+                    DiffChange::Changed { .. } => "~",
+                };
+                format!("{kind}{}", entry.dotted_path())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                "~address.city",
+                "-address.zip",
+                "-tags.2",
+                "-tags.1",
+                "~qty",
+                "+paid"
+            ]
+        );
+        assert_eq!(entries[4].type_change(), Some(("string", "int")));
+        assert_eq!(entries[0].type_change(), None);
+    }
+
+    #[test]
+    fn json_patch_uses_escaped_pointers() {
+        let diff = DocumentDiff {
+            left_label: "left".to_string(),
+            right_label: "right".to_string(),
+            entries: diff(
+                r#"{"a/b": 1, "list": [1, 2], "gone": true}"#,
+                r#"{"a/b": 2, "list": [1], "new~key": {"$oid": "65f0a1b2c3d4e5f601234567"}}"#,
+            ),
+            side_by_side: false,
+            scroll: 0,
+            return_screen: Screen::Documents,
+        };
+        let patch: Value = serde_json::from_str(&diff.json_patch().expect("patch")).expect("json");
+        assert_eq!(
+            patch,
+            json!([
+                {"op": "replace", "path": "/a~1b", "value": 2},
+                {"op": "remove", "path": "/list/1"},
+                {"op": "remove", "path": "/gone"},
+                {"op": "add", "path": "/new~0key", "value": {"$oid": "65f0a1b2c3d4e5f601234567"}}
+            ])
+        );
+        assert_eq!(diff.summary(), "1 added, 2 removed, 1 changed");
+    }
+
+    #[test]
+    fn marked_document_diffs_against_the_current_one() {
+        let mut app = documents_app();
+        app.toggle_diff_mark().expect("mark");
+        assert_eq!(app.message.as_deref(), Some("marked orders _id 1 for diff"));
+        assert!(
+            app.documents_list_title()
+                .contains("[diff mark: orders _id 1]")
+        );
+
+        app.document_index = Some(1);
+        app.show_document_diff().expect("diff");
+        assert_eq!(app.screen, Screen::DocumentDiff);
+        let diff = app.document_diff.as_ref().expect("diff");
+        assert_eq!(diff.title(), "marked orders _id 1 → orders _id 2");
+        assert_eq!(diff.summary(), "0 added, 0 removed, 2 changed");
+
+        app.toggle_diff_layout();
+        let (left, right) = app.diff_side_lines(app.document_diff.as_ref().expect("diff"));
+        assert_eq!(left.len(), right.len());
+        assert_eq!(left[1].to_string(), "status: \"open\" (string)");
+
+        app.go_back();
+        assert_eq!(app.screen, Screen::Documents);
+        assert!(app.document_diff.is_none());
+
+        app.document_index = Some(0);
+        app.toggle_diff_mark().expect("unmark");
+        assert_eq!(app.message.as_deref(), Some("diff mark cleared"));
+    }
+}
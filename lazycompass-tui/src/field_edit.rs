@@ -0,0 +1,599 @@
+use super::*;
+use lazycompass_mongo::{DocumentUpdateSpec, parse_json_value};
+
+/// Single-line input for changing one field of the viewed document in place.
+#[derive(Debug, Clone)]
+pub(crate) struct FieldPromptState {
+    pub(crate) edit: FieldEdit,
+    pub(crate) input: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum FieldEdit {
+    /// Replace the scalar at `path`, keeping the BSON type of `original`.
+    Set { path: String, original: Bson },
+    /// Add a field to the embedded document at `parent` (empty for the top level).
+    AddField { parent: String },
+    /// Append an item to the array at `path`, which currently holds `len` items.
+    AppendItem { path: String, len: usize },
+}
+
+impl FieldPromptState {
+    pub(crate) fn prompt(&self) -> String {
+        match &self.edit {
+            FieldEdit::Set { path, original } => {
+                format!("set {path} ({})", bson_type_name(original))
+            }
+            FieldEdit::AddField { parent } if parent.is_empty() => {
+                "add field as name: value".to_string()
+            }
+            FieldEdit::AddField { parent } => format!("add field to {parent} as name: value"),
+            FieldEdit::AppendItem { path, .. } => format!("append to {path}"),
+        }
+    }
+}
+
+impl App {
+    /// Opens the field prompt for the scalar under the tree cursor.
+    pub(crate) fn edit_field(&mut self) -> Result<()> {
+        let Some((row, value)) = self.editable_field_under_cursor() else {
+            return Ok(());
+        };
+        if row.path == "_id" {
+            self.message = Some("_id cannot be changed".to_string());
+            return Ok(());
+        }
+        if matches!(value, Bson::Document(_) | Bson::Array(_)) {
+            self.message = Some(format!(
+                "{} is {}; edit its fields or use {} to edit the whole document",
+                row.path,
+                row.type_name,
+                self.keymap.keys_for_actions(&[KeyAction::Edit])
+            ));
+            return Ok(());
+        }
+        if self.block_if_db_write_disabled("update documents") {
+            return Ok(());
+        }
+        self.field_prompt = Some(FieldPromptState {
+            input: editable_text(&value),
+            edit: FieldEdit::Set {
+                path: row.path,
+                original: value,
+            },
+        });
+        Ok(())
+    }
+
+    /// Opens the prompt for a new field or array item next to or inside the cursor row.
+    pub(crate) fn add_field(&mut self) -> Result<()> {
+        let Some((row, value)) = self.editable_field_under_cursor() else {
+            return Ok(());
+        };
+        let (container, container_value) = match value {
+            Bson::Document(_) | Bson::Array(_) => (row.path, value),
+            _ => {
+                let parent = parent_path(&row.path).to_string();
+                let document = self.selected_document()?;
+                let value = if parent.is_empty() {
+                    Bson::Document(document.clone())
+                } else {
+                    value_at(document, &parent)
+                        .cloned()
+                        .ok_or_else(|| anyhow::anyhow!("field {parent} not found"))?
+                };
+                (parent, value)
+            }
+        };
+        if self.block_if_db_write_disabled("update documents") {
+            return Ok(());
+        }
+        let edit = match container_value {
+            Bson::Array(items) => FieldEdit::AppendItem {
+                path: container,
+                len: items.len(),
+            },
+            _ => FieldEdit::AddField { parent: container },
+        };
+        self.field_prompt = Some(FieldPromptState {
+            edit,
+            input: String::new(),
+        });
+        Ok(())
+    }
+
+    /// Asks to `$unset` the field under the cursor, or to drop the array item.
+    pub(crate) fn request_remove_field(&mut self) -> Result<()> {
+        let Some((row, _)) = self.editable_field_under_cursor() else {
+            return Ok(());
+        };
+        if row.path == "_id" {
+            self.message = Some("_id cannot be removed".to_string());
+            return Ok(());
+        }
+        if self.block_if_db_write_disabled("update documents") {
+            return Ok(());
+        }
+        let document = self.selected_document()?;
+        let (update, expected) = remove_update(document, &row.path)?;
+        let id = document_id(document)?;
+        let spec = self.update_spec(id, update, expected)?;
+        self.confirm = Some(ConfirmState {
+            prompt: format!(
+                "remove {} from document {} in {}.{}",
+                row.path,
+                format_bson(&spec.id),
+                spec.database,
+                spec.collection
+            ),
+            action: ConfirmAction::UpdateDocument {
+                spec,
+                summary: format!("removed {}", row.path),
+            },
+            input: String::new(),
+            required: self.connection_confirmation(),
+        });
+        Ok(())
+    }
+
+    pub(crate) fn handle_field_prompt_key(&mut self, key: KeyEvent) -> Result<bool> {
+        let Some(mut prompt) = self.field_prompt.take() else {
+            return Ok(false);
+        };
+
+        match key.code {
+            KeyCode::Esc => {
+                self.message = Some("cancelled".to_string());
+            }
+            KeyCode::Backspace => {
+                prompt.input.pop();
+                self.field_prompt = Some(prompt);
+            }
+            KeyCode::Enter => {
+                if let Err(error) = self.submit_field_prompt(&prompt) {
+                    self.field_prompt = Some(prompt);
+                    self.set_error_message(&error);
+                }
+            }
+            KeyCode::Char(ch) => {
+                if !ch.is_control() {
+                    prompt.input.push(ch);
+                }
+                self.field_prompt = Some(prompt);
+            }
+            _ => {
+                self.field_prompt = Some(prompt);
+            }
+        }
+
+        self.pending_keys.clear();
+        Ok(false)
+    }
+
+    fn submit_field_prompt(&mut self, prompt: &FieldPromptState) -> Result<()> {
+        let document = self.selected_document()?;
+        let (update, summary) = match &prompt.edit {
+            FieldEdit::Set { path, original } => {
+                let value = parse_typed_value(path, &prompt.input, original)?;
+                (set_update(path, value), format!("set {path}"))
+            }
+            FieldEdit::AddField { parent } => {
+                let (name, value) = prompt
+                    .input
+                    .split_once(':')
+                    .ok_or_else(|| anyhow::anyhow!("enter the new field as name: value"))?;
+                let name = name.trim();
+                if name.is_empty() || name.contains('.') || name.starts_with('$') {
+                    anyhow::bail!("field names must be non-empty without '.' or a leading '$'");
+                }
+                let path = if parent.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{parent}.{name}")
+                };
+                if value_at(document, &path).is_some() {
+                    anyhow::bail!("{path} already exists");
+                }
+                let value = parse_json_value("value", value.trim())?;
+                (set_update(&path, value), format!("added {path}"))
+            }
+            FieldEdit::AppendItem { path, len } => {
+                let value = parse_json_value("value", prompt.input.trim())?;
+                (
+                    set_update(&format!("{path}.{len}"), value),
+                    format!("appended to {path}"),
+                )
+            }
+        };
+        let id = document_id(document)?;
+        let spec = self.update_spec(id, update, Document::new())?;
+        if let Some(required) = self.connection_confirmation() {
+            self.confirm = Some(ConfirmState {
+                prompt: format!(
+                    "{summary} in document {} on protected connection '{required}'",
+                    format_bson(&spec.id)
+                ),
+                action: ConfirmAction::UpdateDocument { spec, summary },
+                input: String::new(),
+                required: Some(required),
+            });
+            return Ok(());
+        }
+        self.perform_update_document(spec, summary)
+    }
+
+    pub(crate) fn perform_update_document(
+        &mut self,
+        spec: DocumentUpdateSpec,
+        summary: String,
+    ) -> Result<()> {
+        self.runtime.block_on(self.executor.update_document(
+            &self.storage.config,
+            self.write_guard(),
+            &spec,
+        ))?;
+        self.reload_documents_after_change()?;
+        self.message = Some(summary);
+        Ok(())
+    }
+
+    fn update_spec(
+        &self,
+        id: Bson,
+        update: Document,
+        expected: Document,
+    ) -> Result<DocumentUpdateSpec> {
+        let (connection, database, collection) = self.selected_context()?;
+        Ok(DocumentUpdateSpec {
+            connection: Some(connection),
+            database,
+            collection,
+            id,
+            update,
+            expected,
+        })
+    }
+
+    /// Like [`App::field_under_cursor`], but refuses rows under a key that a dotted update path
+    /// cannot name, since the path would reach a different field or none.
+    fn editable_field_under_cursor(&mut self) -> Option<(TreeRow, Bson)> {
+        if self.screen == Screen::DocumentView && !self.document_json_view {
+            let rows = self.document_tree_rows();
+            if let Some(key) = unaddressable_key(&rows, self.document_tree.cursor) {
+                self.message = Some(format!(
+                    "field '{key}' has '.' or a leading '$' in its name; use {} to edit the whole document",
+                    self.keymap.keys_for_actions(&[KeyAction::Edit])
+                ));
+                return None;
+            }
+        }
+        self.field_under_cursor()
+    }
+
+    /// Tree row and value under the cursor; field edits only work in the tree view.
+    pub(crate) fn field_under_cursor(&mut self) -> Option<(TreeRow, Bson)> {
+        if self.screen != Screen::DocumentView {
+            return None;
+        }
+        if self.document_json_view {
+            self.message = Some(format!(
+                "press {} to pick a field in the tree view",
+                self.keymap.keys_for_actions(&[KeyAction::ToggleTree])
+            ));
+            return None;
+        }
+        let row = self
+            .document_tree_rows()
+            .into_iter()
+            .nth(self.document_tree.cursor)
+            .filter(|row| !matches!(row.node, TreeNode::More(_)))?;
+        let value = self
+            .selected_document()
+            .ok()
+            .and_then(|document| value_at(document, &row.path))
+            .cloned()?;
+        Some((row, value))
+    }
+}
+
+/// Value at a dotted path, stepping into embedded documents and array indexes.
+pub(crate) fn value_at<'a>(document: &'a Document, path: &str) -> Option<&'a Bson> {
+    let mut segments = path.split('.');
+    let mut value = document.get(segments.next()?)?;
+    for segment in segments {
+        value = match value {
+            Bson::Document(inner) => inner.get(segment)?,
+            Bson::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// First key on the way to the row at `cursor`, itself included, that contains `.` or starts
+/// with `$`.
+fn unaddressable_key(rows: &[TreeRow], cursor: usize) -> Option<&str> {
+    let row = rows.get(cursor)?;
+    let mut depth = row.depth;
+    let mut keys = vec![row.key.as_str()];
+    for ancestor in rows[..cursor].iter().rev() {
+        if ancestor.depth < depth {
+            depth = ancestor.depth;
+            keys.push(ancestor.key.as_str());
+        }
+    }
+    keys.into_iter()
+        .rev()
+        .find(|key| key.contains('.') || key.starts_with('$'))
+}
+
+fn parent_path(path: &str) -> &str {
+    path.rsplit_once('.').map_or("", |(parent, _)| parent)
+}
+
+fn set_update(path: &str, value: Bson) -> Document {
+    Document::from_iter([(
+        "$set".to_string(),
+        Bson::Document(Document::from_iter([(path.to_string(), value)])),
+    )])
+}
+
+/// `$unset` for a field, with no extra filter. Array items are dropped by setting the array
+/// without them, since unsetting an index leaves `null` behind; the filter then requires the
+/// array as loaded, so a concurrent change to it makes the update match nothing.
+fn remove_update(document: &Document, path: &str) -> Result<(Document, Document)> {
+    let parent = parent_path(path);
+    if let Some(Bson::Array(items)) = value_at(document, parent) {
+        let index = path[parent.len() + 1..]
+            .parse::<usize>()
+            .map_err(|_| anyhow::anyhow!("invalid array index in {path}"))?;
+        let mut items = items.clone();
+        if index >= items.len() {
+            anyhow::bail!("{path} not found");
+        }
+        let expected = Document::from_iter([(parent.to_string(), Bson::Array(items.clone()))]);
+        items.remove(index);
+        return Ok((set_update(parent, Bson::Array(items)), expected));
+    }
+    let update = Document::from_iter([(
+        "$unset".to_string(),
+        Bson::Document(Document::from_iter([(
+            path.to_string(),
+            Bson::String(String::new()),
+        )])),
+    )]);
+    Ok((update, Document::new()))
+}
+
+/// Prompt text for a scalar: strings unquoted, ObjectIds and dates in shell form.
+fn editable_text(value: &Bson) -> String {
+    match value {
+        Bson::String(text) => text.clone(),
+        _ => leaf_text(value, true).0,
+    }
+}
+
+/// Parses prompt input as the same BSON type as `original`. Strings take the text as typed,
+/// ObjectIds and dates accept a bare value or `ObjectId("…")`/`ISODate("…")`, and other types
+/// take JSON. A null has no type to keep, so it takes any JSON or Extended JSON value.
+fn parse_typed_value(path: &str, input: &str, original: &Bson) -> Result<Bson> {
+    let text = input.trim();
+    if matches!(original, Bson::Null) {
+        return parse_json_value("value", text);
+    }
+    let mismatch = || {
+        anyhow::anyhow!(
+            "{path} is {}; '{text}' is not a valid {}",
+            bson_type_name(original),
+            bson_type_name(original)
+        )
+    };
+    let value = match original {
+        Bson::String(_) => Bson::String(input.to_string()),
+        Bson::Int32(_) => Bson::Int32(text.parse().map_err(|_| mismatch())?),
+        Bson::Int64(_) => Bson::Int64(text.parse().map_err(|_| mismatch())?),
+        Bson::Double(_) => Bson::Double(text.parse().map_err(|_| mismatch())?),
+        Bson::Boolean(_) => Bson::Boolean(text.parse().map_err(|_| mismatch())?),
+        Bson::ObjectId(_) => parse_shell_call("ObjectId", text).map_err(|_| mismatch())?,
+        Bson::DateTime(_) => parse_shell_call("ISODate", text).map_err(|_| mismatch())?,
+        _ => parse_json_value("value", text)?,
+    };
+    if bson_type_name(&value) != bson_type_name(original) {
+        return Err(mismatch());
+    }
+    Ok(value)
+}
+
+fn parse_shell_call(name: &str, text: &str) -> Result<Bson> {
+    if text.starts_with(name) {
+        return parse_json_value("value", text);
+    }
+    let quoted = serde_json::to_string(text.trim_matches('"'))?;
+    parse_json_value("value", &format!("{name}({quoted})"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_document() -> Document {
+        parse_json_document(
+            "document",
+            r#"{"_id": 1, "status": "open", "count": 3, "done": false,
+                "owner": ObjectId("64b64c2f8f1b2c3d4e5f6a7b"),
+                "address": {"city": "Oslo"}, "tags": ["a", "b", "c"],
+                "due": ISODate("2024-01-01T00:00:00Z")}"#,
+        )
+        .expect("document")
+    }
+
+    #[test]
+    fn typed_values_keep_the_original_bson_type() {
+        let document = sample_document();
+        let value = |path| value_at(&document, path).expect("value");
+
+        assert_eq!(
+            parse_typed_value("status", "closed now", value("status")).expect("string"),
+            Bson::String("closed now".to_string())
+        );
+        assert_eq!(
+            parse_typed_value("count", " 42 ", value("count")).expect("int"),
+            Bson::Int32(42)
+        );
+        assert_eq!(
+            parse_typed_value("done", "true", value("done")).expect("bool"),
+            Bson::Boolean(true)
+        );
+        let owner = value("owner");
+        assert_eq!(
+            editable_text(owner),
+            r#"ObjectId("64b64c2f8f1b2c3d4e5f6a7b")"#
+        );
+        assert_eq!(
+            parse_typed_value("owner", "64b64c2f8f1b2c3d4e5f6a7c", owner)
+                .expect("bare object id")
+                .as_object_id()
+                .map(|id| id.to_hex()),
+            Some("64b64c2f8f1b2c3d4e5f6a7c".to_string())
+        );
+        assert!(matches!(
+            parse_typed_value("when", r#"ISODate("2024-05-01T10:00:00Z")"#, value("due")),
+            Ok(Bson::DateTime(_))
+        ));
+
+        let error = parse_typed_value("count", "many", value("count")).expect_err("not an int");
+        assert_eq!(error.to_string(), "count is int; 'many' is not a valid int");
+    }
+
+    #[test]
+    fn null_fields_take_any_value() {
+        assert_eq!(
+            parse_typed_value("note", r#""hello""#, &Bson::Null).expect("string"),
+            Bson::String("hello".to_string())
+        );
+        assert_eq!(
+            parse_typed_value("note", "12", &Bson::Null).expect("number"),
+            Bson::Int32(12)
+        );
+        assert!(matches!(
+            parse_typed_value("note", r#"{"$date": "2024-05-01T10:00:00Z"}"#, &Bson::Null),
+            Ok(Bson::DateTime(_))
+        ));
+        assert_eq!(
+            parse_typed_value("note", "null", &Bson::Null).expect("null"),
+            Bson::Null
+        );
+        assert!(parse_typed_value("note", "not json", &Bson::Null).is_err());
+    }
+
+    #[test]
+    fn updates_target_single_paths() {
+        let document = sample_document();
+        assert_eq!(
+            value_at(&document, "tags.1"),
+            Some(&Bson::String("b".into()))
+        );
+        assert_eq!(value_at(&document, "address.city.zip"), None);
+
+        assert_eq!(
+            remove_update(&document, "address.city").expect("unset"),
+            (
+                parse_json_document("update", r#"{"$unset": {"address.city": ""}}"#).expect("doc"),
+                Document::new()
+            )
+        );
+        assert_eq!(
+            remove_update(&document, "tags.1").expect("array item"),
+            (
+                parse_json_document("update", r#"{"$set": {"tags": ["a", "c"]}}"#).expect("doc"),
+                parse_json_document("expected", r#"{"tags": ["a", "b", "c"]}"#).expect("doc")
+            )
+        );
+    }
+
+    #[test]
+    fn field_prompts_follow_the_tree_cursor_and_write_checks() {
+        let mut app = App::test_app();
+        app.screen = Screen::DocumentView;
+        app.documents = vec![sample_document()];
+        app.document_index = Some(0);
+
+        app.document_tree.cursor = 1;
+        app.edit_field().expect("edit field");
+        assert!(app.field_prompt.is_none());
+        assert!(
+            app.message
+                .as_deref()
+                .is_some_and(|message| message.contains("--dangerously-enable-write")),
+            "{:?}",
+            app.message
+        );
+
+        app.write_enabled = true;
+        app.edit_field().expect("edit field");
+        let prompt = app.field_prompt.take().expect("prompt");
+        assert_eq!(prompt.input, "open");
+        assert_eq!(prompt.prompt(), "set status (string)");
+
+        app.document_tree.cursor = 0;
+        app.edit_field().expect("edit id");
+        assert!(app.field_prompt.is_none());
+        assert_eq!(app.message.as_deref(), Some("_id cannot be changed"));
+
+        app.document_tree.cursor = 6;
+        app.add_field().expect("append");
+        let prompt = app.field_prompt.take().expect("append prompt");
+        assert_eq!(prompt.prompt(), "append to tags");
+
+        app.document_tree.cursor = 2;
+        app.add_field().expect("add field");
+        let prompt = app.field_prompt.take().expect("add prompt");
+        assert_eq!(prompt.prompt(), "add field as name: value");
+    }
+
+    #[test]
+    fn keys_with_dots_or_dollars_are_left_to_the_document_editor() {
+        let mut app = App::test_app();
+        app.write_enabled = true;
+        app.screen = Screen::DocumentView;
+        app.documents = vec![
+            parse_json_document(
+                "document",
+                r#"{"_id": 1, "a.b": 1, "a": {"b": 2}, "meta": {"$note": {"x": 3}}}"#,
+            )
+            .expect("document"),
+        ];
+        app.document_index = Some(0);
+        let refused =
+            "field 'a.b' has '.' or a leading '$' in its name; use e to edit the whole document";
+
+        app.document_tree.cursor = 1;
+        app.edit_field().expect("edit field");
+        assert!(app.field_prompt.is_none());
+        assert_eq!(app.message.as_deref(), Some(refused));
+
+        app.message = None;
+        app.request_remove_field().expect("remove field");
+        assert!(app.confirm.is_none());
+        assert_eq!(app.message.as_deref(), Some(refused));
+
+        let rows = app.document_tree_rows();
+        app.document_tree.expand(&rows[3]);
+        let rows = app.document_tree_rows();
+        app.document_tree.expand(&rows[4]);
+        app.document_tree.cursor = 5;
+        app.edit_field().expect("edit nested field");
+        assert!(app.field_prompt.is_none());
+        assert_eq!(
+            app.message.as_deref(),
+            Some(
+                "field '$note' has '.' or a leading '$' in its name; use e to edit the whole document"
+            )
+        );
+
+        app.document_tree.cursor = 2;
+        app.add_field().expect("add field");
+        let prompt = app.field_prompt.take().expect("add prompt");
+        assert_eq!(prompt.prompt(), "add field to a as name: value");
+    }
+}
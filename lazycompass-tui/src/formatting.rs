@@ -61,6 +61,52 @@ pub(crate) fn format_document(document: &Document) -> Vec<String> {
     }
 }
 
+pub(crate) fn history_item_label(entry: &HistoryEntry) -> String {
+    let source = entry.saved_id.as_deref().unwrap_or("inline");
+    let outcome = match (&entry.error, entry.result_count) {
+        (Some(_), _) => "failed".to_string(),
+        (None, Some(count)) => format!("{count} docs"),
+        (None, None) => "-".to_string(),
+    };
+    format!(
+        "{}  {:<11}  {source}  {outcome} in {}ms",
+        format_timestamp_ms(entry.timestamp_ms),
+        entry.payload.kind_label(),
+        entry.duration_ms
+    )
+}
+
+pub(crate) fn history_detail_lines(entry: &HistoryEntry) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(format!(
+            "ran {} at {} on {}.{}",
+            entry.payload.kind_label(),
+            format_timestamp_ms(entry.timestamp_ms),
+            entry.database,
+            entry.collection
+        )),
+        Line::from(format!(
+            "source: {}",
+            entry.saved_id.as_deref().unwrap_or("inline")
+        )),
+    ];
+    match (&entry.error, entry.result_count) {
+        (Some(error), _) => lines.push(Line::from(format!("error: {error}"))),
+        (None, Some(count)) => lines.push(Line::from(format!(
+            "{count} document(s) in {}ms",
+            entry.duration_ms
+        ))),
+        (None, None) => {}
+    }
+    lines.push(Line::from(""));
+    lines.extend(
+        render_history_payload(&entry.payload)
+            .lines()
+            .map(|line| Line::from(line.to_string())),
+    );
+    lines
+}
+
 fn encoded_bson_size(document: &Document) -> Option<usize> {
     let mut buffer = Vec::new();
     document.to_writer(&mut buffer).ok().map(|_| buffer.len())
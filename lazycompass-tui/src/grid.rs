@@ -0,0 +1,603 @@
+use super::*;
+use std::collections::HashSet;
+
+const ID_COLUMN: &str = "_id";
+const MIN_COLUMN_WIDTH: usize = 3;
+const MAX_AUTO_COLUMN_WIDTH: usize = 24;
+const MAX_COLUMN_WIDTH: usize = 80;
+const COLUMN_WIDTH_STEP: usize = 2;
+const MAX_GRID_COLUMNS: usize = 200;
+const COLUMN_SEPARATOR: &str = " │ ";
+
+/// Sort applied from the grid, re-run against the source of the current results.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GridSort {
+    pub(crate) path: String,
+    pub(crate) descending: bool,
+}
+
+/// Column layout for the documents grid. Columns are the dotted field paths found in the loaded
+/// page, so only user choices are stored here and the layout survives paging.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DocumentGrid {
+    pub(crate) enabled: bool,
+    /// Columns as last arranged by the user; columns not listed follow in discovery order.
+    order: Vec<String>,
+    hidden: HashSet<String>,
+    widths: HashMap<String, usize>,
+    focused: Option<String>,
+    pub(crate) sort: Option<GridSort>,
+}
+
+impl GridSort {
+    fn direction(&self) -> i32 {
+        if self.descending { -1 } else { 1 }
+    }
+
+    pub(crate) fn document(&self) -> Document {
+        Document::from_iter([(self.path.clone(), Bson::Int32(self.direction()))])
+    }
+
+    /// The sort as a query payload string, e.g. `{"age":-1}`.
+    fn json(&self) -> String {
+        let mut sort = serde_json::Map::new();
+        sort.insert(self.path.clone(), self.direction().into());
+        serde_json::Value::Object(sort).to_string()
+    }
+
+    fn label(&self) -> &'static str {
+        if self.descending {
+            "descending"
+        } else {
+            "ascending"
+        }
+    }
+
+    /// Cycles ascending, descending, then unsorted for `path`.
+    fn next(current: Option<&GridSort>, path: &str) -> Option<GridSort> {
+        match current {
+            Some(sort) if sort.path == path && !sort.descending => Some(GridSort {
+                path: path.to_string(),
+                descending: true,
+            }),
+            Some(sort) if sort.path == path => None,
+            _ => Some(GridSort {
+                path: path.to_string(),
+                descending: false,
+            }),
+        }
+    }
+}
+
+impl DocumentGrid {
+    /// Forgets column choices when switching collections; the grid stays on or off.
+    pub(crate) fn reset_layout(&mut self) {
+        *self = Self {
+            enabled: self.enabled,
+            ..Self::default()
+        };
+    }
+
+    /// Visible columns in display order, with `_id` pinned first when present.
+    pub(crate) fn columns(&self, documents: &[Document]) -> Vec<String> {
+        let discovered = field_paths(documents);
+        let mut columns = self
+            .order
+            .iter()
+            .filter(|path| discovered.contains(path))
+            .cloned()
+            .collect::<Vec<_>>();
+        for path in discovered {
+            if !columns.contains(&path) {
+                columns.push(path);
+            }
+        }
+        if let Some(index) = columns.iter().position(|path| path == ID_COLUMN) {
+            let id = columns.remove(index);
+            columns.insert(0, id);
+        }
+        columns.retain(|path| !self.hidden.contains(path));
+        columns
+    }
+
+    fn focus_index(&self, columns: &[String]) -> usize {
+        self.focused
+            .as_ref()
+            .and_then(|focused| columns.iter().position(|path| path == focused))
+            .unwrap_or(0)
+    }
+
+    fn move_focus(&mut self, columns: &[String], delta: isize) {
+        let Some(last) = columns.len().checked_sub(1) else {
+            return;
+        };
+        let index = self
+            .focus_index(columns)
+            .saturating_add_signed(delta)
+            .min(last);
+        self.focused = Some(columns[index].clone());
+    }
+
+    /// Swaps the focused column with its neighbour. The pinned `_id` column never moves.
+    fn move_column(&mut self, columns: &[String], delta: isize) -> Result<()> {
+        let index = self.focus_index(columns);
+        let Some(target) = index
+            .checked_add_signed(delta)
+            .filter(|target| *target < columns.len())
+        else {
+            return Ok(());
+        };
+        if columns[index] == ID_COLUMN || columns[target] == ID_COLUMN {
+            anyhow::bail!("the _id column is pinned");
+        }
+        let mut order = columns.to_vec();
+        order.swap(index, target);
+        self.focused = Some(order[target].clone());
+        self.order = order;
+        Ok(())
+    }
+
+    fn hide_column(&mut self, columns: &[String]) -> Result<String> {
+        let index = self.focus_index(columns);
+        let path = columns
+            .get(index)
+            .ok_or_else(|| anyhow::anyhow!("no column to hide"))?
+            .clone();
+        if path == ID_COLUMN {
+            anyhow::bail!("the _id column is pinned");
+        }
+        self.hidden.insert(path.clone());
+        self.focused = columns
+            .get(index + 1)
+            .or_else(|| index.checked_sub(1).and_then(|index| columns.get(index)))
+            .cloned();
+        Ok(path)
+    }
+
+    fn resize_column(&mut self, path: &str, current: usize, grow: bool) {
+        let width = if grow {
+            current + COLUMN_WIDTH_STEP
+        } else {
+            current.saturating_sub(COLUMN_WIDTH_STEP)
+        };
+        self.widths.insert(
+            path.to_string(),
+            width.clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH),
+        );
+    }
+
+    /// Display width of each column: the user's width, or the widest cell capped for
+    /// readability.
+    fn column_widths(&self, columns: &[String], documents: &[Document]) -> Vec<usize> {
+        columns
+            .iter()
+            .map(|path| {
+                if let Some(width) = self.widths.get(path) {
+                    return *width;
+                }
+                documents
+                    .iter()
+                    .map(|document| cell_text(document, path).chars().count())
+                    .chain([header_label(path, self.sort.as_ref()).chars().count()])
+                    .max()
+                    .unwrap_or(0)
+                    .clamp(MIN_COLUMN_WIDTH, MAX_AUTO_COLUMN_WIDTH)
+            })
+            .collect()
+    }
+}
+
+impl App {
+    pub(crate) fn grid_columns(&self) -> Vec<String> {
+        self.document_grid.columns(&self.documents)
+    }
+
+    /// Grid keys on the Documents screen; column keys need the grid to be shown.
+    pub(crate) fn apply_grid_action(&mut self, action: KeyAction) {
+        if self.screen != Screen::Documents {
+            return;
+        }
+        if action == KeyAction::ToggleGrid {
+            self.document_grid.enabled = !self.document_grid.enabled;
+            return;
+        }
+        if !self.document_grid.enabled {
+            self.message = Some(format!(
+                "press {} to switch to the grid view first",
+                self.keymap.keys_for_actions(&[KeyAction::ToggleGrid])
+            ));
+            return;
+        }
+
+        let columns = self.grid_columns();
+        let result = match action {
+            KeyAction::ColumnLeft => {
+                self.document_grid.move_focus(&columns, -1);
+                Ok(())
+            }
+            KeyAction::ColumnRight => {
+                self.document_grid.move_focus(&columns, 1);
+                Ok(())
+            }
+            KeyAction::MoveColumnLeft => self.document_grid.move_column(&columns, -1),
+            KeyAction::MoveColumnRight => self.document_grid.move_column(&columns, 1),
+            KeyAction::NarrowColumn | KeyAction::WidenColumn => {
+                let index = self.document_grid.focus_index(&columns);
+                if let Some(path) = columns.get(index) {
+                    let widths = self.document_grid.column_widths(&columns, &self.documents);
+                    self.document_grid.resize_column(
+                        path,
+                        widths[index],
+                        action == KeyAction::WidenColumn,
+                    );
+                }
+                Ok(())
+            }
+            KeyAction::HideColumn => self.document_grid.hide_column(&columns).map(|path| {
+                self.message = Some(format!(
+                    "hid column {path}; press {} to show hidden columns",
+                    self.keymap.keys_for_actions(&[KeyAction::ShowColumns])
+                ));
+            }),
+            KeyAction::ShowColumns => {
+                let count = self.document_grid.hidden.len();
+                self.document_grid.hidden.clear();
+                self.message = Some(format!("showing {count} hidden column(s)"));
+                Ok(())
+            }
+            KeyAction::SortColumn => self.sort_by_focused_column(&columns),
+            _ => Ok(()),
+        };
+        if let Err(error) = result {
+            self.set_error_message(&error);
+        }
+    }
+
+    /// Re-runs the current results sorted by the focused column, cycling ascending,
+    /// descending, then the original order.
+    fn sort_by_focused_column(&mut self, columns: &[String]) -> Result<()> {
+        let path = columns
+            .get(self.document_grid.focus_index(columns))
+            .ok_or_else(|| anyhow::anyhow!("no column to sort by"))?
+            .clone();
+        let next = GridSort::next(self.document_grid.sort.as_ref(), &path);
+        match self.document_result_source.clone() {
+            DocumentResultSource::Collection => {
+                let previous = std::mem::replace(&mut self.document_grid.sort, next);
+                self.document_page = 0;
+                if let Err(error) = self.start_load_documents(None, DocumentLoadReason::Refresh) {
+                    self.document_grid.sort = previous;
+                    return Err(error);
+                }
+            }
+            DocumentResultSource::InlineQuery => {
+                let mut payload = self
+                    .inline_query_draft
+                    .as_ref()
+                    .and_then(|draft| draft.parsed.clone())
+                    .ok_or_else(|| anyhow::anyhow!("no inline query to re-run"))?;
+                if let Some(sort) = &next {
+                    payload.sort = Some(sort.json());
+                }
+                self.start_execute_inline_query(payload)?;
+                self.document_grid.sort = next;
+            }
+            DocumentResultSource::SavedQuery { name } => {
+                let index = self
+                    .storage
+                    .queries
+                    .iter()
+                    .position(|query| query.id == name)
+                    .ok_or_else(|| anyhow::anyhow!("saved query '{name}' no longer exists"))?;
+                self.saved_query_index = Some(index);
+                self.start_execute_saved_query_with_sort(next.as_ref().map(GridSort::json))?;
+                self.document_grid.sort = next;
+            }
+            DocumentResultSource::SavedAggregation { .. }
+            | DocumentResultSource::InlineAggregation => {
+                anyhow::bail!("add a $sort stage to sort aggregation results");
+            }
+            DocumentResultSource::Reference { .. } => {
+                anyhow::bail!("a followed reference shows a single document");
+            }
+        }
+        self.message = Some(match &self.document_grid.sort {
+            Some(sort) => format!("sorting by {} {}...", sort.path, sort.label()),
+            None => "sort cleared".to_string(),
+        });
+        Ok(())
+    }
+
+    /// Header and row lines for the grid, scrolled so the focused column fits in `width`.
+    pub(crate) fn grid_lines(
+        &self,
+        rows: &[usize],
+        width: usize,
+    ) -> (Line<'static>, Vec<Line<'static>>) {
+        let grid = &self.document_grid;
+        let columns = grid.columns(&self.documents);
+        let widths = grid.column_widths(&columns, &self.documents);
+        let focus = grid.focus_index(&columns);
+        let pinned = columns.first().is_some_and(|path| path == ID_COLUMN);
+        let shown = visible_columns(&widths, pinned, focus, width);
+
+        let header_style = self.theme.title_style().add_modifier(Modifier::UNDERLINED);
+        let separator = Span::styled(
+            COLUMN_SEPARATOR,
+            self.theme.border_style().add_modifier(Modifier::DIM),
+        );
+        let line = |cells: Vec<Span<'static>>| {
+            let mut spans = Vec::new();
+            for (position, cell) in cells.into_iter().enumerate() {
+                if position > 0 {
+                    spans.push(separator.clone());
+                }
+                spans.push(cell);
+            }
+            Line::from(spans)
+        };
+
+        let header = line(
+            shown
+                .iter()
+                .map(|index| {
+                    let text = fit(
+                        &header_label(&columns[*index], grid.sort.as_ref()),
+                        widths[*index],
+                    );
+                    let style = if *index == focus {
+                        header_style.add_modifier(Modifier::REVERSED)
+                    } else {
+                        header_style
+                    };
+                    Span::styled(text, style)
+                })
+                .collect(),
+        );
+        let lines = rows
+            .iter()
+            .filter_map(|row| self.documents.get(*row))
+            .map(|document| {
+                line(
+                    shown
+                        .iter()
+                        .map(|index| {
+                            Span::raw(fit(&cell_text(document, &columns[*index]), widths[*index]))
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+        (header, lines)
+    }
+
+    /// Dotted path of the focused grid column, for the footer.
+    pub(crate) fn grid_focus_label(&self) -> Option<String> {
+        let columns = self.grid_columns();
+        let index = self.document_grid.focus_index(&columns);
+        let path = columns.get(index)?;
+        Some(format!("column {path} ({}/{})", index + 1, columns.len()))
+    }
+}
+
+/// Dotted paths of the fields in `documents`, in first-seen order. Embedded documents are
+/// flattened; arrays stay one column.
+fn field_paths(documents: &[Document]) -> Vec<String> {
+    fn collect(document: &Document, prefix: &str, paths: &mut Vec<String>) {
+        for (key, value) in document {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            match value {
+                Bson::Document(inner) if !inner.is_empty() => collect(inner, &path, paths),
+                _ if paths.len() >= MAX_GRID_COLUMNS || paths.contains(&path) => {}
+                _ => paths.push(path),
+            }
+        }
+    }
+
+    let mut paths = Vec::new();
+    for document in documents {
+        collect(document, "", &mut paths);
+    }
+    paths
+}
+
+fn lookup<'a>(document: &'a Document, path: &str) -> Option<&'a Bson> {
+    let mut segments = path.split('.');
+    let mut value = document.get(segments.next()?)?;
+    for segment in segments {
+        value = value.as_document()?.get(segment)?;
+    }
+    Some(value)
+}
+
+fn cell_text(document: &Document, path: &str) -> String {
+    let text = match lookup(document, path) {
+        None => return String::new(),
+        Some(Bson::String(text)) => text.clone(),
+        Some(Bson::Array(items)) => {
+            format!(
+                "[{} item{}]",
+                items.len(),
+                if items.len() == 1 { "" } else { "s" }
+            )
+        }
+        Some(value) => leaf_text(value, false).0,
+    };
+    text.replace(['\n', '\r', '\t'], " ")
+}
+
+fn header_label(path: &str, sort: Option<&GridSort>) -> String {
+    match sort {
+        Some(sort) if sort.path == path && sort.descending => format!("{path} ▼"),
+        Some(sort) if sort.path == path => format!("{path} ▲"),
+        _ => path.to_string(),
+    }
+}
+
+/// Pads or truncates `text` to exactly `width` characters.
+fn fit(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return format!("{text:<width$}");
+    }
+    let mut short = text
+        .chars()
+        .take(width.saturating_sub(1))
+        .collect::<String>();
+    short.push('…');
+    short
+}
+
+/// Indexes of the columns drawn in `available` cells: the pinned column, then the scrolling
+/// columns from the first one that still leaves the focused column on screen.
+fn visible_columns(widths: &[usize], pinned: bool, focus: usize, available: usize) -> Vec<usize> {
+    let separator = COLUMN_SEPARATOR.chars().count();
+    let first_scrolling = usize::from(pinned);
+    let mut used = 0;
+    let mut shown = Vec::new();
+    if pinned && let Some(width) = widths.first() {
+        used = *width;
+        shown.push(0);
+    }
+    let fits = |start: usize| {
+        let mut total = used;
+        for width in widths.iter().take(focus + 1).skip(start) {
+            total += width + if total == 0 { 0 } else { separator };
+        }
+        total <= available
+    };
+    let mut start = first_scrolling;
+    if focus >= first_scrolling {
+        while start < focus && !fits(start) {
+            start += 1;
+        }
+    }
+    for (index, width) in widths.iter().enumerate().skip(start) {
+        let needed = width + if shown.is_empty() { 0 } else { separator };
+        if used + needed > available && !shown.is_empty() {
+            break;
+        }
+        used += needed;
+        shown.push(index);
+    }
+    shown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_documents() -> Vec<Document> {
+        let first = parse_json_document(
+            "--",
+            r#"{"_id": 1, "name": "Ada", "address": {"city": "London", "zip": "N1"}, "tags": ["a", "b"]}"#,
+        )
+        .expect("document");
+        let second = parse_json_document(
+            "--",
+            r#"{"name": "Grace", "_id": 2, "age": 36, "address": {"city": "Arlington"}}"#,
+        )
+        .expect("document");
+        vec![first, second]
+    }
+
+    fn grid_app() -> App {
+        let mut app = App::test_app();
+        app.screen = Screen::Documents;
+        app.documents = sample_documents();
+        app.document_index = Some(0);
+        app.document_grid.enabled = true;
+        app
+    }
+
+    #[test]
+    fn columns_flatten_dotted_paths_with_id_pinned_first() {
+        let documents = sample_documents();
+        let grid = DocumentGrid::default();
+        assert_eq!(
+            grid.columns(&documents),
+            ["_id", "name", "address.city", "address.zip", "tags", "age"]
+        );
+        assert_eq!(cell_text(&documents[0], "address.city"), "London");
+        assert_eq!(cell_text(&documents[0], "tags"), "[2 items]");
+        assert_eq!(cell_text(&documents[1], "address.zip"), "");
+        assert_eq!(cell_text(&documents[1], "age"), "36");
+    }
+
+    #[test]
+    fn column_keys_reorder_hide_and_resize() {
+        let mut app = grid_app();
+        app.apply_grid_action(KeyAction::MoveColumnRight);
+        assert_eq!(app.message.as_deref(), Some("the _id column is pinned"));
+
+        app.apply_grid_action(KeyAction::ColumnRight);
+        app.apply_grid_action(KeyAction::MoveColumnRight);
+        assert_eq!(
+            app.grid_columns(),
+            ["_id", "address.city", "name", "address.zip", "tags", "age"]
+        );
+
+        app.apply_grid_action(KeyAction::HideColumn);
+        assert_eq!(
+            app.grid_columns(),
+            ["_id", "address.city", "address.zip", "tags", "age"]
+        );
+        assert_eq!(
+            app.grid_focus_label().as_deref(),
+            Some("column address.zip (3/5)")
+        );
+
+        app.apply_grid_action(KeyAction::WidenColumn);
+        assert_eq!(app.document_grid.widths.get("address.zip"), Some(&13));
+        app.apply_grid_action(KeyAction::ShowColumns);
+        assert_eq!(app.grid_columns().len(), 6);
+
+        app.document_grid.reset_layout();
+        assert!(app.document_grid.enabled);
+        assert_eq!(app.grid_columns()[1], "name");
+    }
+
+    #[test]
+    fn sort_cycles_and_reloads_the_collection() {
+        assert_eq!(
+            GridSort::next(None, "age"),
+            Some(GridSort {
+                path: "age".to_string(),
+                descending: false,
+            })
+        );
+        let descending = GridSort {
+            path: "age".to_string(),
+            descending: true,
+        };
+        assert_eq!(descending.json(), r#"{"age":-1}"#);
+        assert_eq!(GridSort::next(Some(&descending), "age"), None);
+
+        let mut app = grid_app();
+        app.document_result_source = DocumentResultSource::InlineAggregation;
+        app.apply_grid_action(KeyAction::SortColumn);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("add a $sort stage to sort aggregation results")
+        );
+        assert_eq!(app.document_grid.sort, None);
+
+        app.document_result_source = DocumentResultSource::Collection;
+        app.apply_grid_action(KeyAction::SortColumn);
+        assert_eq!(app.message.as_deref(), Some("select a connection"));
+        assert_eq!(app.document_grid.sort, None);
+    }
+
+    #[test]
+    fn visible_columns_keep_pinned_and_focused_columns_on_screen() {
+        let widths = [4, 10, 10, 10, 10];
+        assert_eq!(visible_columns(&widths, true, 0, 30), vec![0, 1, 2]);
+        assert_eq!(visible_columns(&widths, true, 4, 30), vec![0, 3, 4]);
+        assert_eq!(visible_columns(&widths, false, 4, 23), vec![3, 4]);
+        assert_eq!(fit("London", 4), "Lon…");
+        assert_eq!(fit("N1", 4), "N1  ");
+    }
+}
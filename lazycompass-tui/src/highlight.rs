@@ -0,0 +1,307 @@
+use super::*;
+
+const MINUTE_SECS: u64 = 60;
+const HOUR_SECS: u64 = 60 * MINUTE_SECS;
+const DAY_SECS: u64 = 24 * HOUR_SECS;
+const MONTH_SECS: u64 = 30 * DAY_SECS;
+const YEAR_SECS: u64 = 365 * DAY_SECS;
+
+/// Kinds of JSON token colored by the theme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum JsonToken {
+    Key,
+    String,
+    Number,
+    Boolean,
+    Null,
+    /// Extended JSON wrapper keys such as `$oid`, `$date`, and `$numberDecimal`.
+    Extended,
+}
+
+/// Colors one line of pretty-printed relaxed Extended JSON. With `now_ms`, `$date` values get
+/// a relative-time note and `$oid` values the time encoded in the ObjectId.
+pub(crate) fn highlight_json_line(line: &str, theme: Theme, now_ms: Option<i64>) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut key: Option<&str> = None;
+    let mut string_value: Option<&str> = None;
+    let mut rest = line;
+    while let Some(ch) = rest.chars().next() {
+        let len = match ch {
+            '"' => {
+                let len = string_len(rest);
+                let text = &rest[..len];
+                let is_key = rest[len..].trim_start().starts_with(':');
+                let token = if is_key {
+                    key = Some(text.trim_matches('"'));
+                    if text.starts_with("\"$") {
+                        JsonToken::Extended
+                    } else {
+                        JsonToken::Key
+                    }
+                } else {
+                    string_value = Some(text.trim_matches('"'));
+                    match key {
+                        Some(key) if key.starts_with("$number") => JsonToken::Number,
+                        _ => JsonToken::String,
+                    }
+                };
+                spans.push(Span::styled(text.to_string(), theme.json_style(token)));
+                len
+            }
+            '-' | '0'..='9' => {
+                let len = rest
+                    .find(|ch: char| {
+                        !(ch.is_ascii_digit() || matches!(ch, '-' | '+' | '.' | 'e' | 'E'))
+                    })
+                    .unwrap_or(rest.len());
+                spans.push(Span::styled(
+                    rest[..len].to_string(),
+                    theme.json_style(JsonToken::Number),
+                ));
+                len
+            }
+            _ => {
+                let literal = [
+                    ("true", JsonToken::Boolean),
+                    ("false", JsonToken::Boolean),
+                    ("null", JsonToken::Null),
+                ]
+                .into_iter()
+                .find(|(word, _)| rest.starts_with(word));
+                match literal {
+                    Some((word, token)) => {
+                        spans.push(Span::styled(word.to_string(), theme.json_style(token)));
+                        word.len()
+                    }
+                    None => {
+                        let len = rest
+                            .find(|ch: char| matches!(ch, '"' | '-' | '0'..='9' | 't' | 'f' | 'n'))
+                            .filter(|len| *len > 0)
+                            .unwrap_or(ch.len_utf8());
+                        spans.push(Span::raw(rest[..len].to_string()));
+                        len
+                    }
+                }
+            }
+        };
+        rest = &rest[len..];
+    }
+    if let Some(now_ms) = now_ms
+        && let Some(value) = string_value
+    {
+        let hint = match key {
+            Some("$date") => iso_date_ms(value).map(|then| relative_time(then, now_ms)),
+            Some("$oid") => object_id_ms(value).map(|then| object_id_hint(then, now_ms)),
+            _ => None,
+        };
+        if let Some(hint) = hint {
+            spans.push(hint_span(theme, hint));
+        }
+    }
+    Line::from(spans)
+}
+
+/// Byte length of the JSON string starting at the opening quote of `text`, quotes included.
+fn string_len(text: &str) -> usize {
+    let mut escaped = false;
+    for (index, ch) in text.char_indices().skip(1) {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return index + 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// Dimmed note placed after a value.
+pub(crate) fn hint_span(theme: Theme, hint: String) -> Span<'static> {
+    Span::styled(
+        format!("  · {hint}"),
+        theme.text_style().add_modifier(Modifier::DIM),
+    )
+}
+
+/// Relative time such as `3 days ago` or `in 2 hours`.
+pub(crate) fn relative_time(then_ms: i64, now_ms: i64) -> String {
+    let delta = now_ms.saturating_sub(then_ms);
+    let secs = delta.unsigned_abs() / 1000;
+    let (value, unit) = match secs {
+        0..MINUTE_SECS => return "just now".to_string(),
+        MINUTE_SECS..HOUR_SECS => (secs / MINUTE_SECS, "minute"),
+        HOUR_SECS..DAY_SECS => (secs / HOUR_SECS, "hour"),
+        DAY_SECS..MONTH_SECS => (secs / DAY_SECS, "day"),
+        MONTH_SECS..YEAR_SECS => (secs / MONTH_SECS, "month"),
+        _ => (secs / YEAR_SECS, "year"),
+    };
+    let plural = if value == 1 { "" } else { "s" };
+    if delta < 0 {
+        format!("in {value} {unit}{plural}")
+    } else {
+        format!("{value} {unit}{plural} ago")
+    }
+}
+
+/// Creation time stored in an ObjectId, with how long ago that was.
+pub(crate) fn object_id_hint(then_ms: i64, now_ms: i64) -> String {
+    format!(
+        "created {} UTC, {}",
+        format_timestamp_ms(then_ms.max(0) as u64),
+        relative_time(then_ms, now_ms)
+    )
+}
+
+/// Milliseconds since the epoch for an ISO-8601 `$date` string.
+fn iso_date_ms(text: &str) -> Option<i64> {
+    match Bson::try_from(serde_json::json!({ "$date": text })).ok()? {
+        Bson::DateTime(date) => Some(date.timestamp_millis()),
+        _ => None,
+    }
+}
+
+/// Milliseconds since the epoch from the leading timestamp of a hex ObjectId.
+fn object_id_ms(hex: &str) -> Option<i64> {
+    if hex.len() != 24 {
+        return None;
+    }
+    let seconds = u32::from_str_radix(hex.get(..8)?, 16).ok()?;
+    Some(i64::from(seconds) * 1000)
+}
+
+/// Time hint for a date or ObjectId shown in the document tree.
+pub(crate) fn bson_time_hint(value: &Bson, now_ms: i64) -> Option<String> {
+    match value {
+        Bson::DateTime(date) => Some(relative_time(date.timestamp_millis(), now_ms)),
+        Bson::ObjectId(id) => Some(object_id_hint(id.timestamp().timestamp_millis(), now_ms)),
+        _ => None,
+    }
+}
+
+/// Token color for a scalar in the document tree, by its BSON type name.
+pub(crate) fn tree_value_token(type_name: &str) -> Option<JsonToken> {
+    Some(match type_name {
+        "string" | "symbol" => JsonToken::String,
+        "int" | "long" | "double" | "decimal" => JsonToken::Number,
+        "bool" => JsonToken::Boolean,
+        "null" | "undefined" => JsonToken::Null,
+        "objectId" | "date" | "timestamp" | "binData" | "regex" => JsonToken::Extended,
+        _ => return None,
+    })
+}
+
+impl App {
+    /// Current time for date hints, or `None` when they are turned off.
+    pub(crate) fn date_hint_now(&self) -> Option<i64> {
+        self.date_hints.then(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as i64)
+                .unwrap_or_default()
+        })
+    }
+
+    pub(crate) fn highlighted_json_lines(&self, lines: &[String]) -> Vec<Line<'static>> {
+        let now_ms = self.date_hint_now();
+        lines
+            .iter()
+            .map(|line| highlight_json_line(line, self.theme, now_ms))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW_MS: i64 = 1_700_000_000_000;
+
+    fn theme() -> Theme {
+        theme::theme_by_name("classic").expect("classic theme")
+    }
+
+    fn tokens(line: &Line<'_>) -> Vec<(String, Style)> {
+        line.spans
+            .iter()
+            .map(|span| (span.content.to_string(), span.style))
+            .collect()
+    }
+
+    #[test]
+    fn lines_split_into_styled_tokens() {
+        let theme = theme();
+        let line = highlight_json_line(r#"  "name": "a \"b\"","#, theme, None);
+        assert_eq!(
+            tokens(&line),
+            vec![
+                ("  ".to_string(), Style::default()),
+                ("\"name\"".to_string(), theme.json_style(JsonToken::Key)),
+                (": ".to_string(), Style::default()),
+                (
+                    r#""a \"b\"""#.to_string(),
+                    theme.json_style(JsonToken::String)
+                ),
+                (",".to_string(), Style::default()),
+            ]
+        );
+
+        let line = highlight_json_line(r#"  "n": -1.5e3, "ok": true, "x": null"#, theme, None);
+        let styled: Vec<(String, Style)> = tokens(&line)
+            .into_iter()
+            .filter(|(_, style)| *style != Style::default())
+            .collect();
+        assert_eq!(
+            styled,
+            vec![
+                ("\"n\"".to_string(), theme.json_style(JsonToken::Key)),
+                ("-1.5e3".to_string(), theme.json_style(JsonToken::Number)),
+                ("\"ok\"".to_string(), theme.json_style(JsonToken::Key)),
+                ("true".to_string(), theme.json_style(JsonToken::Boolean)),
+                ("\"x\"".to_string(), theme.json_style(JsonToken::Key)),
+                ("null".to_string(), theme.json_style(JsonToken::Null)),
+            ]
+        );
+
+        let line = highlight_json_line(r#"    "$numberDecimal": "1.10""#, theme, None);
+        assert_eq!(line.spans[1].style, theme.json_style(JsonToken::Extended));
+        assert_eq!(line.spans[3].style, theme.json_style(JsonToken::Number));
+    }
+
+    #[test]
+    fn dates_and_object_ids_get_time_hints() {
+        let theme = theme();
+        let text = |line: Line<'_>| line.to_string();
+
+        let date = highlight_json_line(
+            r#"    "$date": "2023-11-11T22:13:20Z""#,
+            theme,
+            Some(NOW_MS),
+        );
+        assert!(text(date).ends_with("  · 3 days ago"), "date hint");
+
+        let oid = highlight_json_line(
+            r#"    "$oid": "6553f1000000000000000000""#,
+            theme,
+            Some(NOW_MS + 7_200_000),
+        );
+        assert!(
+            text(oid).ends_with("  · created 2023-11-14 22:13:20 UTC, 2 hours ago"),
+            "object id hint"
+        );
+
+        let off = highlight_json_line(r#"    "$date": "2023-11-11T22:13:20Z""#, theme, None);
+        assert!(!text(off).contains('·'));
+    }
+
+    #[test]
+    fn relative_time_picks_the_largest_unit() {
+        assert_eq!(relative_time(NOW_MS - 30_000, NOW_MS), "just now");
+        assert_eq!(relative_time(NOW_MS - 60_000, NOW_MS), "1 minute ago");
+        assert_eq!(relative_time(NOW_MS - 5 * 3_600_000, NOW_MS), "5 hours ago");
+        assert_eq!(relative_time(NOW_MS + 2 * 86_400_000, NOW_MS), "in 2 days");
+        assert_eq!(
+            relative_time(NOW_MS - 400 * 86_400_000, NOW_MS),
+            "1 year ago"
+        );
+    }
+}
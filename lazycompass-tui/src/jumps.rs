@@ -0,0 +1,494 @@
+use super::*;
+
+use lazycompass_core::{SessionScreen, SessionState};
+
+const MAX_JUMPS: usize = 100;
+const MAX_COUNT: usize = 99_999;
+
+/// A position in the connection → database → collection → document hierarchy.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Location {
+    pub(crate) connection: String,
+    pub(crate) database: Option<String>,
+    pub(crate) collection: Option<String>,
+    pub(crate) screen: SessionScreen,
+    pub(crate) document_page: u64,
+    pub(crate) document_index: Option<usize>,
+    pub(crate) document_id: Option<Bson>,
+}
+
+impl Location {
+    fn label(&self) -> String {
+        let mut label = self.connection.clone();
+        for part in [&self.database, &self.collection].into_iter().flatten() {
+            label.push('/');
+            label.push_str(part);
+        }
+        if let Some(index) = self.document_index {
+            label.push_str(&format!(" #{}", index + 1));
+        }
+        label
+    }
+}
+
+/// Locations left by jumps, walked with Ctrl-o/Ctrl-i like vim's jump list.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct JumpList {
+    entries: Vec<Location>,
+    /// Position in `entries`; equal to the length when not walking the list.
+    index: usize,
+}
+
+impl JumpList {
+    /// Records the location left by a jump, dropping any newer entries walked back over.
+    pub(crate) fn push(&mut self, location: Location) {
+        self.entries.truncate(self.index);
+        if self.entries.last() != Some(&location) {
+            self.entries.push(location);
+        }
+        if self.entries.len() > MAX_JUMPS {
+            self.entries.remove(0);
+        }
+        self.index = self.entries.len();
+    }
+
+    fn back(&mut self, current: Location) -> Option<Location> {
+        if self.index == self.entries.len() {
+            if self.entries.last() != Some(&current) {
+                self.entries.push(current);
+            }
+            self.index = self.entries.len() - 1;
+        }
+        self.index = self.index.checked_sub(1)?;
+        Some(self.entries[self.index].clone())
+    }
+
+    fn forward(&mut self) -> Option<Location> {
+        if self.index + 1 >= self.entries.len() {
+            return None;
+        }
+        self.index += 1;
+        Some(self.entries[self.index].clone())
+    }
+}
+
+/// The key after `m` names a mark to set; the key after `'` names one to jump to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PendingMark {
+    Set,
+    Jump,
+}
+
+fn is_jump(action: KeyAction) -> bool {
+    matches!(
+        action,
+        KeyAction::Forward | KeyAction::Back | KeyAction::GoTop | KeyAction::GoBottom
+    )
+}
+
+fn is_repeatable(action: KeyAction) -> bool {
+    matches!(
+        action,
+        KeyAction::MoveDown
+            | KeyAction::MoveUp
+            | KeyAction::ColumnLeft
+            | KeyAction::ColumnRight
+            | KeyAction::MoveColumnLeft
+            | KeyAction::MoveColumnRight
+            | KeyAction::NarrowColumn
+            | KeyAction::WidenColumn
+            | KeyAction::MoveStageUp
+            | KeyAction::MoveStageDown
+    )
+}
+
+impl App {
+    /// Adds a digit to the count prefix. `0` only counts after another digit, and digits bound
+    /// to an action keep their binding.
+    pub(crate) fn push_count_digit(&mut self, key: KeyEvent) -> bool {
+        let KeyCode::Char(ch) = key.code else {
+            return false;
+        };
+        let Some(digit) = ch.to_digit(10) else {
+            return false;
+        };
+        if key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            || !self.pending_keys.is_empty()
+            || (digit == 0 && self.count_prefix.is_none())
+            || self.keymap.lookup(&[], KeyChord::from_event(key)) != KeyMatch::None
+        {
+            return false;
+        }
+        let count = self.count_prefix.unwrap_or(0) * 10 + digit as usize;
+        self.count_prefix = Some(count.min(MAX_COUNT));
+        true
+    }
+
+    /// Runs `action` with an optional count prefix: movements repeat, pages turn `count` at once
+    /// with a single load, `G`/`gg` go to that item, and jumps leave their starting location on
+    /// the jump list.
+    pub(crate) fn apply_counted_action(
+        &mut self,
+        action: KeyAction,
+        count: Option<usize>,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<bool> {
+        let before = if is_jump(action) {
+            self.current_location()
+        } else {
+            None
+        };
+        let quit = match count {
+            Some(count) if matches!(action, KeyAction::GoTop | KeyAction::GoBottom) => {
+                if !self.go_to_item(count) {
+                    self.apply_action(action, terminal)?;
+                }
+                false
+            }
+            Some(count) if action == KeyAction::NextPage => {
+                self.next_page(count)?;
+                false
+            }
+            Some(count) if action == KeyAction::PreviousPage => {
+                self.previous_page(count)?;
+                false
+            }
+            Some(count) if is_repeatable(action) => {
+                for _ in 0..count {
+                    if !self.move_within_matches(action) {
+                        self.apply_action(action, terminal)?;
+                    }
+                }
+                false
+            }
+            _ => {
+                if self.move_within_matches(action) {
+                    return Ok(false);
+                }
+                self.apply_action(action, terminal)?
+            }
+        };
+        if let Some(before) = before {
+            self.record_jump(before);
+        }
+        Ok(quit)
+    }
+
+    /// Leaves `before` on the jump list when the current location moved away from it.
+    pub(crate) fn record_jump(&mut self, before: Location) {
+        if self.current_location().as_ref() != Some(&before) {
+            self.jump_list.push(before);
+        }
+    }
+
+    /// Selects the `count`-th item (1-based) of the current list. Returns `false` on screens
+    /// without a list.
+    fn go_to_item(&mut self, count: usize) -> bool {
+        let screen = self.screen;
+        let Some(len) = self.list_items(screen).map(|items| items.len()) else {
+            return false;
+        };
+        if let Some(selected) = self.list_selection_mut(screen) {
+            Self::select_index(
+                selected,
+                len,
+                count.saturating_sub(1).min(len.saturating_sub(1)),
+            );
+        }
+        true
+    }
+
+    pub(crate) fn current_location(&self) -> Option<Location> {
+        let connection = self.selected_connection()?.name.clone();
+        let screen = self.session_state()?.screen;
+        let documents = screen == SessionScreen::Documents;
+        let document = documents
+            .then(|| {
+                self.document_index
+                    .and_then(|index| self.documents.get(index))
+            })
+            .flatten();
+        Some(Location {
+            connection,
+            database: (screen != SessionScreen::Connections)
+                .then(|| self.selected_database().map(str::to_string))
+                .flatten(),
+            collection: matches!(
+                screen,
+                SessionScreen::Collections | SessionScreen::Documents
+            )
+            .then(|| self.selected_collection().map(str::to_string))
+            .flatten(),
+            screen,
+            document_page: if documents { self.document_page } else { 0 },
+            document_index: document.and(self.document_index),
+            document_id: document.and_then(|document| document.get("_id").cloned()),
+        })
+    }
+
+    pub(crate) fn start_mark(&mut self, pending: PendingMark) {
+        self.pending_mark = Some(pending);
+        self.message = Some(match pending {
+            PendingMark::Set => "mark: press a letter".to_string(),
+            PendingMark::Jump => "jump to mark: press a letter".to_string(),
+        });
+    }
+
+    /// Handles the letter after `m` or `'`; any other key cancels.
+    pub(crate) fn handle_mark_key(&mut self, pending: PendingMark, key: KeyEvent) -> Result<()> {
+        self.message = None;
+        let KeyCode::Char(name) = key.code else {
+            return Ok(());
+        };
+        if !name.is_ascii_alphabetic() {
+            self.message = Some(format!("invalid mark '{name}'"));
+            return Ok(());
+        }
+        match pending {
+            PendingMark::Set => {
+                let location = self
+                    .current_location()
+                    .ok_or_else(|| anyhow::anyhow!("select a connection to set a mark"))?;
+                self.message = Some(format!("mark '{name}' set at {}", location.label()));
+                self.marks.insert(name, location);
+            }
+            PendingMark::Jump => {
+                let location = self
+                    .marks
+                    .get(&name)
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("mark '{name}' is not set"))?;
+                if let Some(before) = self.current_location() {
+                    self.jump_list.push(before);
+                }
+                self.go_to_location(location)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn jump_back(&mut self) -> Result<()> {
+        let Some(current) = self.current_location() else {
+            return Ok(());
+        };
+        match self.jump_list.back(current) {
+            Some(location) => self.go_to_location(location),
+            None => {
+                self.message = Some("already at the oldest jump".to_string());
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn jump_forward(&mut self) -> Result<()> {
+        match self.jump_list.forward() {
+            Some(location) => self.go_to_location(location),
+            None => {
+                self.message = Some("already at the newest jump".to_string());
+                Ok(())
+            }
+        }
+    }
+
+    /// Moves to `location`, selecting straight away when its list is already loaded and
+    /// otherwise walking down the hierarchy as each list loads, like a restored session.
+    pub(crate) fn go_to_location(&mut self, location: Location) -> Result<()> {
+        let connection_index = self
+            .storage
+            .config
+            .connections
+            .iter()
+            .position(|connection| connection.name == location.connection)
+            .ok_or_else(|| {
+                anyhow::anyhow!("connection '{}' no longer exists", location.connection)
+            })?;
+        let same_connection = self.connection_index == Some(connection_index);
+        let same_database =
+            same_connection && self.selected_database() == location.database.as_deref();
+        let same_collection =
+            same_database && self.selected_collection() == location.collection.as_deref();
+        let position = |items: &[String], name: &Option<String>| {
+            name.as_ref()
+                .and_then(|name| items.iter().position(|item| item == name))
+        };
+
+        match location.screen {
+            SessionScreen::Connections => {
+                self.connection_index = Some(connection_index);
+                self.screen = Screen::Connections;
+                return Ok(());
+            }
+            SessionScreen::Databases if same_connection => {
+                if let Some(index) = position(&self.database_items, &location.database) {
+                    self.database_index = Some(index);
+                    self.screen = Screen::Databases;
+                    return Ok(());
+                }
+            }
+            SessionScreen::Collections if same_database => {
+                if let Some(index) = position(&self.collection_items, &location.collection) {
+                    self.collection_index = Some(index);
+                    self.screen = Screen::Collections;
+                    return Ok(());
+                }
+            }
+            SessionScreen::Documents
+                if same_collection && matches!(self.document_state, LoadState::Idle) =>
+            {
+                let by_id = location.document_id.as_ref().and_then(|id| {
+                    self.documents
+                        .iter()
+                        .position(|document| document.get("_id") == Some(id))
+                });
+                let same_page = self.document_result_source == DocumentResultSource::Collection
+                    && self.document_page == location.document_page;
+                let index = by_id.or(location.document_index.filter(|_| same_page));
+                if index.is_some() || (same_page && location.document_index.is_none()) {
+                    if let Some(index) = index {
+                        Self::select_index(&mut self.document_index, self.documents.len(), index);
+                    }
+                    self.screen = Screen::Documents;
+                    return Ok(());
+                }
+            }
+            _ => {}
+        }
+
+        self.pending_jump_index = location.document_index;
+        self.restore_session(SessionState {
+            connection: location.connection,
+            database: location.database,
+            collection: location.collection,
+            screen: location.screen,
+            document_page: location.document_page,
+            inline_query: None,
+            inline_aggregation: None,
+            active_inline_draft: None,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(collection: &str) -> Location {
+        Location {
+            connection: "local".to_string(),
+            database: Some("app".to_string()),
+            collection: Some(collection.to_string()),
+            screen: SessionScreen::Collections,
+            document_page: 0,
+            document_index: None,
+            document_id: None,
+        }
+    }
+
+    fn app_with_lists() -> App {
+        let mut app = App::test_app();
+        app.select_test_namespace("app", "c1");
+        app.collection_items = (1..=12).map(|index| format!("c{index}")).collect();
+        app.screen = Screen::Collections;
+        app
+    }
+
+    #[test]
+    fn jump_list_walks_back_and_forward() {
+        let mut jumps = JumpList::default();
+        jumps.push(location("a"));
+        jumps.push(location("b"));
+        assert_eq!(jumps.back(location("c")), Some(location("b")));
+        assert_eq!(jumps.back(location("b")), Some(location("a")));
+        assert_eq!(jumps.back(location("a")), None);
+        assert_eq!(jumps.forward(), Some(location("b")));
+        assert_eq!(jumps.forward(), Some(location("c")));
+        assert_eq!(jumps.forward(), None);
+
+        jumps.back(location("c"));
+        jumps.push(location("b"));
+        assert_eq!(jumps.back(location("d")), Some(location("b")));
+        assert_eq!(jumps.forward(), Some(location("d")));
+    }
+
+    #[test]
+    fn counts_accumulate_and_pick_items() {
+        let mut app = app_with_lists();
+        for ch in ['1', '0'] {
+            assert!(app.push_count_digit(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE)));
+        }
+        assert_eq!(app.count_prefix, Some(10));
+        assert!(app.go_to_item(10));
+        assert_eq!(app.collection_index, Some(9));
+        assert!(app.go_to_item(99));
+        assert_eq!(app.collection_index, Some(11));
+
+        let mut app = app_with_lists();
+        assert!(!app.push_count_digit(KeyEvent::new(KeyCode::Char('0'), KeyModifiers::NONE)));
+        assert!(!app.push_count_digit(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE)));
+        assert!(!app.push_count_digit(KeyEvent::new(KeyCode::Char('5'), KeyModifiers::CONTROL)));
+        assert_eq!(app.count_prefix, None);
+        app.screen = Screen::DocumentView;
+        assert!(!app.go_to_item(3));
+    }
+
+    #[test]
+    fn counted_page_keys_turn_pages_with_one_load() {
+        let mut app = app_with_lists();
+        app.screen = Screen::Documents;
+        let mut terminal = Terminal::with_options(
+            CrosstermBackend::new(std::io::stdout()),
+            ratatui::TerminalOptions {
+                viewport: ratatui::Viewport::Fixed(Rect::new(0, 0, 80, 24)),
+            },
+        )
+        .expect("terminal");
+
+        let before = app.next_load_id;
+        app.apply_counted_action(KeyAction::NextPage, Some(3), &mut terminal)
+            .expect("next pages");
+        assert_eq!(app.document_page, 3);
+        assert_eq!(app.next_load_id, before + 1);
+        assert_eq!(app.document_load_id, Some(before + 1));
+
+        app.apply_counted_action(KeyAction::PreviousPage, Some(5), &mut terminal)
+            .expect("previous pages");
+        assert_eq!(app.document_page, 0);
+        assert_eq!(app.next_load_id, before + 2);
+    }
+
+    #[test]
+    fn marks_return_to_loaded_positions() {
+        let mut app = app_with_lists();
+        app.collection_index = Some(3);
+        app.handle_mark_key(
+            PendingMark::Set,
+            KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE),
+        )
+        .expect("set mark");
+        app.collection_index = Some(7);
+        app.screen = Screen::Databases;
+
+        app.handle_mark_key(
+            PendingMark::Jump,
+            KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE),
+        )
+        .expect("jump");
+        assert_eq!(app.screen, Screen::Collections);
+        assert_eq!(app.collection_index, Some(3));
+
+        app.jump_back().expect("jump back");
+        assert_eq!(app.screen, Screen::Databases);
+        app.jump_forward().expect("jump forward");
+        assert_eq!(app.screen, Screen::Collections);
+        assert!(
+            app.handle_mark_key(
+                PendingMark::Jump,
+                KeyEvent::new(KeyCode::Char('b'), KeyModifiers::NONE),
+            )
+            .is_err()
+        );
+    }
+}
@@ -1,4 +1,7 @@
 use super::*;
+use lazycompass_core::KeyChords;
+use std::collections::BTreeMap;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum KeyAction {
     Quit,
@@ -25,11 +28,46 @@ pub(crate) enum KeyAction {
     ClearApplied,
     ToggleHelp,
     AddConnection,
+    ShowHistory,
+    Search,
+    ToggleTree,
+    ToggleGrid,
+    ColumnLeft,
+    ColumnRight,
+    MoveColumnLeft,
+    MoveColumnRight,
+    NarrowColumn,
+    WidenColumn,
+    HideColumn,
+    ShowColumns,
+    SortColumn,
+    EditField,
+    AddField,
+    RemoveField,
+    VisualSelect,
+    ToggleSelect,
+    SelectAll,
+    ClearSelection,
+    MarkDiff,
+    ShowDiff,
+    OpenPipelineBuilder,
+    MoveStageUp,
+    MoveStageDown,
+    CommandLine,
+    SetMark,
+    JumpToMark,
+    JumpBack,
+    JumpForward,
+    FollowReference,
+    ReferenceBack,
+    SearchNext,
+    SearchPrevious,
+    ClearSearch,
 }
 
-#[derive(Debug, Clone, Copy)]
-struct KeyBinding {
-    action: KeyAction,
+/// A single key press; uppercase letters carry their case in the char rather than `SHIFT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct KeyChord {
     code: KeyCode,
     modifiers: KeyModifiers,
 }
@@ -40,182 +78,86 @@ pub(crate) struct HintGroup {
     pub(crate) label: &'static str,
 }
 
-const KEY_BINDINGS: &[KeyBinding] = &[
-    KeyBinding {
-        action: KeyAction::Quit,
-        code: KeyCode::Char('q'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::MoveDown,
-        code: KeyCode::Char('j'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::MoveDown,
-        code: KeyCode::Down,
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::MoveUp,
-        code: KeyCode::Char('k'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::MoveUp,
-        code: KeyCode::Up,
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::Back,
-        code: KeyCode::Char('h'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::Back,
-        code: KeyCode::Left,
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::Forward,
-        code: KeyCode::Char('l'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::Forward,
-        code: KeyCode::Right,
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::Forward,
-        code: KeyCode::Enter,
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::GoBottom,
-        code: KeyCode::Char('G'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::GoBottom,
-        code: KeyCode::Char('G'),
-        modifiers: KeyModifiers::SHIFT,
-    },
-    KeyBinding {
-        action: KeyAction::NextPage,
-        code: KeyCode::PageDown,
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::PreviousPage,
-        code: KeyCode::PageUp,
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::Insert,
-        code: KeyCode::Char('i'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::Edit,
-        code: KeyCode::Char('e'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::Delete,
-        code: KeyCode::Char('d'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::ExportResults,
-        code: KeyCode::Char('x'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::CopyResults,
-        code: KeyCode::Char('y'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::SaveQuery,
-        code: KeyCode::Char('Q'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::SaveQuery,
-        code: KeyCode::Char('Q'),
-        modifiers: KeyModifiers::SHIFT,
-    },
-    KeyBinding {
-        action: KeyAction::SaveAggregation,
-        code: KeyCode::Char('A'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::SaveAggregation,
-        code: KeyCode::Char('A'),
-        modifiers: KeyModifiers::SHIFT,
-    },
-    KeyBinding {
-        action: KeyAction::RunInlineQuery,
-        code: KeyCode::Char('R'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::RunInlineQuery,
-        code: KeyCode::Char('R'),
-        modifiers: KeyModifiers::SHIFT,
-    },
-    KeyBinding {
-        action: KeyAction::RunInlineAggregation,
-        code: KeyCode::Char('S'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::RunInlineAggregation,
-        code: KeyCode::Char('S'),
-        modifiers: KeyModifiers::SHIFT,
-    },
-    KeyBinding {
-        action: KeyAction::RunSavedQuery,
-        code: KeyCode::Char('r'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::RunSavedAggregation,
-        code: KeyCode::Char('a'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::ShowIndexes,
-        code: KeyCode::Char('I'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::ShowIndexes,
-        code: KeyCode::Char('I'),
-        modifiers: KeyModifiers::SHIFT,
-    },
-    KeyBinding {
-        action: KeyAction::ClearApplied,
-        code: KeyCode::Char('c'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::ToggleHelp,
-        code: KeyCode::Char('?'),
-        modifiers: KeyModifiers::NONE,
-    },
-    KeyBinding {
-        action: KeyAction::ToggleHelp,
-        code: KeyCode::Char('?'),
-        modifiers: KeyModifiers::SHIFT,
-    },
-    KeyBinding {
-        action: KeyAction::AddConnection,
-        code: KeyCode::Char('n'),
-        modifiers: KeyModifiers::NONE,
-    },
+/// Result of feeding a key to [`KeyMap::lookup`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum KeyMatch {
+    Action(KeyAction),
+    /// The keys so far start a longer binding such as `gg`.
+    Pending(Vec<KeyChord>),
+    None,
+}
+
+/// Key sequences bound to each action: the defaults below with `[keys]` overrides applied.
+#[derive(Debug, Clone)]
+pub(crate) struct KeyMap {
+    bindings: Vec<(Vec<KeyChord>, KeyAction)>,
+    /// Single keys checked first while a kept `/` filter is shown, so they may reuse main keys.
+    search_bindings: Vec<(Vec<KeyChord>, KeyAction)>,
+}
+
+const DEFAULT_KEYS: &[(KeyAction, &[&str])] = &[
+    (KeyAction::Quit, &["q"]),
+    (KeyAction::MoveDown, &["j", "down"]),
+    (KeyAction::MoveUp, &["k", "up"]),
+    (KeyAction::Back, &["h", "left"]),
+    (KeyAction::Forward, &["l", "enter", "right"]),
+    (KeyAction::GoTop, &["gg"]),
+    (KeyAction::GoBottom, &["G"]),
+    (KeyAction::NextPage, &["pagedown"]),
+    (KeyAction::PreviousPage, &["pageup"]),
+    (KeyAction::Insert, &["i"]),
+    (KeyAction::Edit, &["e"]),
+    (KeyAction::Delete, &["d"]),
+    (KeyAction::ExportResults, &["x"]),
+    (KeyAction::CopyResults, &["y"]),
+    (KeyAction::SaveQuery, &["Q"]),
+    (KeyAction::SaveAggregation, &["A"]),
+    (KeyAction::RunInlineQuery, &["R"]),
+    (KeyAction::RunInlineAggregation, &["S"]),
+    (KeyAction::RunSavedQuery, &["r"]),
+    (KeyAction::RunSavedAggregation, &["a"]),
+    (KeyAction::ShowIndexes, &["I"]),
+    (KeyAction::ClearApplied, &["c"]),
+    (KeyAction::ToggleHelp, &["?"]),
+    (KeyAction::AddConnection, &["n"]),
+    (KeyAction::ShowHistory, &["H"]),
+    (KeyAction::Search, &["/"]),
+    (KeyAction::ToggleTree, &["t"]),
+    (KeyAction::ToggleGrid, &["T"]),
+    (KeyAction::ColumnLeft, &["<"]),
+    (KeyAction::ColumnRight, &[">"]),
+    (KeyAction::MoveColumnLeft, &["{"]),
+    (KeyAction::MoveColumnRight, &["}"]),
+    (KeyAction::NarrowColumn, &["-"]),
+    (KeyAction::WidenColumn, &["+"]),
+    (KeyAction::HideColumn, &["z"]),
+    (KeyAction::ShowColumns, &["Z"]),
+    (KeyAction::SortColumn, &["s"]),
+    (KeyAction::EditField, &["E"]),
+    (KeyAction::AddField, &["o"]),
+    (KeyAction::RemoveField, &["D"]),
+    (KeyAction::VisualSelect, &["v"]),
+    (KeyAction::ToggleSelect, &["space"]),
+    (KeyAction::SelectAll, &["ctrl+a"]),
+    (KeyAction::ClearSelection, &["esc"]),
+    (KeyAction::MarkDiff, &["M"]),
+    (KeyAction::ShowDiff, &["="]),
+    (KeyAction::OpenPipelineBuilder, &["B"]),
+    (KeyAction::MoveStageUp, &["K"]),
+    (KeyAction::MoveStageDown, &["J"]),
+    (KeyAction::CommandLine, &[":"]),
+    (KeyAction::SetMark, &["m"]),
+    (KeyAction::JumpToMark, &["'"]),
+    (KeyAction::JumpBack, &["ctrl+o"]),
+    (KeyAction::JumpForward, &["ctrl+i", "tab"]),
+    (KeyAction::FollowReference, &["gd"]),
+    (KeyAction::ReferenceBack, &["ctrl+t"]),
+];
+
+const SEARCH_KEYS: &[(KeyAction, &[&str])] = &[
+    (KeyAction::SearchNext, &["n"]),
+    (KeyAction::SearchPrevious, &["N"]),
+    (KeyAction::ClearSearch, &["esc"]),
 ];
 
 const HINT_MOVE: &[KeyAction] = &[KeyAction::MoveDown, KeyAction::MoveUp];
@@ -233,6 +175,16 @@ const HINT_RUN: &[KeyAction] = &[KeyAction::RunSavedQuery, KeyAction::RunSavedAg
 const HINT_INDEXES: &[KeyAction] = &[KeyAction::ShowIndexes];
 const HINT_HELP: &[KeyAction] = &[KeyAction::ToggleHelp];
 const HINT_QUIT: &[KeyAction] = &[KeyAction::Quit];
+const HINT_SEARCH: &[KeyAction] = &[KeyAction::Search];
+const HINT_COMMAND: &[KeyAction] = &[KeyAction::CommandLine];
+const HINT_MARKS: &[KeyAction] = &[KeyAction::SetMark, KeyAction::JumpToMark];
+const HINT_JUMPS: &[KeyAction] = &[KeyAction::JumpBack, KeyAction::JumpForward];
+const HINT_DIFF: &[KeyAction] = &[KeyAction::MarkDiff, KeyAction::ShowDiff];
+const HINT_SELECT: &[KeyAction] = &[
+    KeyAction::VisualSelect,
+    KeyAction::ToggleSelect,
+    KeyAction::SelectAll,
+];
 
 const CONNECTION_HINTS: &[HintGroup] = &[
     HintGroup {
@@ -251,6 +203,14 @@ const CONNECTION_HINTS: &[HintGroup] = &[
         actions: &[KeyAction::AddConnection],
         label: "new connection",
     },
+    HintGroup {
+        actions: HINT_SEARCH,
+        label: "search",
+    },
+    HintGroup {
+        actions: HINT_COMMAND,
+        label: "command",
+    },
     HintGroup {
         actions: HINT_HELP,
         label: "help",
@@ -278,6 +238,14 @@ const DATABASE_HINTS: &[HintGroup] = &[
         actions: HINT_TOP_BOTTOM,
         label: "top/bottom",
     },
+    HintGroup {
+        actions: HINT_SEARCH,
+        label: "search",
+    },
+    HintGroup {
+        actions: HINT_COMMAND,
+        label: "command",
+    },
     HintGroup {
         actions: HINT_HELP,
         label: "help",
@@ -309,6 +277,22 @@ const COLLECTION_HINTS: &[HintGroup] = &[
         actions: HINT_TOP_BOTTOM,
         label: "top/bottom",
     },
+    HintGroup {
+        actions: HINT_MARKS,
+        label: "mark/go to mark",
+    },
+    HintGroup {
+        actions: HINT_JUMPS,
+        label: "jump back/forward",
+    },
+    HintGroup {
+        actions: HINT_SEARCH,
+        label: "search",
+    },
+    HintGroup {
+        actions: HINT_COMMAND,
+        label: "command",
+    },
     HintGroup {
         actions: HINT_HELP,
         label: "help",
@@ -336,6 +320,14 @@ const INDEXES_HINTS: &[HintGroup] = &[
         actions: HINT_TOP_BOTTOM,
         label: "top/bottom",
     },
+    HintGroup {
+        actions: HINT_SEARCH,
+        label: "search",
+    },
+    HintGroup {
+        actions: HINT_COMMAND,
+        label: "command",
+    },
     HintGroup {
         actions: HINT_HELP,
         label: "help",
@@ -374,6 +366,10 @@ const DOCUMENT_HINTS: &[HintGroup] = &[
         actions: HINT_MOVE,
         label: "move",
     },
+    HintGroup {
+        actions: HINT_SELECT,
+        label: "select",
+    },
     HintGroup {
         actions: HINT_FORWARD,
         label: "view",
@@ -386,6 +382,10 @@ const DOCUMENT_HINTS: &[HintGroup] = &[
         actions: HINT_INLINE_RUN,
         label: "quick query",
     },
+    HintGroup {
+        actions: &[KeyAction::OpenPipelineBuilder],
+        label: "pipeline builder",
+    },
     HintGroup {
         actions: HINT_EDITING,
         label: "edit",
@@ -394,6 +394,10 @@ const DOCUMENT_HINTS: &[HintGroup] = &[
         actions: HINT_EXPORT,
         label: "export",
     },
+    HintGroup {
+        actions: HINT_DIFF,
+        label: "diff mark/show",
+    },
     HintGroup {
         actions: HINT_SAVE,
         label: "save",
@@ -402,6 +406,10 @@ const DOCUMENT_HINTS: &[HintGroup] = &[
         actions: HINT_RUN,
         label: "saved",
     },
+    HintGroup {
+        actions: &[KeyAction::ShowHistory],
+        label: "history",
+    },
     HintGroup {
         actions: HINT_PAGE,
         label: "page",
@@ -410,6 +418,77 @@ const DOCUMENT_HINTS: &[HintGroup] = &[
         actions: HINT_TOP_BOTTOM,
         label: "top/bottom",
     },
+    HintGroup {
+        actions: HINT_MARKS,
+        label: "mark/go to mark",
+    },
+    HintGroup {
+        actions: HINT_JUMPS,
+        label: "jump back/forward",
+    },
+    HintGroup {
+        actions: HINT_SEARCH,
+        label: "search",
+    },
+    HintGroup {
+        actions: HINT_COMMAND,
+        label: "command",
+    },
+    HintGroup {
+        actions: &[KeyAction::ToggleGrid],
+        label: "grid",
+    },
+    HintGroup {
+        actions: HINT_HELP,
+        label: "help",
+    },
+    HintGroup {
+        actions: HINT_QUIT,
+        label: "quit",
+    },
+];
+
+const DOCUMENT_GRID_HINTS: &[HintGroup] = &[
+    HintGroup {
+        actions: HINT_MOVE,
+        label: "move",
+    },
+    HintGroup {
+        actions: HINT_SELECT,
+        label: "select",
+    },
+    HintGroup {
+        actions: &[KeyAction::ColumnLeft, KeyAction::ColumnRight],
+        label: "column",
+    },
+    HintGroup {
+        actions: &[KeyAction::MoveColumnLeft, KeyAction::MoveColumnRight],
+        label: "reorder",
+    },
+    HintGroup {
+        actions: &[KeyAction::NarrowColumn, KeyAction::WidenColumn],
+        label: "width",
+    },
+    HintGroup {
+        actions: &[KeyAction::HideColumn, KeyAction::ShowColumns],
+        label: "hide/show",
+    },
+    HintGroup {
+        actions: &[KeyAction::SortColumn],
+        label: "sort",
+    },
+    HintGroup {
+        actions: HINT_FORWARD,
+        label: "view",
+    },
+    HintGroup {
+        actions: HINT_PAGE,
+        label: "page",
+    },
+    HintGroup {
+        actions: &[KeyAction::ToggleGrid],
+        label: "list",
+    },
     HintGroup {
         actions: HINT_HELP,
         label: "help",
@@ -437,6 +516,14 @@ const SAVED_QUERY_HINTS: &[HintGroup] = &[
         actions: HINT_TOP_BOTTOM,
         label: "top/bottom",
     },
+    HintGroup {
+        actions: HINT_SEARCH,
+        label: "search",
+    },
+    HintGroup {
+        actions: HINT_COMMAND,
+        label: "command",
+    },
     HintGroup {
         actions: HINT_HELP,
         label: "help",
@@ -449,6 +536,49 @@ const SAVED_QUERY_HINTS: &[HintGroup] = &[
 
 const SAVED_AGGREGATION_HINTS: &[HintGroup] = SAVED_QUERY_HINTS;
 
+const HISTORY_HINTS: &[HintGroup] = &[
+    HintGroup {
+        actions: HINT_MOVE,
+        label: "move",
+    },
+    HintGroup {
+        actions: HINT_FORWARD,
+        label: "run",
+    },
+    HintGroup {
+        actions: &[KeyAction::Edit],
+        label: "edit",
+    },
+    HintGroup {
+        actions: HINT_SAVE,
+        label: "promote",
+    },
+    HintGroup {
+        actions: HINT_BACK,
+        label: "back",
+    },
+    HintGroup {
+        actions: HINT_TOP_BOTTOM,
+        label: "top/bottom",
+    },
+    HintGroup {
+        actions: HINT_SEARCH,
+        label: "search",
+    },
+    HintGroup {
+        actions: HINT_COMMAND,
+        label: "command",
+    },
+    HintGroup {
+        actions: HINT_HELP,
+        label: "help",
+    },
+    HintGroup {
+        actions: HINT_QUIT,
+        label: "quit",
+    },
+];
+
 const EXPORT_FORMAT_HINTS: &[HintGroup] = &[
     HintGroup {
         actions: HINT_MOVE,
@@ -510,18 +640,42 @@ const DOCUMENT_VIEW_HINTS: &[HintGroup] = &[
         actions: HINT_SCROLL,
         label: "scroll",
     },
+    HintGroup {
+        actions: HINT_FORWARD,
+        label: "expand",
+    },
     HintGroup {
         actions: HINT_BACK,
-        label: "back",
+        label: "collapse/back",
+    },
+    HintGroup {
+        actions: &[KeyAction::ToggleTree],
+        label: "tree/json",
+    },
+    HintGroup {
+        actions: &[
+            KeyAction::EditField,
+            KeyAction::AddField,
+            KeyAction::RemoveField,
+        ],
+        label: "field set/add/remove",
     },
     HintGroup {
         actions: HINT_EDIT_DELETE,
         label: "edit/delete",
     },
+    HintGroup {
+        actions: &[KeyAction::FollowReference, KeyAction::ReferenceBack],
+        label: "follow reference/back",
+    },
     HintGroup {
         actions: HINT_EXPORT,
         label: "export",
     },
+    HintGroup {
+        actions: HINT_DIFF,
+        label: "diff mark/show",
+    },
     HintGroup {
         actions: HINT_TOP_BOTTOM,
         label: "top/bottom",
@@ -535,20 +689,87 @@ const DOCUMENT_VIEW_HINTS: &[HintGroup] = &[
         label: "quit",
     },
 ];
-impl KeyBinding {
-    fn matches(&self, key: KeyEvent) -> bool {
-        self.code == key.code && self.modifiers == key.modifiers
-    }
-}
+const PIPELINE_BUILDER_HINTS: &[HintGroup] = &[
+    HintGroup {
+        actions: HINT_MOVE,
+        label: "move",
+    },
+    HintGroup {
+        actions: HINT_FORWARD,
+        label: "preview",
+    },
+    HintGroup {
+        actions: HINT_EDITING,
+        label: "add/edit/delete stage",
+    },
+    HintGroup {
+        actions: &[KeyAction::ToggleSelect],
+        label: "enable/disable",
+    },
+    HintGroup {
+        actions: &[KeyAction::MoveStageUp, KeyAction::MoveStageDown],
+        label: "reorder",
+    },
+    HintGroup {
+        actions: HINT_PAGE,
+        label: "scroll preview",
+    },
+    HintGroup {
+        actions: &[KeyAction::RunInlineAggregation],
+        label: "run",
+    },
+    HintGroup {
+        actions: &[KeyAction::SaveAggregation],
+        label: "save",
+    },
+    HintGroup {
+        actions: HINT_BACK,
+        label: "back",
+    },
+    HintGroup {
+        actions: HINT_HELP,
+        label: "help",
+    },
+    HintGroup {
+        actions: HINT_QUIT,
+        label: "quit",
+    },
+];
 
-pub(crate) fn action_for_key(key: KeyEvent) -> Option<KeyAction> {
-    KEY_BINDINGS
-        .iter()
-        .find(|binding| binding.matches(key))
-        .map(|binding| binding.action)
-}
-pub(crate) fn hint_groups(screen: Screen) -> &'static [HintGroup] {
+const DOCUMENT_DIFF_HINTS: &[HintGroup] = &[
+    HintGroup {
+        actions: HINT_SCROLL,
+        label: "scroll",
+    },
+    HintGroup {
+        actions: &[KeyAction::ToggleTree],
+        label: "unified/side-by-side",
+    },
+    HintGroup {
+        actions: HINT_EXPORT,
+        label: "export/copy JSON Patch",
+    },
+    HintGroup {
+        actions: HINT_BACK,
+        label: "back",
+    },
+    HintGroup {
+        actions: HINT_TOP_BOTTOM,
+        label: "top/bottom",
+    },
+    HintGroup {
+        actions: HINT_HELP,
+        label: "help",
+    },
+    HintGroup {
+        actions: HINT_QUIT,
+        label: "quit",
+    },
+];
+
+pub(crate) fn hint_groups(screen: Screen, grid: bool) -> &'static [HintGroup] {
     match screen {
+        Screen::Documents if grid => DOCUMENT_GRID_HINTS,
         Screen::Connections => CONNECTION_HINTS,
         Screen::Databases => DATABASE_HINTS,
         Screen::Collections => COLLECTION_HINTS,
@@ -556,84 +777,557 @@ pub(crate) fn hint_groups(screen: Screen) -> &'static [HintGroup] {
         Screen::IndexView => INDEX_VIEW_HINTS,
         Screen::Documents => DOCUMENT_HINTS,
         Screen::DocumentView => DOCUMENT_VIEW_HINTS,
+        Screen::DocumentDiff => DOCUMENT_DIFF_HINTS,
         Screen::ExportFormatSelect => EXPORT_FORMAT_HINTS,
         Screen::SavedQuerySelect => SAVED_QUERY_HINTS,
         Screen::SavedAggregationSelect => SAVED_AGGREGATION_HINTS,
         Screen::SaveQueryScopeSelect => SAVE_SCOPE_HINTS,
         Screen::SaveAggregationScopeSelect => SAVE_SCOPE_HINTS,
         Screen::AddConnectionScopeSelect => ADD_CONNECTION_SCOPE_HINTS,
+        Screen::History => HISTORY_HINTS,
+        Screen::PipelineBuilder => PIPELINE_BUILDER_HINTS,
+    }
+}
+
+impl KeyAction {
+    /// Name used for this action in the `[keys]` config section.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            KeyAction::Quit => "quit",
+            KeyAction::MoveDown => "move_down",
+            KeyAction::MoveUp => "move_up",
+            KeyAction::Back => "back",
+            KeyAction::Forward => "forward",
+            KeyAction::GoTop => "go_top",
+            KeyAction::GoBottom => "go_bottom",
+            KeyAction::NextPage => "next_page",
+            KeyAction::PreviousPage => "previous_page",
+            KeyAction::Insert => "insert",
+            KeyAction::Edit => "edit",
+            KeyAction::Delete => "delete",
+            KeyAction::ExportResults => "export_results",
+            KeyAction::CopyResults => "copy_results",
+            KeyAction::SaveQuery => "save_query",
+            KeyAction::SaveAggregation => "save_aggregation",
+            KeyAction::RunInlineQuery => "run_inline_query",
+            KeyAction::RunInlineAggregation => "run_inline_aggregation",
+            KeyAction::RunSavedQuery => "run_saved_query",
+            KeyAction::RunSavedAggregation => "run_saved_aggregation",
+            KeyAction::ShowIndexes => "show_indexes",
+            KeyAction::ClearApplied => "clear_applied",
+            KeyAction::ToggleHelp => "toggle_help",
+            KeyAction::AddConnection => "add_connection",
+            KeyAction::ShowHistory => "show_history",
+            KeyAction::Search => "search",
+            KeyAction::ToggleTree => "toggle_tree",
+            KeyAction::ToggleGrid => "toggle_grid",
+            KeyAction::ColumnLeft => "column_left",
+            KeyAction::ColumnRight => "column_right",
+            KeyAction::MoveColumnLeft => "move_column_left",
+            KeyAction::MoveColumnRight => "move_column_right",
+            KeyAction::NarrowColumn => "narrow_column",
+            KeyAction::WidenColumn => "widen_column",
+            KeyAction::HideColumn => "hide_column",
+            KeyAction::ShowColumns => "show_columns",
+            KeyAction::SortColumn => "sort_column",
+            KeyAction::EditField => "edit_field",
+            KeyAction::AddField => "add_field",
+            KeyAction::RemoveField => "remove_field",
+            KeyAction::VisualSelect => "visual_select",
+            KeyAction::ToggleSelect => "toggle_select",
+            KeyAction::SelectAll => "select_all",
+            KeyAction::ClearSelection => "clear_selection",
+            KeyAction::MarkDiff => "mark_diff",
+            KeyAction::ShowDiff => "show_diff",
+            KeyAction::OpenPipelineBuilder => "pipeline_builder",
+            KeyAction::MoveStageUp => "move_stage_up",
+            KeyAction::MoveStageDown => "move_stage_down",
+            KeyAction::CommandLine => "command_line",
+            KeyAction::SetMark => "set_mark",
+            KeyAction::JumpToMark => "jump_to_mark",
+            KeyAction::JumpBack => "jump_back",
+            KeyAction::JumpForward => "jump_forward",
+            KeyAction::FollowReference => "follow_reference",
+            KeyAction::ReferenceBack => "reference_back",
+            KeyAction::SearchNext => "search_next",
+            KeyAction::SearchPrevious => "search_previous",
+            KeyAction::ClearSearch => "clear_search",
+        }
+    }
+
+    pub(crate) fn all() -> impl Iterator<Item = Self> {
+        DEFAULT_KEYS
+            .iter()
+            .chain(SEARCH_KEYS)
+            .map(|(action, _)| *action)
+    }
+
+    fn is_search(self) -> bool {
+        SEARCH_KEYS.iter().any(|(action, _)| *action == self)
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Self::all().find(|action| action.name() == name)
+    }
+}
+
+impl KeyChord {
+    pub(crate) fn from_event(key: KeyEvent) -> Self {
+        let mut modifiers = key.modifiers;
+        if matches!(key.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self {
+            code: key.code,
+            modifiers,
+        }
+    }
+
+    /// Parses one chord such as `q`, `G`, `ctrl+d`, `shift+tab`, or `pagedown`.
+    fn parse(token: &str) -> Result<Self, String> {
+        let (prefix, key) = match token.strip_suffix("++") {
+            Some(prefix) => (prefix, "+"),
+            None => match token.rsplit_once('+') {
+                Some((prefix, key)) if !key.is_empty() => (prefix, key),
+                _ => ("", token),
+            },
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('+').filter(|modifier| !modifier.is_empty()) {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{modifier}'")),
+            };
+        }
+        let mut chars = key.chars();
+        let mut code = match (chars.next(), chars.next()) {
+            (Some(ch), None) => KeyCode::Char(ch),
+            _ => named_key(key).ok_or_else(|| format!("unknown key '{key}'"))?,
+        };
+        if modifiers.contains(KeyModifiers::SHIFT) {
+            match code {
+                KeyCode::Char(ch) => {
+                    code = KeyCode::Char(ch.to_ascii_uppercase());
+                    modifiers.remove(KeyModifiers::SHIFT);
+                }
+                KeyCode::Tab => {
+                    code = KeyCode::BackTab;
+                    modifiers.remove(KeyModifiers::SHIFT);
+                }
+                _ => {}
+            }
+        }
+        Ok(Self { code, modifiers })
+    }
+
+    fn label(self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(ch) => ch.to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "Shift+Tab".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            KeyCode::Insert => "Ins".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::F(number) => format!("F{number}"),
+            other => format!("{other:?}"),
+        };
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("Alt+");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            label.push_str("Shift+");
+        }
+        label.push_str(&key);
+        label
+    }
+}
+
+fn named_key(name: &str) -> Option<KeyCode> {
+    let name = name.to_ascii_lowercase();
+    let code = match name.as_str() {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" | "ins" => KeyCode::Insert,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        _ => {
+            let number = name.strip_prefix('f')?.parse::<u8>().ok()?;
+            return (1..=12).contains(&number).then_some(KeyCode::F(number));
+        }
+    };
+    Some(code)
+}
+
+/// Parses a binding into the chords pressed in order. Chords are separated by spaces
+/// (`"g g"`), and a run of plain characters that is not a key name (`"gg"`) is a sequence too.
+fn parse_sequence(spec: &str) -> Result<Vec<KeyChord>, String> {
+    let mut chords = Vec::new();
+    for token in spec.split_whitespace() {
+        if !token.contains('+') && token.chars().count() > 1 && named_key(token).is_none() {
+            chords.extend(token.chars().map(|ch| KeyChord {
+                code: KeyCode::Char(ch),
+                modifiers: KeyModifiers::NONE,
+            }));
+        } else {
+            chords.push(KeyChord::parse(token)?);
+        }
     }
+    if chords.is_empty() {
+        return Err("key is empty".to_string());
+    }
+    Ok(chords)
 }
 
-fn action_keys(action: KeyAction) -> &'static [&'static str] {
-    match action {
-        KeyAction::Quit => &["q"],
-        KeyAction::MoveDown => &["j", "↓"],
-        KeyAction::MoveUp => &["k", "↑"],
-        KeyAction::Back => &["h", "←"],
-        KeyAction::Forward => &["l", "Enter", "→"],
-        KeyAction::GoTop => &["gg"],
-        KeyAction::GoBottom => &["G"],
-        KeyAction::NextPage => &["PgDn"],
-        KeyAction::PreviousPage => &["PgUp"],
-        KeyAction::Insert => &["i"],
-        KeyAction::Edit => &["e"],
-        KeyAction::Delete => &["d"],
-        KeyAction::ExportResults => &["x"],
-        KeyAction::CopyResults => &["y"],
-        KeyAction::SaveQuery => &["Q"],
-        KeyAction::SaveAggregation => &["A"],
-        KeyAction::RunInlineQuery => &["R"],
-        KeyAction::RunInlineAggregation => &["S"],
-        KeyAction::RunSavedQuery => &["r"],
-        KeyAction::RunSavedAggregation => &["a"],
-        KeyAction::ShowIndexes => &["I"],
-        KeyAction::ClearApplied => &["c"],
-        KeyAction::ToggleHelp => &["?"],
-        KeyAction::AddConnection => &["n"],
+fn sequence_label(sequence: &[KeyChord]) -> String {
+    let labels = sequence
+        .iter()
+        .map(|chord| chord.label())
+        .collect::<Vec<_>>();
+    if labels.iter().all(|label| label.chars().count() == 1) {
+        labels.concat()
+    } else {
+        labels.join(" ")
     }
 }
 
-pub(crate) fn keys_for_actions(actions: &[KeyAction]) -> String {
-    let mut keys = Vec::new();
-    for action in actions {
-        keys.extend_from_slice(action_keys(*action));
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::resolve(&BTreeMap::new()).0
+    }
+}
+
+impl KeyMap {
+    /// Layers `[keys]` over the defaults. An action listed in config loses its default keys;
+    /// other actions keep theirs unless a configured key takes them, which is reported. Unknown
+    /// actions, bad keys, and keys that clash with (or prefix) an earlier binding are skipped
+    /// with a warning. Search actions are bound separately and take single keys only.
+    pub(crate) fn resolve(keys: &BTreeMap<String, KeyChords>) -> (Self, Vec<String>) {
+        let mut map = Self {
+            bindings: Vec::new(),
+            search_bindings: Vec::new(),
+        };
+        let mut warnings = Vec::new();
+        let mut configured = Vec::new();
+        for (name, chords) in keys {
+            let Some(action) = KeyAction::from_name(name) else {
+                warnings.push(format!("unknown key action '{name}' in [keys]"));
+                continue;
+            };
+            configured.push(action);
+            for spec in chords.chords() {
+                match parse_sequence(spec) {
+                    Ok(sequence) if action.is_search() && sequence.len() > 1 => warnings.push(
+                        format!("key '{spec}' for {name} must be a single key; ignoring it"),
+                    ),
+                    Ok(sequence) => match map.conflict(action, &sequence) {
+                        Some(other) => warnings.push(format!(
+                            "key '{spec}' for {name} conflicts with {}; ignoring it",
+                            other.name()
+                        )),
+                        None => map.scope_mut(action).push((sequence, action)),
+                    },
+                    Err(error) => warnings.push(format!(
+                        "invalid key '{spec}' for {name} in [keys]: {error}"
+                    )),
+                }
+            }
+        }
+        for (action, specs) in DEFAULT_KEYS.iter().chain(SEARCH_KEYS) {
+            if configured.contains(action) {
+                continue;
+            }
+            for spec in *specs {
+                let Ok(sequence) = parse_sequence(spec) else {
+                    continue;
+                };
+                match map.conflict(*action, &sequence) {
+                    Some(other) => warnings.push(format!(
+                        "key '{spec}' moved from {} to {}",
+                        action.name(),
+                        other.name()
+                    )),
+                    None => map.scope_mut(*action).push((sequence, *action)),
+                }
+            }
+        }
+        (map, warnings)
+    }
+
+    fn scope_mut(&mut self, action: KeyAction) -> &mut Vec<(Vec<KeyChord>, KeyAction)> {
+        if action.is_search() {
+            &mut self.search_bindings
+        } else {
+            &mut self.bindings
+        }
+    }
+
+    fn conflict(&self, action: KeyAction, sequence: &[KeyChord]) -> Option<KeyAction> {
+        let bindings = if action.is_search() {
+            &self.search_bindings
+        } else {
+            &self.bindings
+        };
+        bindings
+            .iter()
+            .find(|(existing, _)| existing.starts_with(sequence) || sequence.starts_with(existing))
+            .map(|(_, action)| *action)
+    }
+
+    /// Feeds `key` after the `pending` chords of an unfinished sequence. A key that breaks the
+    /// sequence is looked up on its own, so `g` then `j` still moves down.
+    pub(crate) fn lookup(&self, pending: &[KeyChord], key: KeyChord) -> KeyMatch {
+        let mut sequence = pending.to_vec();
+        sequence.push(key);
+        if let Some((_, action)) = self
+            .bindings
+            .iter()
+            .find(|(existing, _)| *existing == sequence)
+        {
+            return KeyMatch::Action(*action);
+        }
+        if self
+            .bindings
+            .iter()
+            .any(|(existing, _)| existing.starts_with(&sequence))
+        {
+            return KeyMatch::Pending(sequence);
+        }
+        if pending.is_empty() {
+            KeyMatch::None
+        } else {
+            self.lookup(&[], key)
+        }
+    }
+
+    /// Action bound to `key` pressed on its own.
+    pub(crate) fn action_for_key(&self, key: KeyEvent) -> Option<KeyAction> {
+        match self.lookup(&[], KeyChord::from_event(key)) {
+            KeyMatch::Action(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    /// Search action bound to `key` while a kept `/` filter is shown.
+    pub(crate) fn search_action_for_key(&self, key: KeyEvent) -> Option<KeyAction> {
+        let chord = KeyChord::from_event(key);
+        self.search_bindings
+            .iter()
+            .find(|(sequence, _)| *sequence == [chord])
+            .map(|(_, action)| *action)
+    }
+
+    pub(crate) fn keys_for_actions(&self, actions: &[KeyAction]) -> String {
+        actions
+            .iter()
+            .flat_map(|action| {
+                self.bindings
+                    .iter()
+                    .chain(&self.search_bindings)
+                    .filter(move |(_, bound)| bound == action)
+                    .map(|(sequence, _)| sequence_label(sequence))
+            })
+            .collect::<Vec<_>>()
+            .join("/")
     }
-    keys.join("/")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-    use std::collections::HashSet;
+
+    fn press(code: KeyCode) -> KeyChord {
+        KeyChord::from_event(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn keys(entries: &[(&str, &[&str])]) -> BTreeMap<String, KeyChords> {
+        entries
+            .iter()
+            .map(|(name, chords)| {
+                (
+                    name.to_string(),
+                    KeyChords::Many(chords.iter().map(|chord| chord.to_string()).collect()),
+                )
+            })
+            .collect()
+    }
 
     #[test]
     fn key_bindings_are_unique() {
-        let mut seen = HashSet::new();
-        for binding in KEY_BINDINGS {
-            let key = format!("{:?}:{:?}", binding.code, binding.modifiers);
-            assert!(seen.insert(key), "duplicate key binding: {binding:?}");
+        let (map, warnings) = KeyMap::resolve(&BTreeMap::new());
+        assert!(warnings.is_empty(), "{warnings:?}");
+        let defaults = DEFAULT_KEYS
+            .iter()
+            .map(|(_, specs)| specs.len())
+            .sum::<usize>();
+        assert_eq!(map.bindings.len(), defaults, "a default binding clashed");
+        for (index, (sequence, action)) in map.bindings.iter().enumerate() {
+            for (other, other_action) in &map.bindings[index + 1..] {
+                assert!(
+                    !sequence.starts_with(other) && !other.starts_with(sequence),
+                    "{action:?} and {other_action:?} share a key"
+                );
+            }
         }
     }
 
     #[test]
     fn arrow_keys_map_to_vim_actions() {
+        let map = KeyMap::default();
         assert_eq!(
-            action_for_key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)),
+            map.action_for_key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)),
             Some(KeyAction::MoveDown)
         );
         assert_eq!(
-            action_for_key(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE)),
+            map.action_for_key(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE)),
             Some(KeyAction::MoveUp)
         );
         assert_eq!(
-            action_for_key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE)),
+            map.action_for_key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE)),
             Some(KeyAction::Back)
         );
         assert_eq!(
-            action_for_key(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE)),
+            map.action_for_key(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE)),
             Some(KeyAction::Forward)
         );
+        assert_eq!(
+            map.action_for_key(KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+            Some(KeyAction::GoBottom)
+        );
+    }
+
+    #[test]
+    fn lookup_waits_for_multi_key_sequences() {
+        let map = KeyMap::default();
+        let g = press(KeyCode::Char('g'));
+        assert_eq!(map.lookup(&[], g), KeyMatch::Pending(vec![g]));
+        assert_eq!(map.lookup(&[g], g), KeyMatch::Action(KeyAction::GoTop));
+        assert_eq!(
+            map.lookup(&[g], press(KeyCode::Char('j'))),
+            KeyMatch::Action(KeyAction::MoveDown)
+        );
+        assert_eq!(map.lookup(&[], press(KeyCode::Char('w'))), KeyMatch::None);
+    }
+
+    #[test]
+    fn configured_keys_replace_defaults_and_drive_hints() {
+        let (map, warnings) = KeyMap::resolve(&keys(&[
+            ("next_page", &["ctrl+f", "space"]),
+            ("go_top", &["home", "g g"]),
+            ("quit", &["ctrl+q"]),
+        ]));
+        assert_eq!(
+            warnings,
+            vec!["key 'space' moved from toggle_select to next_page"]
+        );
+        assert_eq!(
+            map.action_for_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL)),
+            Some(KeyAction::NextPage)
+        );
+        assert_eq!(
+            map.action_for_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            map.keys_for_actions(&[KeyAction::GoTop, KeyAction::GoBottom]),
+            "Home/gg/G"
+        );
+        assert_eq!(map.keys_for_actions(&[KeyAction::NextPage]), "Ctrl+f/Space");
+        assert_eq!(map.keys_for_actions(&[KeyAction::Quit]), "Ctrl+q");
+        assert_eq!(
+            KeyMap::default().keys_for_actions(&[KeyAction::Forward, KeyAction::PreviousPage]),
+            "l/Enter/→/PgUp"
+        );
+    }
+
+    #[test]
+    fn configured_keys_take_over_default_keys() {
+        let (map, warnings) = KeyMap::resolve(&keys(&[("quit", &["j"])]));
+        assert_eq!(warnings, vec!["key 'j' moved from move_down to quit"]);
+        assert_eq!(map.keys_for_actions(&[KeyAction::MoveDown]), "↓");
+        assert_eq!(
+            map.action_for_key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE)),
+            Some(KeyAction::Quit)
+        );
+    }
+
+    #[test]
+    fn search_keys_are_scoped_to_the_filter_and_configurable() {
+        let map = KeyMap::default();
+        let n = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(map.search_action_for_key(n), Some(KeyAction::SearchNext));
+        assert_eq!(map.action_for_key(n), Some(KeyAction::AddConnection));
+
+        let (map, warnings) = KeyMap::resolve(&keys(&[
+            ("search_next", &["ctrl+n", "n n"]),
+            ("clear_search", &["q"]),
+        ]));
+        assert_eq!(
+            warnings,
+            vec!["key 'n n' for search_next must be a single key; ignoring it"]
+        );
+        assert_eq!(map.search_action_for_key(n), None);
+        assert_eq!(
+            map.search_action_for_key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL)),
+            Some(KeyAction::SearchNext)
+        );
+        assert_eq!(map.keys_for_actions(&[KeyAction::ClearSearch]), "q");
+        assert_eq!(map.keys_for_actions(&[KeyAction::Quit]), "q");
+    }
+
+    #[test]
+    fn resolve_reports_unknown_actions_bad_keys_and_conflicts() {
+        let (map, warnings) = KeyMap::resolve(&keys(&[
+            ("delete", &["x"]),
+            ("edit", &["hyper+e", "E"]),
+            ("export_results", &["x", "X"]),
+            ("explode", &["z"]),
+            ("go_top", &["g"]),
+        ]));
+        assert_eq!(
+            warnings,
+            vec![
+                "invalid key 'hyper+e' for edit in [keys]: unknown modifier 'hyper'",
+                "unknown key action 'explode' in [keys]",
+                "key 'x' for export_results conflicts with delete; ignoring it",
+                "key 'E' moved from edit_field to edit",
+                "key 'gd' moved from follow_reference to go_top",
+            ]
+        );
+        assert_eq!(map.keys_for_actions(&[KeyAction::EditField]), "");
+        assert_eq!(map.keys_for_actions(&[KeyAction::ExportResults]), "X");
+        assert_eq!(map.keys_for_actions(&[KeyAction::GoTop]), "g");
+        assert_eq!(
+            map.lookup(&[], press(KeyCode::Char('g'))),
+            KeyMatch::Action(KeyAction::GoTop)
+        );
     }
 }
@@ -6,20 +6,22 @@ use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
 };
 use lazycompass_core::{
-    Config, ConnectionSpec, OutputFormat, SavedAggregation, SavedQuery, SavedScope, WriteGuard,
+    Config, ConnectionProtection, ConnectionSpec, HistoryEntry, HistoryPayload, OutputFormat,
+    SavedAggregation, SavedQuery, SavedScope, WriteGuard, format_timestamp_ms,
     redact_sensitive_text,
 };
 use lazycompass_mongo::{
     Bson, Document, DocumentDeleteSpec, DocumentInsertSpec, DocumentListSpec, DocumentReplaceSpec,
-    MongoExecutor, parse_json_document,
+    MongoExecutor, parse_json_document, pipeline_write_stage,
 };
 use lazycompass_output::{
     ExportNameSource, render_documents, suggested_export_filename, write_rendered_output,
 };
 use lazycompass_storage::{
-    ConfigPaths, StorageSnapshot, append_connection_to_global_config,
-    append_connection_to_repo_config, load_storage_with_config, saved_aggregation_path,
-    saved_query_path, write_saved_aggregation, write_saved_query,
+    ConfigPaths, StorageFingerprint, StorageSnapshot, append_connection_to_global_config,
+    append_connection_to_repo_config, append_history, load_config_with_profile, load_history,
+    load_storage_with_config, saved_aggregation_path, saved_query_path, storage_fingerprint,
+    write_saved_aggregation, write_saved_query,
 };
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
@@ -27,46 +29,79 @@ use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::io::{Stdout, Write, stdout};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::runtime::Runtime;
 
 mod actions;
 mod app_loop;
 mod clipboard;
+mod command_line;
+mod completion;
+mod diff;
 mod editor;
 mod errors;
+mod field_edit;
 mod formatting;
+mod grid;
+mod highlight;
+mod jumps;
 mod keymap;
 mod loading;
 mod navigation;
 mod payloads;
+mod pipeline;
+mod quick_aggregation;
+mod references;
+mod reload;
 mod render;
+mod search;
+mod selection;
+mod session;
 mod terminal;
 mod theme;
+mod tree;
 
+pub use clipboard::{ClipboardMethods, clipboard_methods};
+
+use command_line::CommandLineState;
+use completion::{CompletionPopup, FieldSample, sample_fields};
+use diff::{DiffMark, DocumentDiff};
 use editor::{
     create_secure_editor_temp_file, is_editor_cancelled, resolve_editor, run_editor_command,
 };
 use errors::format_error;
-use formatting::{connection_label, document_id, document_preview, format_bson, format_document};
-use keymap::{KeyAction, action_for_key, hint_groups, keys_for_actions};
+use field_edit::{FieldPromptState, value_at};
+use formatting::{
+    connection_label, document_id, document_preview, format_bson, format_document,
+    history_detail_lines, history_item_label,
+};
+use grid::{DocumentGrid, GridSort};
+use highlight::{JsonToken, bson_time_hint, hint_span, tree_value_token};
+use jumps::{JumpList, Location, PendingMark};
+use keymap::{KeyAction, KeyChord, KeyMap, KeyMatch, hint_groups};
 use payloads::{
     default_saved_id, parse_aggregation_payload_input, parse_aggregation_save_input,
     parse_inline_aggregation_payload, parse_inline_query_payload, parse_query_payload_input,
     parse_query_save_input, render_aggregation_payload_template, render_aggregation_save_template,
-    render_inline_aggregation_template, render_inline_query_template,
+    render_history_payload, render_inline_aggregation_template, render_inline_query_template,
     render_query_payload_template, render_query_save_template, saved_scope_label,
 };
+use pipeline::PipelineBuilder;
+use quick_aggregation::QuickAggregationModalState;
+use references::{ReferenceFrame, ReferenceTarget};
+use search::{ListSearch, fuzzy_match};
+use selection::DocumentSelection;
 use terminal::{restore_terminal, resume_terminal, setup_terminal, suspend_terminal};
 use theme::{Theme, resolve_theme};
+use tree::{DocumentTree, TreeNode, TreeRow, bson_type_name, leaf_text};
 
-const PAGE_SIZE: u64 = 20;
+const DEFAULT_PAGE_SIZE: u64 = 20;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Screen {
     Connections,
@@ -76,12 +111,15 @@ enum Screen {
     IndexView,
     Documents,
     DocumentView,
+    DocumentDiff,
     ExportFormatSelect,
     SavedQuerySelect,
     SavedAggregationSelect,
     SaveQueryScopeSelect,
     SaveAggregationScopeSelect,
     AddConnectionScopeSelect,
+    History,
+    PipelineBuilder,
 }
 
 #[derive(Debug, Clone)]
@@ -89,7 +127,7 @@ struct ConfirmState {
     prompt: String,
     action: ConfirmAction,
     input: String,
-    required: Option<&'static str>,
+    required: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -122,6 +160,30 @@ enum ConfirmAction {
         path: PathBuf,
         rendered: String,
     },
+    InsertDocument {
+        spec: DocumentInsertSpec,
+    },
+    ReplaceDocument {
+        spec: DocumentReplaceSpec,
+        id_changed: bool,
+    },
+    UpdateDocument {
+        spec: lazycompass_mongo::DocumentUpdateSpec,
+        summary: String,
+    },
+    DeleteDocuments {
+        spec: lazycompass_mongo::DocumentBulkDeleteSpec,
+    },
+    ReplaceDocuments {
+        specs: Vec<DocumentReplaceSpec>,
+    },
+    RunAggregation {
+        spec: lazycompass_mongo::AggregationSpec,
+        saved_name: Option<String>,
+    },
+    RestoreSession {
+        session: lazycompass_core::SessionState,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -174,6 +236,9 @@ struct QuickQueryModalState {
     projection_cursor: usize,
     sort_cursor: usize,
     limit_cursor: usize,
+    /// Field paths sampled from the loaded documents, offered by Tab completion.
+    fields: Vec<FieldSample>,
+    completion: Option<CompletionPopup>,
 }
 
 impl QuickQueryModalState {
@@ -205,6 +270,8 @@ impl QuickQueryModalState {
             projection_cursor,
             sort_cursor,
             limit_cursor,
+            fields: Vec::new(),
+            completion: None,
         }
     }
 
@@ -400,6 +467,13 @@ enum PendingEditorAction {
         collection: String,
         document: Document,
     },
+    /// Edit the selected documents together as one JSON array.
+    EditMany {
+        connection: String,
+        database: String,
+        collection: String,
+        documents: Vec<Document>,
+    },
     SaveQuery {
         template: SavedQuery,
     },
@@ -408,6 +482,10 @@ enum PendingEditorAction {
     },
     RunInlineQuery,
     RunInlineAggregation,
+    /// Edit the pipeline builder stage under the cursor, or a new one after it.
+    EditPipelineStage {
+        insert: bool,
+    },
     SaveInlineQuery {
         scope: SavedScope,
         draft: InlineQueryPayload,
@@ -472,6 +550,21 @@ enum LoadResult {
         id: u64,
         result: Result<Vec<Document>>,
     },
+    PipelinePreview {
+        id: u64,
+        result: Result<Vec<Document>>,
+    },
+    Count {
+        id: u64,
+        label: String,
+        result: Result<u64>,
+    },
+    Reference {
+        id: u64,
+        target: ReferenceTarget,
+        label: String,
+        result: Result<Vec<Document>>,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -485,10 +578,18 @@ enum DocumentLoadReason {
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum DocumentResultSource {
     Collection,
-    SavedQuery { name: String },
-    SavedAggregation { name: String },
+    SavedQuery {
+        name: String,
+    },
+    SavedAggregation {
+        name: String,
+    },
     InlineQuery,
     InlineAggregation,
+    /// The document a followed reference points at.
+    Reference {
+        label: String,
+    },
 }
 
 struct ListView<'a> {
@@ -508,11 +609,16 @@ struct ExportTarget {
 struct App {
     paths: ConfigPaths,
     storage: StorageSnapshot,
+    storage_fingerprint: StorageFingerprint,
+    storage_checked_at: Instant,
+    storage_reload_pending: Option<Instant>,
+    session_connections: Vec<ConnectionSpec>,
     executor: MongoExecutor,
     runtime: Runtime,
     theme: Theme,
     write_enabled: bool,
     allow_pipeline_writes: bool,
+    allow_insecure: bool,
     screen: Screen,
     connection_index: Option<usize>,
     database_items: Vec<String>,
@@ -525,15 +631,41 @@ struct App {
     index_scroll: u16,
     documents: Vec<Document>,
     document_index: Option<usize>,
+    document_selection: DocumentSelection,
     document_page: u64,
+    /// Documents per collection page, changed with `:set page_size`.
+    page_size: u64,
     document_lines: Vec<String>,
     document_scroll: u16,
-    last_g: bool,
+    document_tree: DocumentTree,
+    /// Show the document view as pretty JSON instead of the tree.
+    document_json_view: bool,
+    /// Annotate dates and ObjectIds with relative times, changed with `:set date_hints`.
+    date_hints: bool,
+    document_grid: DocumentGrid,
+    diff_mark: Option<DiffMark>,
+    document_diff: Option<DocumentDiff>,
+    keymap: KeyMap,
+    pending_keys: Vec<KeyChord>,
+    /// Digits typed before an action, as in `5j` or `10G`.
+    count_prefix: Option<usize>,
+    pending_mark: Option<PendingMark>,
+    marks: HashMap<char, Location>,
+    jump_list: JumpList,
+    /// Document to select once a jump that reloads the collection page finishes.
+    pending_jump_index: Option<usize>,
+    /// Documents left by following references, most recent last.
+    reference_stack: Vec<ReferenceFrame>,
+    search: Option<ListSearch>,
+    command_line: Option<CommandLineState>,
+    /// Commands run from the `:` line this session, oldest first.
+    command_history: Vec<String>,
     help_visible: bool,
     message: Option<String>,
     confirm: Option<ConfirmState>,
     editor_prompt: Option<EditorPromptState>,
     path_prompt: Option<PathPromptState>,
+    field_prompt: Option<FieldPromptState>,
     editor_command: Option<String>,
     warnings: VecDeque<String>,
     load_tx: Sender<LoadResult>,
@@ -547,6 +679,8 @@ struct App {
     saved_agg_load_id: Option<u64>,
     inline_query_load_id: Option<u64>,
     inline_agg_load_id: Option<u64>,
+    count_load_id: Option<u64>,
+    reference_load_id: Option<u64>,
     database_state: LoadState,
     collection_state: LoadState,
     index_state: LoadState,
@@ -566,21 +700,40 @@ struct App {
     add_connection_scope_index: Option<usize>,
     inline_query_draft: Option<InlineQueryDraft>,
     inline_aggregation_draft: Option<InlineAggregationDraft>,
+    pipeline_builder: Option<PipelineBuilder>,
     active_inline_draft: Option<InlineDraftKind>,
     quick_query_modal: Option<QuickQueryModalState>,
+    quick_aggregation_modal: Option<QuickAggregationModalState>,
     query_save_source: QuerySaveSource,
     aggregation_save_source: AggregationSaveSource,
+    history_items: Vec<HistoryEntry>,
+    history_index: Option<usize>,
+    pending_history: HashMap<u64, (HistoryEntry, Instant)>,
+    pending_session: Option<lazycompass_core::SessionState>,
 }
 
-pub fn run(config: Config, write_enabled: bool, allow_pipeline_writes: bool) -> Result<()> {
+pub fn run(
+    config: Config,
+    write_enabled: bool,
+    allow_pipeline_writes: bool,
+    allow_insecure: bool,
+) -> Result<()> {
     let cwd = std::env::current_dir().context("unable to resolve current directory")?;
     let paths = ConfigPaths::resolve_from(&cwd)?;
     let storage = load_storage_with_config(&paths, config)?;
     let mut app = App::new(paths, storage, write_enabled, allow_pipeline_writes)?;
+    app.allow_insecure = allow_insecure;
+    app.offer_session_restore();
 
     let mut terminal = setup_terminal()?;
     let result = app.run(&mut terminal);
     restore_terminal(&mut terminal)?;
+    if let Err(error) = app.record_session() {
+        eprintln!(
+            "warning: unable to save session: {}",
+            redact_sensitive_text(&format_error(&error))
+        );
+    }
     result
 }
 
@@ -616,4 +769,29 @@ impl App {
         )
         .expect("build app")
     }
+
+    pub(crate) fn test_connection(name: &str) -> ConnectionSpec {
+        ConnectionSpec {
+            name: name.to_string(),
+            uri: "mongodb://localhost:27017".to_string(),
+            uri_command: None,
+            default_database: None,
+            protection: ConnectionProtection::Open,
+            options: None,
+        }
+    }
+
+    /// Adds a `local` test connection and selects it with `database` and `collection` as the
+    /// only listed database and collection.
+    pub(crate) fn select_test_namespace(&mut self, database: &str, collection: &str) {
+        self.storage
+            .config
+            .connections
+            .push(Self::test_connection("local"));
+        self.connection_index = Some(self.storage.config.connections.len() - 1);
+        self.database_items = vec![database.to_string()];
+        self.database_index = Some(0);
+        self.collection_items = vec![collection.to_string()];
+        self.collection_index = Some(0);
+    }
 }
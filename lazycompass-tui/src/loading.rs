@@ -15,6 +15,7 @@ impl App {
         let request_id = self.next_load_id();
         self.database_load_id = Some(request_id);
         self.database_state = LoadState::Loading;
+        self.clear_reference_stack();
         self.database_items.clear();
         self.database_index = None;
         self.message = None;
@@ -33,6 +34,14 @@ impl App {
     }
 
     pub(crate) fn start_execute_saved_query(&mut self) -> Result<()> {
+        self.start_execute_saved_query_with_sort(None)
+    }
+
+    /// Runs the selected saved query, with `sort` replacing its own sort when given.
+    pub(crate) fn start_execute_saved_query_with_sort(
+        &mut self,
+        sort: Option<String>,
+    ) -> Result<()> {
         let query_index = self
             .saved_query_index
             .ok_or_else(|| anyhow::anyhow!("select a saved query"))?;
@@ -64,14 +73,30 @@ impl App {
             collection,
             filter: saved.filter.clone(),
             projection: saved.projection.clone(),
-            sort: saved.sort.clone(),
+            sort: sort.or_else(|| saved.sort.clone()),
             limit: saved.limit,
         };
 
         let config = self.storage.config.clone();
         let request_id = self.next_load_id();
+        self.begin_history(
+            request_id,
+            HistoryEntry::started(
+                spec.connection.clone(),
+                spec.database.clone(),
+                spec.collection.clone(),
+                Some(saved.id.clone()),
+                HistoryPayload::Query {
+                    filter: spec.filter.clone(),
+                    projection: spec.projection.clone(),
+                    sort: spec.sort.clone(),
+                    limit: spec.limit,
+                },
+            ),
+        );
         self.saved_query_load_id = Some(request_id);
         self.saved_query_state = LoadState::Loading;
+        self.document_grid.sort = None;
         self.message = Some(format!("executing saved query '{}'...", saved.id));
         let saved_name = saved.id.clone();
         let sender = self.load_tx.clone();
@@ -101,7 +126,23 @@ impl App {
 
         let config = self.storage.config.clone();
         let request_id = self.next_load_id();
+        self.begin_history(
+            request_id,
+            HistoryEntry::started(
+                spec.connection.clone(),
+                spec.database.clone(),
+                spec.collection.clone(),
+                None,
+                HistoryPayload::Query {
+                    filter: spec.filter.clone(),
+                    projection: spec.projection.clone(),
+                    sort: spec.sort.clone(),
+                    limit: spec.limit,
+                },
+            ),
+        );
         self.inline_query_load_id = Some(request_id);
+        self.document_grid.sort = None;
         self.message = Some("executing inline query...".to_string());
         let sender = self.load_tx.clone();
         self.runtime.spawn(async move {
@@ -152,26 +193,7 @@ impl App {
             pipeline: saved.pipeline.clone(),
         };
 
-        let config = self.storage.config.clone();
-        let write_guard = self.write_guard();
-        let request_id = self.next_load_id();
-        self.saved_agg_load_id = Some(request_id);
-        self.saved_agg_state = LoadState::Loading;
-        self.message = Some(format!("executing saved aggregation '{}'...", saved.id));
-        let saved_name = saved.id.clone();
-        let sender = self.load_tx.clone();
-        self.runtime.spawn(async move {
-            let executor = MongoExecutor::new();
-            let result = executor
-                .execute_aggregation(&config, write_guard, &spec)
-                .await;
-            let _ = sender.send(LoadResult::SavedAggregation {
-                id: request_id,
-                name: saved_name,
-                result,
-            });
-        });
-        Ok(())
+        self.execute_aggregation(spec, Some(saved.id))
     }
 
     pub(crate) fn start_execute_inline_aggregation(
@@ -186,23 +208,83 @@ impl App {
             pipeline: payload.pipeline,
         };
 
+        self.execute_aggregation(spec, None)
+    }
+
+    /// Pipelines with `$out`/`$merge` on a `confirm` connection wait for the connection name.
+    fn execute_aggregation(
+        &mut self,
+        spec: lazycompass_mongo::AggregationSpec,
+        saved_name: Option<String>,
+    ) -> Result<()> {
+        if self.write_enabled
+            && let Some(required) = self.connection_confirmation()
+            && let Some(stage) = pipeline_write_stage(&spec.pipeline)?
+        {
+            self.confirm = Some(ConfirmState {
+                prompt: format!(
+                    "run aggregation with {stage} on protected connection '{required}'"
+                ),
+                action: ConfirmAction::RunAggregation { spec, saved_name },
+                input: String::new(),
+                required: Some(required),
+            });
+            return Ok(());
+        }
+        self.spawn_aggregation(spec, saved_name);
+        Ok(())
+    }
+
+    pub(crate) fn spawn_aggregation(
+        &mut self,
+        spec: lazycompass_mongo::AggregationSpec,
+        saved_name: Option<String>,
+    ) {
         let config = self.storage.config.clone();
         let write_guard = self.write_guard();
         let request_id = self.next_load_id();
-        self.inline_agg_load_id = Some(request_id);
-        self.message = Some("executing inline aggregation...".to_string());
+        self.begin_history(
+            request_id,
+            HistoryEntry::started(
+                spec.connection.clone(),
+                spec.database.clone(),
+                spec.collection.clone(),
+                saved_name.clone(),
+                HistoryPayload::Aggregation {
+                    pipeline: spec.pipeline.clone(),
+                },
+            ),
+        );
+        self.document_grid.sort = None;
+        match &saved_name {
+            Some(name) => {
+                self.saved_agg_load_id = Some(request_id);
+                self.saved_agg_state = LoadState::Loading;
+                self.message = Some(format!("executing saved aggregation '{name}'..."));
+            }
+            None => {
+                self.inline_agg_load_id = Some(request_id);
+                self.message = Some("executing inline aggregation...".to_string());
+            }
+        }
         let sender = self.load_tx.clone();
         self.runtime.spawn(async move {
             let executor = MongoExecutor::new();
             let result = executor
                 .execute_aggregation(&config, write_guard, &spec)
                 .await;
-            let _ = sender.send(LoadResult::InlineAggregation {
-                id: request_id,
-                result,
+            let _ = sender.send(match saved_name {
+                Some(name) => LoadResult::SavedAggregation {
+                    id: request_id,
+                    name,
+                    result,
+                },
+                None => LoadResult::InlineAggregation {
+                    id: request_id,
+                    result,
+                },
             });
         });
-        Ok(())
     }
 
     pub(crate) fn start_load_collections(&mut self) -> Result<()> {
@@ -217,6 +299,7 @@ impl App {
         let database_name = database.to_string();
         let request_id = self.next_load_id();
         self.collection_load_id = Some(request_id);
+        self.clear_reference_stack();
         self.collection_state = LoadState::Loading;
         self.collection_items.clear();
         self.collection_index = None;
@@ -240,6 +323,9 @@ impl App {
         pending_index: Option<usize>,
         reason: DocumentLoadReason,
     ) -> Result<()> {
+        if reason == DocumentLoadReason::EnterCollection {
+            self.document_grid.reset_layout();
+        }
         let connection = self
             .selected_connection()
             .ok_or_else(|| anyhow::anyhow!("select a connection"))?;
@@ -253,8 +339,9 @@ impl App {
             connection: Some(connection.name.clone()),
             database: database.to_string(),
             collection: collection.to_string(),
-            skip: self.document_page * PAGE_SIZE,
-            limit: PAGE_SIZE,
+            skip: self.document_page * self.page_size,
+            limit: self.page_size,
+            sort: self.document_grid.sort.as_ref().map(GridSort::document),
         };
         let config = self.storage.config.clone();
         let request_id = self.next_load_id();
@@ -265,6 +352,7 @@ impl App {
         self.document_pending_index = pending_index;
         self.documents.clear();
         self.document_index = None;
+        self.document_selection.clear();
         self.document_lines.clear();
         self.document_scroll = 0;
         self.message = None;
@@ -330,6 +418,7 @@ impl App {
         };
         self.document_lines = format_document(document);
         self.document_scroll = 0;
+        self.document_tree.show(document);
     }
 
     pub(crate) fn prepare_index_view(&mut self) {
@@ -378,9 +467,8 @@ mod tests {
         let storage = StorageSnapshot {
             config: Config {
                 connections: vec![ConnectionSpec {
-                    name: "local".to_string(),
-                    uri: "mongodb://localhost:27017".to_string(),
                     default_database: Some("app".to_string()),
+                    ..App::test_connection("local")
                 }],
                 ..Config::default()
             },
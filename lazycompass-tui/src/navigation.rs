@@ -19,7 +19,13 @@ impl App {
             Screen::Documents => {
                 Self::select_index(&mut self.document_index, self.documents.len(), 0)
             }
-            Screen::DocumentView => self.document_scroll = 0,
+            Screen::DocumentView if self.document_json_view => self.document_scroll = 0,
+            Screen::DocumentView => self.document_tree.cursor = 0,
+            Screen::DocumentDiff => {
+                if let Some(diff) = &mut self.document_diff {
+                    diff.scroll = 0;
+                }
+            }
             Screen::ExportFormatSelect => Self::select_index(&mut self.export_format_index, 3, 0),
             Screen::SavedQuerySelect => {
                 Self::select_index(&mut self.saved_query_index, self.storage.queries.len(), 0)
@@ -38,6 +44,10 @@ impl App {
             Screen::AddConnectionScopeSelect => {
                 Self::select_index(&mut self.add_connection_scope_index, 3, 0)
             }
+            Screen::History => {
+                Self::select_index(&mut self.history_index, self.history_items.len(), 0)
+            }
+            Screen::PipelineBuilder => self.pipeline_cursor_to(false),
         }
     }
 
@@ -56,7 +66,15 @@ impl App {
             Screen::Indexes => Self::select_last(&mut self.index_index, self.indexes.len()),
             Screen::IndexView => self.index_scroll = self.max_index_scroll(),
             Screen::Documents => Self::select_last(&mut self.document_index, self.documents.len()),
-            Screen::DocumentView => self.document_scroll = self.max_document_scroll(),
+            Screen::DocumentView if self.document_json_view => {
+                self.document_scroll = self.max_document_scroll()
+            }
+            Screen::DocumentView => self.tree_cursor_to_end(),
+            Screen::DocumentDiff => {
+                if let Some(diff) = &mut self.document_diff {
+                    diff.scroll = diff.max_scroll();
+                }
+            }
             Screen::ExportFormatSelect => Self::select_last(&mut self.export_format_index, 3),
             Screen::SavedQuerySelect => {
                 Self::select_last(&mut self.saved_query_index, self.storage.queries.len())
@@ -71,6 +89,8 @@ impl App {
             Screen::AddConnectionScopeSelect => {
                 Self::select_last(&mut self.add_connection_scope_index, 3)
             }
+            Screen::History => Self::select_last(&mut self.history_index, self.history_items.len()),
+            Screen::PipelineBuilder => self.pipeline_cursor_to(true),
         }
     }
 
@@ -92,7 +112,13 @@ impl App {
             Screen::Documents => {
                 Self::move_selection(&mut self.document_index, self.documents.len(), -1)
             }
-            Screen::DocumentView => self.scroll_document(-1),
+            Screen::DocumentView if self.document_json_view => self.scroll_document(-1),
+            Screen::DocumentView => self.move_tree_cursor(-1),
+            Screen::DocumentDiff => {
+                if let Some(diff) = &mut self.document_diff {
+                    diff.scroll_by(-1);
+                }
+            }
             Screen::ExportFormatSelect => {
                 Self::move_selection(&mut self.export_format_index, 3, -1)
             }
@@ -115,6 +141,10 @@ impl App {
                 3, // Session-only, Repo, Global
                 -1,
             ),
+            Screen::History => {
+                Self::move_selection(&mut self.history_index, self.history_items.len(), -1)
+            }
+            Screen::PipelineBuilder => self.move_pipeline_cursor(-1),
         }
     }
 
@@ -136,7 +166,13 @@ impl App {
             Screen::Documents => {
                 Self::move_selection(&mut self.document_index, self.documents.len(), 1)
             }
-            Screen::DocumentView => self.scroll_document(1),
+            Screen::DocumentView if self.document_json_view => self.scroll_document(1),
+            Screen::DocumentView => self.move_tree_cursor(1),
+            Screen::DocumentDiff => {
+                if let Some(diff) = &mut self.document_diff {
+                    diff.scroll_by(1);
+                }
+            }
             Screen::ExportFormatSelect => Self::move_selection(&mut self.export_format_index, 3, 1),
             Screen::SavedQuerySelect => {
                 Self::move_selection(&mut self.saved_query_index, self.storage.queries.len(), 1)
@@ -157,6 +193,10 @@ impl App {
                 3, // Session-only, Repo, Global
                 1,
             ),
+            Screen::History => {
+                Self::move_selection(&mut self.history_index, self.history_items.len(), 1)
+            }
+            Screen::PipelineBuilder => self.move_pipeline_cursor(1),
         }
     }
 
@@ -168,7 +208,13 @@ impl App {
             Screen::Indexes => self.screen = Screen::Collections,
             Screen::IndexView => self.screen = Screen::Indexes,
             Screen::Documents => self.screen = Screen::Collections,
-            Screen::DocumentView => self.screen = Screen::Documents,
+            Screen::DocumentView if self.document_json_view => {
+                if !self.reference_back() {
+                    self.screen = Screen::Documents;
+                }
+            }
+            Screen::DocumentView => self.collapse_tree_node(),
+            Screen::DocumentDiff => self.close_document_diff(),
             Screen::ExportFormatSelect => {
                 self.screen = self.export_return_screen.unwrap_or(Screen::Documents);
                 self.export_action = None;
@@ -189,6 +235,7 @@ impl App {
                 self.screen = Screen::Connections;
                 self.add_connection_scope_index = Some(0);
             }
+            Screen::History | Screen::PipelineBuilder => self.screen = Screen::Documents,
         }
     }
 
@@ -240,7 +287,12 @@ impl App {
                     self.screen = Screen::DocumentView;
                 }
             }
-            Screen::DocumentView => {}
+            Screen::DocumentView => {
+                if !self.document_json_view {
+                    self.expand_tree_node();
+                }
+            }
+            Screen::DocumentDiff => {}
             Screen::ExportFormatSelect => {
                 if let Err(error) = self.select_export_format() {
                     self.set_error_message(&error);
@@ -271,16 +323,31 @@ impl App {
                     self.set_error_message(&error);
                 }
             }
+            Screen::History => {
+                if let Err(error) = self.rerun_history_entry() {
+                    self.set_error_message(&error);
+                }
+            }
+            Screen::PipelineBuilder => {
+                if let Err(error) = self.preview_pipeline_stage() {
+                    self.set_error_message(&error);
+                }
+            }
         }
 
         Ok(())
     }
 
-    pub(crate) fn next_page(&mut self) -> Result<()> {
+    /// Moves `count` pages forward with a single load.
+    pub(crate) fn next_page(&mut self, count: usize) -> Result<()> {
+        if self.screen == Screen::PipelineBuilder {
+            self.scroll_pipeline_preview(preview_scroll(count));
+            return Ok(());
+        }
         if self.screen != Screen::Documents {
             return Ok(());
         }
-        self.document_page += 1;
+        self.document_page = self.document_page.saturating_add(count as u64);
         if let Err(error) = self.start_load_documents(None, DocumentLoadReason::NavigateNext) {
             self.set_error_message(&error);
             return Ok(());
@@ -288,14 +355,19 @@ impl App {
         Ok(())
     }
 
-    pub(crate) fn previous_page(&mut self) -> Result<()> {
+    /// Moves `count` pages back, stopping at the first page, with a single load.
+    pub(crate) fn previous_page(&mut self, count: usize) -> Result<()> {
+        if self.screen == Screen::PipelineBuilder {
+            self.scroll_pipeline_preview(-preview_scroll(count));
+            return Ok(());
+        }
         if self.screen != Screen::Documents {
             return Ok(());
         }
         if self.document_page == 0 {
             return Ok(());
         }
-        self.document_page -= 1;
+        self.document_page = self.document_page.saturating_sub(count as u64);
         if let Err(error) = self.start_load_documents(None, DocumentLoadReason::NavigatePrevious) {
             self.set_error_message(&error);
         }
@@ -370,6 +442,11 @@ impl App {
     }
 }
 
+/// Preview lines scrolled by `count` page keys in the pipeline builder.
+fn preview_scroll(count: usize) -> i16 {
+    count.saturating_mul(10).min(i16::MAX as usize) as i16
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
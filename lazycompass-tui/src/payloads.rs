@@ -12,6 +12,39 @@ pub(crate) fn render_inline_query_template() -> Result<String> {
     .context("unable to serialize inline query template")
 }
 
+/// Renders a history payload as editable inline draft text.
+pub(crate) fn render_history_payload(payload: &HistoryPayload) -> String {
+    // Keep unparsable fields as strings so the editor still shows what was run.
+    let json = |value: &String| {
+        serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.clone()))
+    };
+    let value = match payload {
+        HistoryPayload::Query {
+            filter,
+            projection,
+            sort,
+            limit,
+        } => {
+            let mut object = serde_json::Map::new();
+            for (field, value) in [
+                ("filter", filter),
+                ("projection", projection),
+                ("sort", sort),
+            ] {
+                if let Some(value) = value {
+                    object.insert(field.to_string(), json(value));
+                }
+            }
+            if let Some(limit) = limit {
+                object.insert("limit".to_string(), (*limit).into());
+            }
+            serde_json::Value::Object(object)
+        }
+        HistoryPayload::Aggregation { pipeline } => json(pipeline),
+    };
+    serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string())
+}
+
 pub(crate) fn parse_inline_query_payload(contents: &str) -> Result<InlineQueryPayload> {
     let contents = normalize_json_text(contents).context("invalid JSON for inline query")?;
     let value: serde_json::Value =
@@ -104,6 +137,7 @@ pub(crate) fn parse_query_payload_input(
         projection,
         sort,
         limit,
+        tags: template.tags.clone(),
     })
 }
 
@@ -178,6 +212,7 @@ pub(crate) fn parse_query_save_input(contents: &str, scope: SavedScope) -> Resul
         projection,
         sort,
         limit,
+        tags: Vec::new(),
     })
 }
 
@@ -224,6 +259,7 @@ pub(crate) fn parse_aggregation_payload_input(
         id: template.id.clone(),
         scope: template.scope.clone(),
         pipeline: serde_json::to_string(&pipeline).context("unable to serialize pipeline JSON")?,
+        tags: template.tags.clone(),
     })
 }
 
@@ -277,6 +313,7 @@ pub(crate) fn parse_aggregation_save_input(
         id,
         scope,
         pipeline: serde_json::to_string(pipeline).context("unable to serialize pipeline JSON")?,
+        tags: Vec::new(),
     })
 }
 
@@ -338,6 +375,7 @@ mod tests {
             projection: None,
             sort: None,
             limit: None,
+            tags: Vec::new(),
         }
     }
 
@@ -364,6 +402,7 @@ mod tests {
             id: "agg".to_string(),
             scope: SavedScope::Shared,
             pipeline: "[]".to_string(),
+            tags: Vec::new(),
         };
         let err = parse_aggregation_payload_input(r#"{ "x": 1 }"#, &template)
             .expect_err("expected array payload");
@@ -382,6 +421,7 @@ mod tests {
             projection: Some(r#"{"email":1}"#.to_string()),
             sort: Some(r#"{"createdAt":-1}"#.to_string()),
             limit: Some(10),
+            tags: Vec::new(),
         };
         let rendered = render_query_payload_template(&template).expect("render");
         assert!(rendered.contains("\"filter\""));
@@ -0,0 +1,595 @@
+use super::*;
+use errors::is_network_error;
+use lazycompass_mongo::{
+    AggregationSpec, is_known_pipeline_stage, is_pipeline_write_stage, normalize_json_text,
+};
+
+/// Documents shown when previewing the pipeline up to a stage.
+const PREVIEW_LIMIT: u64 = 10;
+const NEW_STAGE_TEMPLATE: &str = "{\n  \"$match\": {}\n}\n";
+
+/// One stage as the user wrote it; it is validated when the pipeline is previewed, run, or saved
+/// so a typo never loses the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PipelineStage {
+    text: String,
+    pub(crate) enabled: bool,
+}
+
+impl PipelineStage {
+    fn new(text: String) -> Self {
+        Self {
+            text,
+            enabled: true,
+        }
+    }
+
+    /// Compact JSON for the stage, or why it is not a valid stage.
+    fn compact(&self) -> Result<String> {
+        let normalized = normalize_json_text(&self.text).context("invalid JSON")?;
+        let value: serde_json::Value = serde_json::from_str(&normalized).context("invalid JSON")?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| anyhow::anyhow!("a stage must be a JSON object"))?;
+        let mut keys = object.keys();
+        let (Some(name), None) = (keys.next(), keys.next()) else {
+            anyhow::bail!("a stage must have exactly one field, such as {{\"$match\": {{}}}}");
+        };
+        if !is_known_pipeline_stage(name) {
+            anyhow::bail!("unknown stage '{name}'");
+        }
+        serde_json::to_string(&value).context("unable to serialize stage")
+    }
+
+    fn name(&self) -> Option<String> {
+        let normalized = normalize_json_text(&self.text).ok()?;
+        let value: serde_json::Value = serde_json::from_str(&normalized).ok()?;
+        value.as_object()?.keys().next().cloned()
+    }
+
+    fn label(&self) -> String {
+        match self.compact() {
+            Ok(compact) => compact,
+            Err(_) => self.text.split_whitespace().collect::<Vec<_>>().join(" "),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum PreviewState {
+    Loading,
+    Documents(Vec<Document>),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct StagePreview {
+    /// Index of the stage whose output is shown.
+    pub(crate) stage: usize,
+    pub(crate) state: PreviewState,
+    pub(crate) scroll: u16,
+}
+
+/// Stages of the pipeline being built for one collection, with the preview of the stage last
+/// selected.
+#[derive(Debug, Clone)]
+pub(crate) struct PipelineBuilder {
+    database: String,
+    collection: String,
+    pub(crate) stages: Vec<PipelineStage>,
+    pub(crate) cursor: usize,
+    pub(crate) preview: Option<StagePreview>,
+    preview_load_id: Option<u64>,
+}
+
+/// An enabled stage in a pipeline prefix, numbered as shown in the builder.
+#[derive(Debug, Clone)]
+struct PrefixStage {
+    number: usize,
+    name: String,
+    json: String,
+}
+
+impl PipelineBuilder {
+    fn new(database: String, collection: String, pipeline: Option<&str>) -> Self {
+        let stages = pipeline
+            .and_then(|pipeline| serde_json::from_str::<Vec<serde_json::Value>>(pipeline).ok())
+            .unwrap_or_default()
+            .iter()
+            .map(|stage| {
+                PipelineStage::new(serde_json::to_string_pretty(stage).unwrap_or_default())
+            })
+            .collect();
+        Self {
+            database,
+            collection,
+            stages,
+            cursor: 0,
+            preview: None,
+            preview_load_id: None,
+        }
+    }
+
+    /// Enabled stages up to and including `end`, failing on the first invalid one.
+    fn prefix(&self, end: usize) -> Result<Vec<PrefixStage>> {
+        self.stages
+            .iter()
+            .enumerate()
+            .take(end + 1)
+            .filter(|(_, stage)| stage.enabled)
+            .map(|(index, stage)| {
+                let json = stage
+                    .compact()
+                    .with_context(|| format!("stage {} is invalid", index + 1))?;
+                Ok(PrefixStage {
+                    number: index + 1,
+                    name: stage.name().unwrap_or_default(),
+                    json,
+                })
+            })
+            .collect()
+    }
+
+    /// The enabled stages as a pipeline JSON array.
+    pub(crate) fn pipeline(&self) -> Result<String> {
+        if self.stages.is_empty() {
+            anyhow::bail!("add a stage first");
+        }
+        Ok(pipeline_json(&self.prefix(self.stages.len() - 1)?, None))
+    }
+
+    pub(crate) fn stage_items(&self) -> Vec<String> {
+        self.stages
+            .iter()
+            .enumerate()
+            .map(|(index, stage)| {
+                let marker = match (stage.enabled, stage.compact().is_ok()) {
+                    (_, false) => "!",
+                    (true, true) => "✓",
+                    (false, true) => "·",
+                };
+                format!("{marker} {:>2}  {}", index + 1, stage.label())
+            })
+            .collect()
+    }
+
+    pub(crate) fn preview_title(&self) -> String {
+        let Some(preview) = &self.preview else {
+            return "Preview".to_string();
+        };
+        let stage = preview.stage + 1;
+        match &preview.state {
+            PreviewState::Loading => format!("Preview after stage {stage} (running...)"),
+            PreviewState::Documents(documents) => format!(
+                "Preview after stage {stage} ({} document(s), limit {PREVIEW_LIMIT})",
+                documents.len()
+            ),
+            PreviewState::Failed(_) => format!("Preview after stage {stage} (failed)"),
+        }
+    }
+
+    fn move_stage(&mut self, delta: isize) {
+        let Some(target) = self.cursor.checked_add_signed(delta) else {
+            return;
+        };
+        if target >= self.stages.len() {
+            return;
+        }
+        self.stages.swap(self.cursor, target);
+        self.cursor = target;
+        self.preview = None;
+    }
+}
+
+fn pipeline_json(stages: &[PrefixStage], limit: Option<u64>) -> String {
+    let mut items = stages
+        .iter()
+        .map(|stage| stage.json.clone())
+        .collect::<Vec<_>>();
+    if let Some(limit) = limit {
+        items.push(format!("{{\"$limit\":{limit}}}"));
+    }
+    format!("[{}]", items.join(","))
+}
+
+/// Runs `stages` with a `$limit`; when the server rejects it, re-runs shorter prefixes so the
+/// error names the first stage that fails.
+async fn run_stage_preview(
+    config: &Config,
+    guard: WriteGuard,
+    spec: AggregationSpec,
+    stages: Vec<PrefixStage>,
+) -> Result<Vec<Document>> {
+    let executor = MongoExecutor::new();
+    let prefix_spec = |count: usize, limit: u64| AggregationSpec {
+        pipeline: pipeline_json(&stages[..count], Some(limit)),
+        ..spec.clone()
+    };
+    let error = match executor
+        .execute_aggregation(config, guard, &prefix_spec(stages.len(), PREVIEW_LIMIT))
+        .await
+    {
+        Ok(documents) => return Ok(documents),
+        Err(error) if is_network_error(&error) || stages.is_empty() => return Err(error),
+        Err(error) => error,
+    };
+    for count in 1..stages.len() {
+        if let Err(error) = executor
+            .execute_aggregation(config, guard, &prefix_spec(count, 1))
+            .await
+        {
+            let stage = &stages[count - 1];
+            return Err(error.context(format!("stage {} ({}) failed", stage.number, stage.name)));
+        }
+    }
+    let stage = &stages[stages.len() - 1];
+    Err(error.context(format!("stage {} ({}) failed", stage.number, stage.name)))
+}
+
+impl App {
+    /// Opens the builder for the current collection, starting from the inline aggregation
+    /// draft the first time.
+    pub(crate) fn open_pipeline_builder(&mut self) -> Result<()> {
+        if self.screen != Screen::Documents {
+            return Ok(());
+        }
+        let (_, database, collection) = self.selected_context()?;
+        let reuse = self.pipeline_builder.as_ref().is_some_and(|builder| {
+            builder.database == database && builder.collection == collection
+        });
+        if !reuse {
+            let draft = self
+                .inline_aggregation_draft
+                .as_ref()
+                .and_then(|draft| draft.parsed.as_ref())
+                .map(|payload| payload.pipeline.as_str());
+            self.pipeline_builder = Some(PipelineBuilder::new(database, collection, draft));
+        }
+        self.screen = Screen::PipelineBuilder;
+        self.message = None;
+        Ok(())
+    }
+
+    pub(crate) fn pipeline_builder_mut(&mut self) -> Option<&mut PipelineBuilder> {
+        if self.screen != Screen::PipelineBuilder {
+            return None;
+        }
+        self.pipeline_builder.as_mut()
+    }
+
+    pub(crate) fn move_pipeline_cursor(&mut self, delta: isize) {
+        if let Some(builder) = self.pipeline_builder_mut() {
+            let last = builder.stages.len().saturating_sub(1);
+            builder.cursor = builder.cursor.saturating_add_signed(delta).min(last);
+        }
+    }
+
+    pub(crate) fn pipeline_cursor_to(&mut self, end: bool) {
+        if let Some(builder) = self.pipeline_builder_mut() {
+            builder.cursor = if end {
+                builder.stages.len().saturating_sub(1)
+            } else {
+                0
+            };
+        }
+    }
+
+    pub(crate) fn scroll_pipeline_preview(&mut self, delta: i16) {
+        if let Some(preview) = self
+            .pipeline_builder_mut()
+            .and_then(|builder| builder.preview.as_mut())
+        {
+            preview.scroll = preview.scroll.saturating_add_signed(delta);
+        }
+    }
+
+    pub(crate) fn apply_pipeline_action(&mut self, action: KeyAction) {
+        let Some(builder) = self.pipeline_builder_mut() else {
+            return;
+        };
+        match action {
+            KeyAction::MoveStageUp => builder.move_stage(-1),
+            KeyAction::MoveStageDown => builder.move_stage(1),
+            KeyAction::ToggleSelect => {
+                if let Some(stage) = builder.stages.get_mut(builder.cursor) {
+                    stage.enabled = !stage.enabled;
+                    builder.preview = None;
+                }
+            }
+            KeyAction::Delete if builder.cursor < builder.stages.len() => {
+                builder.stages.remove(builder.cursor);
+                builder.cursor = builder.cursor.min(builder.stages.len().saturating_sub(1));
+                builder.preview = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Opens the stage under the cursor, or a new stage after it, in the editor.
+    pub(crate) fn edit_pipeline_stage(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        insert: bool,
+    ) -> Result<()> {
+        let Some(builder) = self.pipeline_builder_mut() else {
+            return Ok(());
+        };
+        if !insert && builder.stages.is_empty() {
+            self.message = Some("no stage to edit; add one first".to_string());
+            return Ok(());
+        }
+        let action = PendingEditorAction::EditPipelineStage { insert };
+        let Some(_) = self.ensure_editor_command(action.clone())? else {
+            return Ok(());
+        };
+        self.perform_editor_action(action, terminal)
+    }
+
+    pub(crate) fn edit_pipeline_stage_with_editor(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        insert: bool,
+    ) -> Result<()> {
+        let editor = self
+            .editor_command
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("editor command missing"))?;
+        let builder = self
+            .pipeline_builder
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("open the pipeline builder first"))?;
+        let initial = match builder.stages.get(builder.cursor) {
+            Some(stage) if !insert => stage.text.clone(),
+            _ => NEW_STAGE_TEMPLATE.to_string(),
+        };
+        let contents = self.open_editor(terminal, &editor, "pipeline_stage", &initial)?;
+        if contents.trim().is_empty() || (insert && is_editor_cancelled(&contents, &initial)) {
+            self.message = Some("cancelled".to_string());
+            return Ok(());
+        }
+        let Some(builder) = self.pipeline_builder.as_mut() else {
+            return Ok(());
+        };
+        if insert {
+            let index = if builder.stages.is_empty() {
+                0
+            } else {
+                builder.cursor + 1
+            };
+            builder.stages.insert(index, PipelineStage::new(contents));
+            builder.cursor = index;
+        } else if let Some(stage) = builder.stages.get_mut(builder.cursor) {
+            stage.text = contents;
+        }
+        self.preview_pipeline_stage()
+    }
+
+    /// Runs the enabled stages up to the cursor with a `$limit` and shows the output.
+    pub(crate) fn preview_pipeline_stage(&mut self) -> Result<()> {
+        let (connection, database, collection) = self.selected_context()?;
+        let request_id = self.next_load_id();
+        let config = self.storage.config.clone();
+        let guard = self.write_guard();
+        let Some(builder) = self.pipeline_builder.as_mut() else {
+            return Ok(());
+        };
+        if builder.stages.is_empty() {
+            return Ok(());
+        }
+        let stage = builder.cursor;
+        let failed = |message: String| StagePreview {
+            stage,
+            state: PreviewState::Failed(message),
+            scroll: 0,
+        };
+        builder.preview_load_id = None;
+        let stages = match builder.prefix(stage) {
+            Ok(stages) => stages,
+            Err(error) => {
+                builder.preview = Some(failed(format_error(&error)));
+                return Ok(());
+            }
+        };
+        if let Some(write) = stages
+            .iter()
+            .find(|prefix| is_pipeline_write_stage(&prefix.name))
+        {
+            builder.preview = Some(failed(format!(
+                "stage {} ({}) writes data; preview a stage before it or run the pipeline",
+                write.number, write.name
+            )));
+            return Ok(());
+        }
+        builder.preview = Some(StagePreview {
+            stage,
+            state: PreviewState::Loading,
+            scroll: 0,
+        });
+        builder.preview_load_id = Some(request_id);
+        let spec = AggregationSpec {
+            connection: Some(connection),
+            database,
+            collection,
+            pipeline: String::new(),
+        };
+        let sender = self.load_tx.clone();
+        self.runtime.spawn(async move {
+            let result = run_stage_preview(&config, guard, spec, stages).await;
+            let _ = sender.send(LoadResult::PipelinePreview {
+                id: request_id,
+                result,
+            });
+        });
+        Ok(())
+    }
+
+    pub(crate) fn apply_pipeline_preview(&mut self, id: u64, result: Result<Vec<Document>>) {
+        let Some(builder) = self.pipeline_builder.as_mut() else {
+            return;
+        };
+        if builder.preview_load_id != Some(id) {
+            return;
+        }
+        builder.preview_load_id = None;
+        if let Some(preview) = builder.preview.as_mut() {
+            preview.state = match result {
+                Ok(documents) => PreviewState::Documents(documents),
+                Err(error) => PreviewState::Failed(format_error(&error)),
+            };
+        }
+    }
+
+    /// Runs the enabled stages as the inline aggregation and shows the results.
+    pub(crate) fn run_pipeline_builder(&mut self) -> Result<()> {
+        let Some(builder) = self.pipeline_builder_mut() else {
+            return Ok(());
+        };
+        let payload = InlineAggregationPayload {
+            pipeline: builder.pipeline()?,
+        };
+        self.inline_aggregation_draft = Some(InlineAggregationDraft {
+            raw: render_pipeline_draft(&payload.pipeline)?,
+            parsed: Some(payload.clone()),
+        });
+        self.active_inline_draft = Some(InlineDraftKind::Aggregation);
+        self.screen = Screen::Documents;
+        self.start_execute_inline_aggregation(payload)
+    }
+
+    /// Saves the enabled stages as a saved aggregation through the usual scope prompt.
+    pub(crate) fn save_pipeline_builder(&mut self) -> Result<()> {
+        let Some(builder) = self.pipeline_builder_mut() else {
+            return Ok(());
+        };
+        let payload = InlineAggregationPayload {
+            pipeline: builder.pipeline()?,
+        };
+        self.aggregation_save_source = AggregationSaveSource::InlineDraft(payload);
+        self.save_agg_scope_index = Some(0);
+        self.screen = Screen::SaveAggregationScopeSelect;
+        self.message = Some("select save mode for aggregation".to_string());
+        Ok(())
+    }
+
+    /// Lines for the preview pane: the sampled documents as JSON, or the error.
+    pub(crate) fn pipeline_preview_lines(&self) -> Vec<Line<'static>> {
+        let Some(preview) = self
+            .pipeline_builder
+            .as_ref()
+            .and_then(|builder| builder.preview.as_ref())
+        else {
+            return vec![Line::from(
+                "Enter previews the output up to the selected stage",
+            )];
+        };
+        match &preview.state {
+            PreviewState::Loading => vec![Line::from("running...")],
+            PreviewState::Failed(message) => {
+                vec![Line::from(Span::styled(
+                    message.clone(),
+                    self.theme.error_style(),
+                ))]
+            }
+            PreviewState::Documents(documents) if documents.is_empty() => {
+                vec![Line::from("no documents")]
+            }
+            PreviewState::Documents(documents) => documents
+                .iter()
+                .flat_map(|document| {
+                    format_document(document)
+                        .into_iter()
+                        .chain(std::iter::once(String::new()))
+                })
+                .map(Line::from)
+                .collect(),
+        }
+    }
+}
+
+fn render_pipeline_draft(pipeline: &str) -> Result<String> {
+    let value: serde_json::Value =
+        serde_json::from_str(pipeline).context("unable to render pipeline draft")?;
+    serde_json::to_string_pretty(&value).context("unable to render pipeline draft")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder(stages: &[&str]) -> PipelineBuilder {
+        let mut builder = PipelineBuilder::new("app".to_string(), "orders".to_string(), None);
+        builder.stages = stages
+            .iter()
+            .map(|stage| PipelineStage::new(stage.to_string()))
+            .collect();
+        builder
+    }
+
+    #[test]
+    fn stages_validate_one_known_operator() {
+        let stage = PipelineStage::new(
+            "{ \"$match\": { \"_id\": ObjectId(\"65f0a1b2c3d4e5f601234567\") } }".to_string(),
+        );
+        assert_eq!(
+            stage.compact().expect("valid"),
+            r#"{"$match":{"_id":{"$oid":"65f0a1b2c3d4e5f601234567"}}}"#
+        );
+        let error = PipelineStage::new(r#"{"$mtach": {}}"#.to_string())
+            .compact()
+            .expect_err("unknown");
+        assert_eq!(error.to_string(), "unknown stage '$mtach'");
+        assert!(
+            PipelineStage::new(r#"{"$match": {}, "$limit": 1}"#.to_string())
+                .compact()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn prefix_skips_disabled_stages_and_names_invalid_ones() {
+        let mut builder = builder(&[
+            r#"{"$match": {"status": "open"}}"#,
+            r#"{"$sort": {"total": -1}}"#,
+            r#"{"$group": "#,
+        ]);
+        builder.stages[1].enabled = false;
+        let prefix = builder.prefix(1).expect("prefix");
+        assert_eq!(
+            pipeline_json(&prefix, Some(PREVIEW_LIMIT)),
+            r#"[{"$match":{"status":"open"}},{"$limit":10}]"#
+        );
+        let error = builder.prefix(2).expect_err("invalid stage");
+        assert_eq!(error.to_string(), "stage 3 is invalid");
+        assert!(builder.stage_items()[2].starts_with("!  3"));
+        assert!(builder.stage_items()[1].starts_with("·  2"));
+    }
+
+    #[test]
+    fn builder_reorders_toggles_and_saves_enabled_stages() {
+        let mut app = App::test_app();
+        app.screen = Screen::PipelineBuilder;
+        app.pipeline_builder = Some(builder(&[
+            r#"{"$match": {}}"#,
+            r#"{"$limit": 5}"#,
+            r#"{"$project": {"total": 1}}"#,
+        ]));
+        app.apply_pipeline_action(KeyAction::MoveStageDown);
+        app.apply_pipeline_action(KeyAction::ToggleSelect);
+        app.move_pipeline_cursor(1);
+        app.apply_pipeline_action(KeyAction::Delete);
+        let builder = app.pipeline_builder.as_ref().expect("builder");
+        assert_eq!(builder.cursor, 1);
+        assert_eq!(builder.stages.len(), 2);
+        assert!(!builder.stages[1].enabled);
+        assert_eq!(builder.pipeline().expect("pipeline"), r#"[{"$limit":5}]"#);
+
+        app.save_pipeline_builder().expect("save");
+        assert_eq!(app.screen, Screen::SaveAggregationScopeSelect);
+        match &app.aggregation_save_source {
+            AggregationSaveSource::InlineDraft(payload) => {
+                assert_eq!(payload.pipeline, r#"[{"$limit":5}]"#)
+            }
+            other => panic!("unexpected save source {other:?}"),
+        }
+    }
+}
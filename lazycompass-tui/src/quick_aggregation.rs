@@ -0,0 +1,354 @@
+use super::*;
+
+/// Multi-line pipeline editor shown over the documents, validated on every edit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct QuickAggregationModalState {
+    pub(crate) text: String,
+    /// Byte offset into `text`.
+    pub(crate) cursor: usize,
+    /// Stage count when the text parses as a pipeline, or the parse error.
+    pub(crate) validation: Result<usize, String>,
+}
+
+impl QuickAggregationModalState {
+    pub(crate) fn from_draft(draft: Option<&InlineAggregationDraft>) -> Result<Self> {
+        let text = match draft {
+            Some(draft) => draft.raw.clone(),
+            None => render_inline_aggregation_template()?,
+        };
+        let mut modal = Self {
+            cursor: text.len(),
+            text,
+            validation: Ok(0),
+        };
+        modal.validate();
+        Ok(modal)
+    }
+
+    fn validate(&mut self) {
+        self.validation = parse_inline_aggregation_payload(&self.text)
+            .and_then(|payload| {
+                let stages: Vec<serde_json::Value> = serde_json::from_str(&payload.pipeline)?;
+                Ok(stages.len())
+            })
+            .map_err(|error| format_error(&error));
+    }
+
+    /// Replaces the text from `start` to the cursor.
+    pub(crate) fn replace(&mut self, start: usize, text: &str) {
+        self.text.replace_range(start..self.cursor, text);
+        self.cursor = start + text.len();
+        self.validate();
+    }
+
+    fn insert(&mut self, text: &str) {
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+        self.validate();
+    }
+
+    /// Inserts a newline keeping the current line's indentation.
+    fn newline(&mut self) {
+        let (line, _) = self.cursor_position();
+        let indent = self
+            .text
+            .lines()
+            .nth(line)
+            .map(|line| line.len() - line.trim_start().len())
+            .unwrap_or(0);
+        self.insert(&format!("\n{}", " ".repeat(indent)));
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor == 0 {
+            return;
+        }
+        let start = prev_char_boundary(&self.text, self.cursor);
+        self.text.drain(start..self.cursor);
+        self.cursor = start;
+        self.validate();
+    }
+
+    fn delete(&mut self) {
+        if self.cursor >= self.text.len() {
+            return;
+        }
+        let end = next_char_boundary(&self.text, self.cursor);
+        self.text.drain(self.cursor..end);
+        self.validate();
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        self.text[..offset].rfind('\n').map_or(0, |index| index + 1)
+    }
+
+    fn line_end(&self, offset: usize) -> usize {
+        self.text[offset..]
+            .find('\n')
+            .map_or(self.text.len(), |index| offset + index)
+    }
+
+    /// Line and character column of the cursor.
+    pub(crate) fn cursor_position(&self) -> (usize, usize) {
+        let line = self.text[..self.cursor].matches('\n').count();
+        let column = self.text[self.line_start(self.cursor)..self.cursor]
+            .chars()
+            .count();
+        (line, column)
+    }
+
+    /// Moves up or down one line, keeping the column where the target line is long enough.
+    fn move_vertical(&mut self, down: bool) {
+        let (_, column) = self.cursor_position();
+        let start = self.line_start(self.cursor);
+        let target_start = if down {
+            let end = self.line_end(self.cursor);
+            if end == self.text.len() {
+                return;
+            }
+            end + 1
+        } else {
+            if start == 0 {
+                return;
+            }
+            self.line_start(start - 1)
+        };
+        let target_end = self.line_end(target_start);
+        self.cursor = self.text[target_start..target_end]
+            .char_indices()
+            .nth(column)
+            .map_or(target_end, |(index, _)| target_start + index);
+    }
+
+    /// Byte offsets of the bracket next to the cursor and its partner, ignoring brackets inside
+    /// strings.
+    pub(crate) fn matching_bracket(&self) -> Option<(usize, usize)> {
+        let brackets = bracket_positions(&self.text);
+        let at = |offset: usize| brackets.iter().position(|(index, _)| *index == offset);
+        let current = at(self.cursor).or_else(|| {
+            let previous = prev_char_boundary(&self.text, self.cursor);
+            (previous < self.cursor).then(|| at(previous)).flatten()
+        })?;
+        let (offset, bracket) = brackets[current];
+        let mut depth = 0usize;
+        if matches!(bracket, '[' | '{') {
+            for &(index, other) in &brackets[current..] {
+                if matches!(other, '[' | '{') {
+                    depth += 1;
+                } else {
+                    depth -= 1;
+                    if depth == 0 {
+                        return closes(bracket, other).then_some((offset, index));
+                    }
+                }
+            }
+        } else {
+            for &(index, other) in brackets[..=current].iter().rev() {
+                if matches!(other, ']' | '}') {
+                    depth += 1;
+                } else {
+                    depth -= 1;
+                    if depth == 0 {
+                        return closes(other, bracket).then_some((offset, index));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn closes(open: char, close: char) -> bool {
+    matches!((open, close), ('[', ']') | ('{', '}'))
+}
+
+/// Brackets outside string literals, with their byte offsets.
+fn bracket_positions(text: &str) -> Vec<(usize, char)> {
+    let mut brackets = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, ch) in text.char_indices() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '[' | ']' | '{' | '}' => brackets.push((index, ch)),
+            _ => {}
+        }
+    }
+    brackets
+}
+
+impl App {
+    pub(crate) fn open_quick_aggregation_modal(&mut self) -> Result<()> {
+        if self.screen != Screen::Documents {
+            return Ok(());
+        }
+        let _ = self.selected_context()?;
+        self.quick_aggregation_modal = Some(QuickAggregationModalState::from_draft(
+            self.inline_aggregation_draft.as_ref(),
+        )?);
+        self.message = None;
+        Ok(())
+    }
+
+    pub(crate) fn handle_quick_aggregation_modal_key(
+        &mut self,
+        key: KeyEvent,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<bool> {
+        let Some(mut modal) = self.quick_aggregation_modal.take() else {
+            return Ok(false);
+        };
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Esc => {
+                self.message = Some("cancelled".to_string());
+            }
+            KeyCode::Char('r') if control => match parse_inline_aggregation_payload(&modal.text) {
+                Ok(payload) => {
+                    self.inline_aggregation_draft = Some(InlineAggregationDraft {
+                        raw: modal.text,
+                        parsed: Some(payload.clone()),
+                    });
+                    self.active_inline_draft = Some(InlineDraftKind::Aggregation);
+                    self.start_execute_inline_aggregation(payload)?;
+                }
+                Err(error) => {
+                    self.quick_aggregation_modal = Some(modal);
+                    self.set_error_message(&error);
+                }
+            },
+            KeyCode::Char('e') if control => {
+                self.quick_aggregation_modal = Some(modal);
+                let action = PendingEditorAction::RunInlineAggregation;
+                let Some(_) = self.ensure_editor_command(action.clone())? else {
+                    self.pending_keys.clear();
+                    return Ok(false);
+                };
+                if let Err(error) = self.perform_editor_action(action, terminal) {
+                    self.set_error_message(&error);
+                }
+            }
+            KeyCode::Enter => {
+                modal.newline();
+                self.quick_aggregation_modal = Some(modal);
+            }
+            KeyCode::Tab => {
+                match modal.complete_operator().as_slice() {
+                    [] => modal.insert("  "),
+                    [_] => {}
+                    candidates => self.message = Some(candidates.join("  ")),
+                }
+                self.quick_aggregation_modal = Some(modal);
+            }
+            KeyCode::Backspace => {
+                modal.backspace();
+                self.quick_aggregation_modal = Some(modal);
+            }
+            KeyCode::Delete => {
+                modal.delete();
+                self.quick_aggregation_modal = Some(modal);
+            }
+            KeyCode::Left => {
+                modal.cursor = prev_char_boundary(&modal.text, modal.cursor);
+                self.quick_aggregation_modal = Some(modal);
+            }
+            KeyCode::Right => {
+                modal.cursor = next_char_boundary(&modal.text, modal.cursor);
+                self.quick_aggregation_modal = Some(modal);
+            }
+            KeyCode::Up | KeyCode::Down => {
+                modal.move_vertical(key.code == KeyCode::Down);
+                self.quick_aggregation_modal = Some(modal);
+            }
+            KeyCode::Home => {
+                modal.cursor = modal.line_start(modal.cursor);
+                self.quick_aggregation_modal = Some(modal);
+            }
+            KeyCode::End => {
+                modal.cursor = modal.line_end(modal.cursor);
+                self.quick_aggregation_modal = Some(modal);
+            }
+            KeyCode::Char(ch) => {
+                if !ch.is_control() && !control {
+                    modal.insert(ch.encode_utf8(&mut [0; 4]));
+                }
+                self.quick_aggregation_modal = Some(modal);
+            }
+            _ => {
+                self.quick_aggregation_modal = Some(modal);
+            }
+        }
+
+        self.pending_keys.clear();
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modal(text: &str, cursor: usize) -> QuickAggregationModalState {
+        let mut modal = QuickAggregationModalState {
+            text: text.to_string(),
+            cursor,
+            validation: Ok(0),
+        };
+        modal.validate();
+        modal
+    }
+
+    #[test]
+    fn validation_follows_each_edit() {
+        let mut modal = modal(r#"[{"$match": {}}"#, 15);
+        assert!(modal.validation.is_err());
+        modal.insert("]");
+        assert_eq!(modal.validation, Ok(1));
+        modal.backspace();
+        modal.insert(r#", {"$limit": 5}]"#);
+        assert_eq!(modal.validation, Ok(2));
+
+        let draft = InlineAggregationDraft {
+            raw: modal.text.clone(),
+            parsed: None,
+        };
+        let reopened = QuickAggregationModalState::from_draft(Some(&draft)).expect("modal");
+        assert_eq!(reopened.text, modal.text);
+        assert_eq!(reopened.validation, Ok(2));
+    }
+
+    #[test]
+    fn brackets_match_outside_strings() {
+        let text = r#"[{"$match": {"name": "a}b"}}]"#;
+        assert_eq!(modal(text, 0).matching_bracket(), Some((0, 28)));
+        assert_eq!(modal(text, 27).matching_bracket(), Some((27, 1)));
+        assert_eq!(modal(text, text.len()).matching_bracket(), Some((28, 0)));
+        assert_eq!(modal(text, 12).matching_bracket(), Some((12, 26)));
+        assert_eq!(modal(r#"[{"a": 1]"#, 0).matching_bracket(), None);
+        assert_eq!(modal(text, 5).matching_bracket(), None);
+    }
+
+    #[test]
+    fn cursor_moves_between_lines_and_keeps_indent() {
+        let mut modal = modal("[\n  {\"$match\": {}},\n  {}\n]", 0);
+        modal.move_vertical(true);
+        modal.move_vertical(true);
+        assert_eq!(modal.cursor_position(), (2, 0));
+        modal.cursor = modal.line_end(modal.cursor);
+        assert_eq!(modal.cursor_position(), (2, 4));
+        modal.move_vertical(false);
+        assert_eq!(modal.cursor_position(), (1, 4));
+        modal.newline();
+        assert_eq!(modal.cursor_position(), (2, 2));
+    }
+}
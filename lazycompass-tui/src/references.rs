@@ -0,0 +1,349 @@
+use super::*;
+
+use lazycompass_core::ReferenceRule;
+use lazycompass_mongo::{QuerySpec, render_relaxed_extjson_string};
+
+const MAX_REFERENCE_DEPTH: usize = 50;
+
+/// Collection and field a followed value is looked up by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ReferenceTarget {
+    pub(crate) collection: String,
+    pub(crate) field: String,
+}
+
+/// Document list state left by following a reference, restored by going back.
+#[derive(Debug, Clone)]
+pub(crate) struct ReferenceFrame {
+    collection_index: Option<usize>,
+    documents: Vec<Document>,
+    document_index: Option<usize>,
+    document_page: u64,
+    document_result_source: DocumentResultSource,
+    document_tree: DocumentTree,
+}
+
+/// Where a field points: the first configured rule for `collection.path` (array indexes
+/// ignored), otherwise a collection named after an ObjectId field (`userId`, `user_id`, or
+/// `user._id` → `users`).
+pub(crate) fn resolve_reference(
+    rules: &[ReferenceRule],
+    collection: &str,
+    path: &str,
+    value: &Bson,
+    collections: &[String],
+) -> Result<ReferenceTarget> {
+    let segments: Vec<&str> = path
+        .split('.')
+        .filter(|segment| segment.parse::<usize>().is_err())
+        .collect();
+    let field = segments.join(".");
+    if let Some(rule) = rules
+        .iter()
+        .find(|rule| rule.collection == collection && rule.field == field)
+    {
+        return Ok(ReferenceTarget {
+            collection: rule.target_collection.clone(),
+            field: rule.target_field.clone(),
+        });
+    }
+    if !matches!(value, Bson::ObjectId(_)) {
+        anyhow::bail!("{collection}.{field} is not an ObjectId; add a reference rule to follow it");
+    }
+    if field == "_id" {
+        anyhow::bail!("_id is this document's own id");
+    }
+    let base = reference_base_name(&segments)
+        .ok_or_else(|| anyhow::anyhow!("no reference rule for {collection}.{field}"))?;
+    guess_collection(&base, collections)
+        .map(|collection| ReferenceTarget {
+            collection: collection.to_string(),
+            field: "_id".to_string(),
+        })
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "no reference rule for {collection}.{field} and no collection named like '{base}'"
+            )
+        })
+}
+
+/// Field name with its id suffix removed, falling back to the parent field for `user._id`.
+fn reference_base_name(segments: &[&str]) -> Option<String> {
+    let (last, parents) = segments.split_last()?;
+    let stripped = ["_ids", "_id", "Ids", "Id", "IDs", "ID"]
+        .iter()
+        .find_map(|suffix| last.strip_suffix(suffix))
+        .unwrap_or(last);
+    let base = if stripped.is_empty() {
+        parents.last()?
+    } else {
+        stripped
+    };
+    Some(base.to_string())
+}
+
+/// Collection matching `base` or a plural of it, ignoring case.
+fn guess_collection<'a>(base: &str, collections: &'a [String]) -> Option<&'a String> {
+    let base = base.to_lowercase();
+    let mut candidates = vec![base.clone(), format!("{base}s"), format!("{base}es")];
+    if let Some(stem) = base.strip_suffix('y') {
+        candidates.push(format!("{stem}ies"));
+    }
+    candidates.iter().find_map(|candidate| {
+        collections
+            .iter()
+            .find(|collection| collection.to_lowercase() == *candidate)
+    })
+}
+
+impl App {
+    /// Opens the document referenced by the field under the tree cursor.
+    pub(crate) fn follow_reference(&mut self) -> Result<()> {
+        let Some((row, value)) = self.field_under_cursor() else {
+            return Ok(());
+        };
+        if !matches!(row.node, TreeNode::Value { .. }) {
+            self.message = Some("move to an id field to follow it".to_string());
+            return Ok(());
+        }
+        if self.reference_stack.len() >= MAX_REFERENCE_DEPTH {
+            anyhow::bail!("reference stack is full; go back before following more");
+        }
+        let (connection, database, collection) = self.selected_context()?;
+        let target = resolve_reference(
+            &self.storage.config.references,
+            &collection,
+            &row.path,
+            &value,
+            &self.collection_items,
+        )?;
+        if !self.collection_items.contains(&target.collection) {
+            anyhow::bail!("collection '{}' not found in {database}", target.collection);
+        }
+        let mut filter = Document::new();
+        filter.insert(target.field.clone(), value.clone());
+        let spec = QuerySpec {
+            connection: Some(connection),
+            database,
+            collection: target.collection.clone(),
+            filter: Some(render_relaxed_extjson_string(&Bson::Document(filter))),
+            projection: None,
+            sort: None,
+            limit: Some(1),
+        };
+        let label = format!(
+            "{}.{} = {}",
+            target.collection,
+            target.field,
+            leaf_text(&value, false).0
+        );
+        let config = self.storage.config.clone();
+        let request_id = self.next_load_id();
+        self.reference_load_id = Some(request_id);
+        self.message = Some(format!("following {label}..."));
+        let sender = self.load_tx.clone();
+        self.runtime.spawn(async move {
+            let executor = MongoExecutor::new();
+            let result = executor.execute_query(&config, &spec).await;
+            let _ = sender.send(LoadResult::Reference {
+                id: request_id,
+                target,
+                label,
+                result,
+            });
+        });
+        Ok(())
+    }
+
+    pub(crate) fn apply_reference_result(
+        &mut self,
+        id: u64,
+        target: ReferenceTarget,
+        label: String,
+        result: Result<Vec<Document>>,
+    ) {
+        if self.reference_load_id != Some(id) {
+            return;
+        }
+        self.reference_load_id = None;
+        let documents = match result {
+            Ok(documents) => documents,
+            Err(error) => {
+                self.set_error_message(&error);
+                return;
+            }
+        };
+        if documents.is_empty() {
+            self.message = Some(format!("no document with {label}"));
+            return;
+        }
+        let Some(collection_index) = self
+            .collection_items
+            .iter()
+            .position(|collection| *collection == target.collection)
+        else {
+            return;
+        };
+        self.reference_stack.push(ReferenceFrame {
+            collection_index: self.collection_index,
+            documents: std::mem::take(&mut self.documents),
+            document_index: self.document_index,
+            document_page: self.document_page,
+            document_result_source: self.document_result_source.clone(),
+            document_tree: std::mem::take(&mut self.document_tree),
+        });
+        self.collection_index = Some(collection_index);
+        self.documents = documents;
+        self.document_index = Some(0);
+        self.document_page = 0;
+        self.document_selection.clear();
+        self.document_result_source = DocumentResultSource::Reference {
+            label: label.clone(),
+        };
+        self.screen = Screen::DocumentView;
+        self.prepare_document_view();
+        self.message = Some(format!(
+            "opened {label}; {} to go back",
+            self.keymap.keys_for_actions(&[KeyAction::ReferenceBack])
+        ));
+    }
+
+    /// Returns to the document a reference was followed from. Returns false when no reference
+    /// has been followed.
+    pub(crate) fn reference_back(&mut self) -> bool {
+        let Some(frame) = self.reference_stack.pop() else {
+            return false;
+        };
+        self.reference_load_id = None;
+        self.collection_index = frame.collection_index;
+        self.documents = frame.documents;
+        self.document_index = frame.document_index;
+        self.document_page = frame.document_page;
+        self.document_result_source = frame.document_result_source;
+        self.document_selection.clear();
+        self.document_lines = self
+            .document_index
+            .and_then(|index| self.documents.get(index))
+            .map(format_document)
+            .unwrap_or_default();
+        self.document_scroll = 0;
+        self.document_tree = frame.document_tree;
+        self.screen = Screen::DocumentView;
+        true
+    }
+
+    /// Drops followed references once the database they were followed in is left.
+    pub(crate) fn clear_reference_stack(&mut self) {
+        self.reference_stack.clear();
+        self.reference_load_id = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use lazycompass_mongo::parse_json_document;
+
+    fn collections() -> Vec<String> {
+        ["categories", "orders", "Person", "users"]
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn object_id() -> Bson {
+        Bson::ObjectId("64b7f0c2a1b2c3d4e5f60718".parse().expect("object id"))
+    }
+
+    fn target(collection: &str, field: &str) -> ReferenceTarget {
+        ReferenceTarget {
+            collection: collection.to_string(),
+            field: field.to_string(),
+        }
+    }
+
+    #[test]
+    fn rules_win_over_guesses_and_ignore_array_indexes() {
+        let rules = vec![
+            ReferenceRule::parse("orders.userId -> Person._id").expect("rule"),
+            ReferenceRule::parse("orders.items.sku -> products.sku").expect("rule"),
+        ];
+        let resolve = |path: &str, value: &Bson| {
+            resolve_reference(&rules, "orders", path, value, &collections())
+        };
+
+        assert_eq!(
+            resolve("userId", &object_id()).expect("rule"),
+            target("Person", "_id")
+        );
+        assert_eq!(
+            resolve("items.2.sku", &Bson::from("A-1")).expect("rule"),
+            target("products", "sku")
+        );
+        let error = resolve("status", &Bson::from("paid")).expect_err("not an id");
+        assert_eq!(
+            error.to_string(),
+            "orders.status is not an ObjectId; add a reference rule to follow it"
+        );
+    }
+
+    #[test]
+    fn guesses_plural_collections_from_field_names() {
+        let resolve =
+            |path: &str| resolve_reference(&[], "orders", path, &object_id(), &collections());
+
+        assert_eq!(resolve("user_id").expect("guess"), target("users", "_id"));
+        assert_eq!(
+            resolve("lines.0.categoryId").expect("guess"),
+            target("categories", "_id")
+        );
+        assert_eq!(
+            resolve("person._id").expect("guess"),
+            target("Person", "_id")
+        );
+        assert_eq!(resolve("user").expect("guess"), target("users", "_id"));
+        assert!(resolve("_id").is_err());
+        let error = resolve("warehouseId").expect_err("no collection");
+        assert_eq!(
+            error.to_string(),
+            "no reference rule for orders.warehouseId and no collection named like 'warehouse'"
+        );
+    }
+
+    #[test]
+    fn following_a_reference_pushes_a_frame_and_back_restores_it() {
+        let mut app = App::test_app();
+        app.collection_items = collections();
+        app.collection_index = Some(1);
+        let order = parse_json_document(
+            "order",
+            r#"{"_id": 1, "userId": {"$oid": "64b7f0c2a1b2c3d4e5f60718"}}"#,
+        )
+        .expect("order");
+        app.documents = vec![order.clone()];
+        app.document_index = Some(0);
+        app.prepare_document_view();
+        app.document_tree.cursor = 1;
+        app.screen = Screen::DocumentView;
+
+        app.reference_load_id = Some(7);
+        let user = parse_json_document("user", r#"{"_id": 2, "name": "Ada"}"#).expect("user");
+        app.apply_reference_result(
+            7,
+            target("users", "_id"),
+            "users._id = 1".to_string(),
+            Ok(vec![user.clone()]),
+        );
+        assert_eq!(app.selected_collection(), Some("users"));
+        assert_eq!(app.documents, vec![user]);
+        assert_eq!(app.screen, Screen::DocumentView);
+        assert_eq!(app.reference_stack.len(), 1);
+
+        assert!(app.reference_back());
+        assert_eq!(app.selected_collection(), Some("orders"));
+        assert_eq!(app.documents, vec![order]);
+        assert_eq!(app.document_tree.cursor, 1);
+        assert!(!app.reference_back());
+    }
+}
@@ -0,0 +1,268 @@
+use super::*;
+
+const STORAGE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const STORAGE_RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+impl App {
+    /// Reload config and saved specs once their files stop changing, so edits made in another
+    /// pane show up without restarting.
+    pub(crate) fn poll_storage_changes(&mut self) {
+        let now = Instant::now();
+        if now.duration_since(self.storage_checked_at) < STORAGE_POLL_INTERVAL
+            && self.storage_reload_pending.is_none()
+        {
+            return;
+        }
+        self.storage_checked_at = now;
+
+        let fingerprint = storage_fingerprint(&self.paths, &self.storage.config);
+        if fingerprint != self.storage_fingerprint {
+            self.storage_fingerprint = fingerprint;
+            self.storage_reload_pending = Some(now);
+            return;
+        }
+        if self
+            .storage_reload_pending
+            .is_some_and(|changed_at| now.duration_since(changed_at) >= STORAGE_RELOAD_DEBOUNCE)
+        {
+            self.storage_reload_pending = None;
+            self.reload_storage();
+        }
+    }
+
+    pub(crate) fn reload_storage(&mut self) {
+        let profile = self.storage.config.active_profile.clone();
+        let result =
+            load_config_with_profile(&self.paths, profile.as_deref()).and_then(|mut config| {
+                if self.allow_insecure {
+                    config.allow_insecure = Some(true);
+                }
+                load_storage_with_config(&self.paths, config)
+            });
+        match result {
+            Ok(storage) => self.apply_reloaded_storage(storage),
+            Err(error) => self.warnings.push_back(format!(
+                "config reload failed, keeping previous config: {}",
+                redact_sensitive_text(&format_error(&error))
+            )),
+        }
+        self.storage_fingerprint = storage_fingerprint(&self.paths, &self.storage.config);
+    }
+
+    fn apply_reloaded_storage(&mut self, mut storage: StorageSnapshot) {
+        for connection in &self.session_connections {
+            if !storage
+                .config
+                .connections
+                .iter()
+                .any(|existing| existing.name == connection.name)
+            {
+                storage.config.connections.push(connection.clone());
+            }
+        }
+
+        let connection_name = self.selected_connection().map(|spec| spec.name.clone());
+        let query_id = self
+            .saved_query_index
+            .and_then(|index| self.storage.queries.get(index))
+            .map(|query| query.id.clone());
+        let aggregation_id = self
+            .saved_agg_index
+            .and_then(|index| self.storage.aggregations.get(index))
+            .map(|aggregation| aggregation.id.clone());
+
+        for warning in &storage.warnings {
+            if !self.storage.warnings.contains(warning) {
+                self.warnings.push_back(warning.clone());
+            }
+        }
+        let (theme, theme_warnings) = resolve_theme(&storage.config, &self.paths);
+        self.warnings.extend(theme_warnings);
+        self.theme = theme;
+        let (keymap, key_warnings) = KeyMap::resolve(&storage.config.keys);
+        self.warnings.extend(key_warnings);
+        self.keymap = keymap;
+        self.pending_keys.clear();
+        self.storage = storage;
+
+        let first_connection = (!self.storage.config.connections.is_empty()).then_some(0);
+        self.connection_index = match connection_name {
+            Some(name) => {
+                let position = self
+                    .storage
+                    .config
+                    .connections
+                    .iter()
+                    .position(|spec| spec.name == name);
+                if position.is_none() {
+                    self.warnings
+                        .push_back(format!("connection '{name}' was removed from config"));
+                    self.return_to_connections();
+                }
+                position.or(first_connection)
+            }
+            None => first_connection,
+        };
+        self.saved_query_index = reselect(
+            self.storage.queries.iter().map(|query| query.id.as_str()),
+            query_id.as_deref(),
+        );
+        self.saved_agg_index = reselect(
+            self.storage
+                .aggregations
+                .iter()
+                .map(|aggregation| aggregation.id.as_str()),
+            aggregation_id.as_deref(),
+        );
+    }
+
+    fn return_to_connections(&mut self) {
+        self.screen = Screen::Connections;
+        self.database_items.clear();
+        self.database_index = None;
+        self.collection_items.clear();
+        self.collection_index = None;
+        self.documents.clear();
+        self.document_index = None;
+        self.document_lines.clear();
+        self.database_load_id = None;
+        self.collection_load_id = None;
+        self.document_load_id = None;
+        self.database_state = LoadState::Idle;
+        self.collection_state = LoadState::Idle;
+        self.document_state = LoadState::Idle;
+    }
+}
+
+fn reselect<'a>(
+    ids: impl ExactSizeIterator<Item = &'a str>,
+    previous: Option<&str>,
+) -> Option<usize> {
+    let len = ids.len();
+    let mut ids = ids;
+    match previous.and_then(|previous| ids.position(|id| id == previous)) {
+        Some(index) => Some(index),
+        None => (len > 0).then_some(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazycompass_core::{Config, SavedQuery};
+    use lazycompass_storage::StorageSnapshot;
+    use std::fs;
+
+    use super::*;
+
+    fn write_config(app: &App, contents: &str) {
+        fs::create_dir_all(&app.paths.global_root).expect("create global root");
+        fs::write(app.paths.global_config_path(), contents).expect("write config");
+    }
+
+    #[test]
+    fn reload_storage_keeps_selection_by_name() {
+        let mut app = App::test_app_with_storage(StorageSnapshot {
+            config: Config {
+                connections: vec![App::test_connection("alpha"), App::test_connection("beta")],
+                ..Config::default()
+            },
+            queries: Vec::new(),
+            aggregations: Vec::new(),
+            warnings: Vec::new(),
+        });
+        app.connection_index = Some(1);
+        app.screen = Screen::Databases;
+        write_config(
+            &app,
+            r#"allow_insecure = true
+
+[[connections]]
+name = "beta"
+uri = "mongodb://localhost:27018"
+
+[[connections]]
+name = "gamma"
+uri = "mongodb://localhost:27019"
+"#,
+        );
+
+        app.reload_storage();
+
+        assert_eq!(app.storage.config.connections.len(), 2);
+        assert_eq!(app.connection_index, Some(0));
+        assert_eq!(app.screen, Screen::Databases);
+        assert_eq!(
+            app.selected_connection().map(|spec| spec.uri.as_str()),
+            Some("mongodb://localhost:27018")
+        );
+    }
+
+    #[test]
+    fn reload_storage_returns_to_connections_when_selected_connection_is_removed() {
+        let mut app = App::test_app_with_storage(StorageSnapshot {
+            config: Config {
+                connections: vec![App::test_connection("alpha")],
+                ..Config::default()
+            },
+            queries: vec![SavedQuery {
+                id: "recent".to_string(),
+                scope: SavedScope::Shared,
+                filter: None,
+                projection: None,
+                sort: None,
+                limit: None,
+                tags: Vec::new(),
+            }],
+            aggregations: Vec::new(),
+            warnings: Vec::new(),
+        });
+        app.connection_index = Some(0);
+        app.screen = Screen::Collections;
+        app.database_items = vec!["app".to_string()];
+        app.saved_query_index = Some(0);
+        write_config(&app, "allow_insecure = true\n");
+
+        app.reload_storage();
+
+        assert!(app.storage.config.connections.is_empty());
+        assert_eq!(app.screen, Screen::Connections);
+        assert_eq!(app.connection_index, None);
+        assert!(app.database_items.is_empty());
+        assert_eq!(app.saved_query_index, None);
+        assert!(
+            app.warnings
+                .iter()
+                .any(|warning| warning == "connection 'alpha' was removed from config")
+        );
+    }
+
+    #[test]
+    fn reload_storage_keeps_previous_snapshot_on_error() {
+        let mut app = App::test_app_with_storage(StorageSnapshot {
+            config: Config {
+                connections: vec![App::test_connection("alpha")],
+                ..Config::default()
+            },
+            queries: Vec::new(),
+            aggregations: Vec::new(),
+            warnings: Vec::new(),
+        });
+        app.session_connections
+            .push(App::test_connection("scratch"));
+        write_config(&app, "[[connections]\n");
+
+        app.reload_storage();
+
+        assert_eq!(app.storage.config.connections.len(), 1);
+        assert!(
+            app.warnings
+                .iter()
+                .any(|warning| warning.starts_with("config reload failed"))
+        );
+
+        write_config(&app, "allow_insecure = true\n");
+        app.reload_storage();
+        assert_eq!(app.storage.config.connections.len(), 1);
+        assert_eq!(app.storage.config.connections[0].name, "scratch");
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+
 pub(crate) fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -21,6 +22,23 @@ pub(crate) fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect
     vertical[1]
 }
 
+fn quick_aggregation_layout(area: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .split(area)
+}
+
+/// First visible line of the quick aggregation text area, keeping the cursor line in view.
+fn quick_aggregation_scroll(modal: &QuickAggregationModalState, text_area: Rect) -> u16 {
+    let (line, _) = modal.cursor_position();
+    (line as u16).saturating_sub(text_area.height.saturating_sub(1))
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum MainPaneLayout {
     Single,
@@ -56,6 +74,7 @@ impl App {
             Screen::IndexView => self.render_index_view_screen(frame, layout[1]),
             Screen::Documents => self.render_documents_screen(frame, layout[1]),
             Screen::DocumentView => self.render_document_view_screen(frame, layout[1]),
+            Screen::DocumentDiff => self.render_document_diff_screen(frame, layout[1]),
             Screen::ExportFormatSelect => self.render_export_format_select_screen(frame, layout[1]),
             Screen::SavedQuerySelect => self.render_saved_query_select_screen(frame, layout[1]),
             Screen::SavedAggregationSelect => {
@@ -68,6 +87,8 @@ impl App {
             Screen::AddConnectionScopeSelect => {
                 self.render_add_connection_scope_screen(frame, layout[1])
             }
+            Screen::History => self.render_history_screen(frame, layout[1]),
+            Screen::PipelineBuilder => self.render_pipeline_builder_screen(frame, layout[1]),
         }
 
         if self.help_visible {
@@ -79,6 +100,12 @@ impl App {
                 frame.set_cursor_position((x, y));
             }
         }
+        if self.quick_aggregation_modal.is_some() {
+            self.render_quick_aggregation_modal(frame, layout[1]);
+            if let Some((x, y)) = self.quick_aggregation_cursor(layout[1]) {
+                frame.set_cursor_position((x, y));
+            }
+        }
 
         let footer = Paragraph::new(self.footer_lines())
             .style(self.theme.text_style())
@@ -88,6 +115,15 @@ impl App {
                     .border_style(self.theme.border_style()),
             );
         frame.render_widget(footer, layout[2]);
+        if let Some(line) = &self.command_line {
+            let x = layout[2]
+                .x
+                .saturating_add(1 + line.input.chars().count() as u16);
+            frame.set_cursor_position((
+                x.min(layout[2].right().saturating_sub(1)),
+                layout[2].y.saturating_add(1),
+            ));
+        }
     }
 
     fn hierarchy_layout(&self, screen: Screen, width: u16) -> MainPaneLayout {
@@ -118,7 +154,10 @@ impl App {
             Screen::ExportFormatSelect
             | Screen::SaveQueryScopeSelect
             | Screen::SaveAggregationScopeSelect
-            | Screen::AddConnectionScopeSelect => MainPaneLayout::Single,
+            | Screen::AddConnectionScopeSelect
+            | Screen::DocumentDiff
+            | Screen::PipelineBuilder
+            | Screen::History => MainPaneLayout::Single,
         }
     }
 
@@ -414,11 +453,7 @@ impl App {
                 if self.index_scroll > max_scroll {
                     self.index_scroll = max_scroll;
                 }
-                let lines = self
-                    .index_lines
-                    .iter()
-                    .map(|line| Line::from(line.clone()))
-                    .collect::<Vec<_>>();
+                let lines = self.highlighted_json_lines(&self.index_lines);
                 let body = Paragraph::new(lines)
                     .style(self.theme.text_style())
                     .block(
@@ -458,11 +493,7 @@ impl App {
                 if self.index_scroll > max_scroll {
                     self.index_scroll = max_scroll;
                 }
-                let lines = self
-                    .index_lines
-                    .iter()
-                    .map(|line| Line::from(line.clone()))
-                    .collect::<Vec<_>>();
+                let lines = self.highlighted_json_lines(&self.index_lines);
                 let body = Paragraph::new(lines)
                     .style(self.theme.text_style())
                     .block(
@@ -518,11 +549,7 @@ impl App {
                 if self.index_scroll > max_scroll {
                     self.index_scroll = max_scroll;
                 }
-                let lines = self
-                    .index_lines
-                    .iter()
-                    .map(|line| Line::from(line.clone()))
-                    .collect::<Vec<_>>();
+                let lines = self.highlighted_json_lines(&self.index_lines);
                 let body = Paragraph::new(lines)
                     .style(self.theme.text_style())
                     .block(
@@ -541,23 +568,7 @@ impl App {
     fn render_documents_screen(&self, frame: &mut ratatui::Frame, area: Rect) {
         match self.hierarchy_layout(Screen::Documents, area.width) {
             MainPaneLayout::Single => {
-                let items = self
-                    .documents
-                    .iter()
-                    .map(document_preview)
-                    .collect::<Vec<_>>();
-                let title = self.documents_list_title();
-                self.render_list(
-                    frame,
-                    area,
-                    ListView {
-                        title: &title,
-                        items: &items,
-                        selected: self.document_index,
-                        load_state: &self.document_state,
-                        loading_label: "loading documents...",
-                    },
-                );
+                self.render_documents_pane(frame, area);
             }
             MainPaneLayout::Double => {
                 let panes = Layout::default()
@@ -576,23 +587,7 @@ impl App {
                     },
                     false,
                 );
-                let items = self
-                    .documents
-                    .iter()
-                    .map(document_preview)
-                    .collect::<Vec<_>>();
-                let title = self.documents_list_title();
-                self.render_list(
-                    frame,
-                    panes[1],
-                    ListView {
-                        title: &title,
-                        items: &items,
-                        selected: self.document_index,
-                        load_state: &self.document_state,
-                        loading_label: "loading documents...",
-                    },
-                );
+                self.render_documents_pane(frame, panes[1]);
             }
             MainPaneLayout::Triple => {
                 let panes = Layout::default()
@@ -627,53 +622,91 @@ impl App {
                     },
                     false,
                 );
-                let items = self
-                    .documents
-                    .iter()
-                    .map(document_preview)
-                    .collect::<Vec<_>>();
-                let title = self.documents_list_title();
-                self.render_list(
-                    frame,
-                    panes[2],
-                    ListView {
-                        title: &title,
-                        items: &items,
-                        selected: self.document_index,
-                        load_state: &self.document_state,
-                        loading_label: "loading documents...",
-                    },
-                );
+                self.render_documents_pane(frame, panes[2]);
             }
         }
     }
 
+    fn render_documents_pane(&self, frame: &mut ratatui::Frame, area: Rect) {
+        let items = self.document_items();
+        let title = self.documents_list_title();
+        let view = ListView {
+            title: &title,
+            items: &items,
+            selected: self.document_index,
+            load_state: &self.document_state,
+            loading_label: "loading documents...",
+        };
+        if self.document_grid.enabled && !items.is_empty() {
+            self.render_document_grid(frame, area, view);
+        } else {
+            self.render_list(frame, area, view);
+        }
+    }
+
+    /// Documents as columns by field path, with the header row kept above the scrolling rows.
+    fn render_document_grid(&self, frame: &mut ratatui::Frame, area: Rect, view: ListView<'_>) {
+        let rows = self
+            .search_matches()
+            .unwrap_or_else(|| (0..view.items.len()).collect());
+        let mut title = view.title.to_string();
+        if let Some(search) = self.active_search() {
+            title.push_str(&format!(
+                " /{} ({}/{})",
+                search.query,
+                rows.len(),
+                view.items.len()
+            ));
+        }
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.title_style())
+            .title(Line::from(Span::styled(title, self.theme.title_style())));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let sections = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(inner);
+        let selected_rows = self.selected_document_indexes();
+        let marking = !selected_rows.is_empty() || self.document_selection.visual();
+        let marker_width = if marking { 2 } else { 0 };
+        let (mut header, mut lines) =
+            self.grid_lines(&rows, inner.width.saturating_sub(2 + marker_width) as usize);
+        header
+            .spans
+            .insert(0, Span::raw(" ".repeat(2 + marker_width as usize)));
+        if marking {
+            for (line, row) in lines.iter_mut().zip(&rows) {
+                let marker = if selected_rows.contains(row) {
+                    "● "
+                } else {
+                    "  "
+                };
+                line.spans.insert(0, Span::raw(marker));
+            }
+        }
+        frame.render_widget(
+            Paragraph::new(header).style(self.theme.text_style()),
+            sections[0],
+        );
+        let selected = view
+            .selected
+            .and_then(|selected| rows.iter().position(|row| *row == selected));
+        let list = List::new(lines.into_iter().map(ListItem::new).collect::<Vec<_>>())
+            .style(self.theme.text_style())
+            .highlight_style(self.theme.selection_style())
+            .highlight_symbol("> ")
+            .highlight_spacing(ratatui::widgets::HighlightSpacing::Always);
+        let mut state = ListState::default();
+        state.select(selected);
+        frame.render_stateful_widget(list, sections[1], &mut state);
+    }
+
     fn render_document_view_screen(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         match self.hierarchy_layout(Screen::DocumentView, area.width) {
             MainPaneLayout::Single => {
-                let max_scroll = self.max_document_scroll();
-                if self.document_scroll > max_scroll {
-                    self.document_scroll = max_scroll;
-                }
-                let lines = self
-                    .document_lines
-                    .iter()
-                    .map(|line| Line::from(line.clone()))
-                    .collect::<Vec<_>>();
-                let body = Paragraph::new(lines)
-                    .style(self.theme.text_style())
-                    .block(
-                        Block::default()
-                            .borders(Borders::ALL)
-                            .border_style(self.theme.border_style())
-                            .title(Line::from(Span::styled(
-                                "Document",
-                                self.theme.title_style(),
-                            ))),
-                    )
-                    .wrap(Wrap { trim: false })
-                    .scroll((self.document_scroll, 0));
-                frame.render_widget(body, area);
+                self.render_document_body(frame, area);
             }
             MainPaneLayout::Double => {
                 let panes = Layout::default()
@@ -698,29 +731,7 @@ impl App {
                     },
                     false,
                 );
-                let max_scroll = self.max_document_scroll();
-                if self.document_scroll > max_scroll {
-                    self.document_scroll = max_scroll;
-                }
-                let lines = self
-                    .document_lines
-                    .iter()
-                    .map(|line| Line::from(line.clone()))
-                    .collect::<Vec<_>>();
-                let body = Paragraph::new(lines)
-                    .style(self.theme.text_style())
-                    .block(
-                        Block::default()
-                            .borders(Borders::ALL)
-                            .border_style(self.theme.border_style())
-                            .title(Line::from(Span::styled(
-                                "Document",
-                                self.theme.title_style(),
-                            ))),
-                    )
-                    .wrap(Wrap { trim: false })
-                    .scroll((self.document_scroll, 0));
-                frame.render_widget(body, panes[1]);
+                self.render_document_body(frame, panes[1]);
             }
             MainPaneLayout::Triple => {
                 let panes = Layout::default()
@@ -761,31 +772,139 @@ impl App {
                     },
                     false,
                 );
-                let max_scroll = self.max_document_scroll();
-                if self.document_scroll > max_scroll {
-                    self.document_scroll = max_scroll;
-                }
-                let lines = self
-                    .document_lines
-                    .iter()
-                    .map(|line| Line::from(line.clone()))
-                    .collect::<Vec<_>>();
-                let body = Paragraph::new(lines)
-                    .style(self.theme.text_style())
-                    .block(
-                        Block::default()
-                            .borders(Borders::ALL)
-                            .border_style(self.theme.border_style())
-                            .title(Line::from(Span::styled(
-                                "Document",
-                                self.theme.title_style(),
-                            ))),
-                    )
-                    .wrap(Wrap { trim: false })
-                    .scroll((self.document_scroll, 0));
-                frame.render_widget(body, panes[2]);
+                self.render_document_body(frame, panes[2]);
+            }
+        }
+    }
+
+    fn render_document_body(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border_style())
+            .title(Line::from(Span::styled(
+                "Document",
+                self.theme.title_style(),
+            )));
+        if self.document_json_view {
+            let max_scroll = self.max_document_scroll();
+            if self.document_scroll > max_scroll {
+                self.document_scroll = max_scroll;
             }
+            let lines = self.highlighted_json_lines(&self.document_lines);
+            let body = Paragraph::new(lines)
+                .style(self.theme.text_style())
+                .block(block)
+                .wrap(Wrap { trim: false })
+                .scroll((self.document_scroll, 0));
+            frame.render_widget(body, area);
+            return;
         }
+
+        let rows = self.document_tree_rows();
+        if rows.is_empty() {
+            let body = Paragraph::new("no fields")
+                .style(self.theme.text_style())
+                .block(block);
+            frame.render_widget(body, area);
+            return;
+        }
+        self.document_tree.cursor = self.document_tree.cursor.min(rows.len() - 1);
+        let items = self
+            .tree_lines(&rows)
+            .into_iter()
+            .map(ListItem::new)
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .style(self.theme.text_style())
+            .block(block)
+            .highlight_style(self.theme.selection_style());
+        let mut state = ListState::default()
+            .with_offset(self.document_tree.offset)
+            .with_selected(Some(self.document_tree.cursor));
+        frame.render_stateful_widget(list, area, &mut state);
+        self.document_tree.offset = state.offset();
+    }
+
+    fn render_document_diff_screen(&self, frame: &mut ratatui::Frame, area: Rect) {
+        let Some(diff) = &self.document_diff else {
+            return;
+        };
+        let block = |title: String| {
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.theme.border_style())
+                .title(Line::from(Span::styled(title, self.theme.title_style())))
+        };
+        if !diff.side_by_side {
+            let mut lines = self.diff_unified_lines(diff);
+            if lines.is_empty() {
+                lines.push(Line::from("no differences"));
+            }
+            let body = Paragraph::new(lines)
+                .style(self.theme.text_style())
+                .block(block(diff.title()))
+                .scroll((diff.scroll, 0));
+            frame.render_widget(body, area);
+            return;
+        }
+
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        let (left, right) = self.diff_side_lines(diff);
+        for (lines, title, pane) in [
+            (left, diff.left_label.clone(), panes[0]),
+            (right, diff.right_label.clone(), panes[1]),
+        ] {
+            let body = Paragraph::new(lines)
+                .style(self.theme.text_style())
+                .block(block(title))
+                .scroll((diff.scroll, 0));
+            frame.render_widget(body, pane);
+        }
+    }
+
+    fn render_pipeline_builder_screen(&self, frame: &mut ratatui::Frame, area: Rect) {
+        let Some(builder) = &self.pipeline_builder else {
+            return;
+        };
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(area);
+        let items = builder.stage_items();
+        let idle = LoadState::Idle;
+        self.render_list(
+            frame,
+            panes[0],
+            ListView {
+                title: &format!("Stages ({})", builder.stages.len()),
+                items: &items,
+                selected: (!items.is_empty()).then_some(builder.cursor),
+                load_state: &idle,
+                loading_label: "",
+            },
+        );
+        let scroll = builder
+            .preview
+            .as_ref()
+            .map(|preview| preview.scroll)
+            .unwrap_or(0);
+        let body = Paragraph::new(self.pipeline_preview_lines())
+            .style(self.theme.text_style())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border_style())
+                    .title(Line::from(Span::styled(
+                        builder.preview_title(),
+                        self.theme.title_style(),
+                    ))),
+            )
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0));
+        frame.render_widget(body, panes[1]);
     }
 
     fn render_export_format_select_screen(&self, frame: &mut ratatui::Frame, area: Rect) {
@@ -1096,13 +1215,49 @@ impl App {
         );
     }
 
+    fn render_history_screen(&self, frame: &mut ratatui::Frame, area: Rect) {
+        let panes = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        let items: Vec<String> = self.history_items.iter().map(history_item_label).collect();
+        self.render_list(
+            frame,
+            panes[0],
+            ListView {
+                title: "History",
+                items: &items,
+                selected: self.history_index,
+                load_state: &LoadState::Idle,
+                loading_label: "",
+            },
+        );
+
+        let lines = self
+            .history_index
+            .and_then(|index| self.history_items.get(index))
+            .map(history_detail_lines)
+            .unwrap_or_default();
+        let body = Paragraph::new(lines)
+            .style(self.theme.text_style())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border_style())
+                    .title(Line::from(Span::styled("Entry", self.theme.title_style()))),
+            )
+            .wrap(Wrap { trim: false });
+        frame.render_widget(body, panes[1]);
+    }
+
+    /// Renders the focused list of a screen, filtered by its `/` search when one is active.
     pub(crate) fn render_list(
         &self,
         frame: &mut ratatui::Frame,
         area: ratatui::layout::Rect,
         view: ListView<'_>,
     ) {
-        self.render_list_with_focus(frame, area, view, true);
+        self.render_list_items(frame, area, view, true, self.active_search());
     }
 
     pub(crate) fn render_list_with_focus(
@@ -1111,6 +1266,17 @@ impl App {
         area: ratatui::layout::Rect,
         view: ListView<'_>,
         active: bool,
+    ) {
+        self.render_list_items(frame, area, view, active, None);
+    }
+
+    fn render_list_items(
+        &self,
+        frame: &mut ratatui::Frame,
+        area: ratatui::layout::Rect,
+        view: ListView<'_>,
+        active: bool,
+        search: Option<&ListSearch>,
     ) {
         let title_style = if active {
             self.theme.title_style()
@@ -1125,7 +1291,41 @@ impl App {
         } else {
             self.theme.border_style().add_modifier(Modifier::DIM)
         };
-        let title = Line::from(Span::styled(view.title.to_string(), title_style));
+        let matches = search.map(|search| {
+            view.items
+                .iter()
+                .enumerate()
+                .filter_map(|(index, item)| {
+                    fuzzy_match(&search.query, item).map(|positions| (index, positions))
+                })
+                .collect::<Vec<_>>()
+        });
+        let title = match (search, &matches) {
+            (Some(search), Some(matches)) => format!(
+                "{} /{} ({}/{})",
+                view.title,
+                search.query,
+                matches.len(),
+                view.items.len()
+            ),
+            _ => view.title.to_string(),
+        };
+        let title = Line::from(Span::styled(title, title_style));
+        if let (Some(search), Some(matches)) = (search, &matches)
+            && matches.is_empty()
+            && !view.items.is_empty()
+        {
+            let placeholder = Paragraph::new(format!("no matches for '{}'", search.query))
+                .style(self.theme.text_style())
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(border_style)
+                        .title(title),
+                );
+            frame.render_widget(placeholder, area);
+            return;
+        }
         if view.items.is_empty() {
             let (text, style) = match view.load_state {
                 LoadState::Loading => (view.loading_label.to_string(), self.theme.text_style()),
@@ -1144,11 +1344,27 @@ impl App {
             return;
         }
 
-        let items = view
-            .items
-            .iter()
-            .map(|item| ListItem::new(item.clone()))
-            .collect::<Vec<_>>();
+        let (items, selected) = match matches {
+            Some(matches) => {
+                let selected = view
+                    .selected
+                    .and_then(|selected| matches.iter().position(|(index, _)| *index == selected));
+                let items = matches
+                    .iter()
+                    .map(|(index, positions)| {
+                        ListItem::new(self.highlight_matches(&view.items[*index], positions))
+                    })
+                    .collect::<Vec<_>>();
+                (items, selected)
+            }
+            None => (
+                view.items
+                    .iter()
+                    .map(|item| ListItem::new(item.clone()))
+                    .collect::<Vec<_>>(),
+                view.selected,
+            ),
+        };
         let highlight_style = if active {
             self.theme.selection_style()
         } else {
@@ -1165,10 +1381,36 @@ impl App {
             .highlight_style(highlight_style)
             .highlight_symbol("> ");
         let mut state = ListState::default();
-        state.select(view.selected);
+        state.select(selected);
         frame.render_stateful_widget(list, area, &mut state);
     }
 
+    fn highlight_matches(&self, item: &str, positions: &[usize]) -> Line<'static> {
+        let mut spans = Vec::new();
+        let mut run = String::new();
+        let mut run_matched = false;
+        for (index, ch) in item.chars().enumerate() {
+            let matched = positions.contains(&index);
+            if matched != run_matched && !run.is_empty() {
+                spans.push(self.match_span(std::mem::take(&mut run), run_matched));
+            }
+            run_matched = matched;
+            run.push(ch);
+        }
+        if !run.is_empty() {
+            spans.push(self.match_span(run, run_matched));
+        }
+        Line::from(spans)
+    }
+
+    fn match_span(&self, text: String, matched: bool) -> Span<'static> {
+        if matched {
+            Span::styled(text, self.theme.match_style())
+        } else {
+            Span::raw(text)
+        }
+    }
+
     pub(crate) fn render_help(&self, frame: &mut ratatui::Frame, area: Rect) {
         let help_area = centered_rect(70, 70, area);
         frame.render_widget(Clear, help_area);
@@ -1211,10 +1453,11 @@ impl App {
             ])
             .split(inner);
 
-        let instructions =
-            Paragraph::new("Tab/Shift+Tab switch fields  Enter run  Ctrl+E editor  Esc cancel")
-                .style(self.theme.text_style())
-                .wrap(Wrap { trim: false });
+        let instructions = Paragraph::new(
+            "Tab complete/next field  Shift+Tab prev  Enter run  Ctrl+E editor  Esc cancel",
+        )
+        .style(self.theme.text_style())
+        .wrap(Wrap { trim: false });
         frame.render_widget(instructions, chunks[0]);
         self.render_quick_query_field(
             frame,
@@ -1248,6 +1491,153 @@ impl App {
             false,
             modal.focus == QuickQueryField::Limit,
         );
+        if let Some(popup) = &modal.completion {
+            let field_rect = match modal.focus {
+                QuickQueryField::Filter => chunks[1],
+                QuickQueryField::Projection => chunks[2],
+                QuickQueryField::Sort => chunks[3],
+                QuickQueryField::Limit => chunks[4],
+            };
+            let column = modal.field_text(modal.focus)[..popup.start].chars().count() as u16;
+            self.render_completion_popup(frame, popup, field_rect, column, area);
+        }
+    }
+
+    fn render_completion_popup(
+        &self,
+        frame: &mut ratatui::Frame,
+        popup: &CompletionPopup,
+        field_rect: Rect,
+        column: u16,
+        bounds: Rect,
+    ) {
+        let label_width = popup
+            .items
+            .iter()
+            .map(|item| item.label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let items: Vec<ListItem> = popup
+            .items
+            .iter()
+            .map(|item| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:<label_width$}  ", item.label),
+                        self.theme.text_style(),
+                    ),
+                    Span::styled(item.hint.clone(), self.theme.border_style()),
+                ]))
+            })
+            .collect();
+        let hint_width = popup
+            .items
+            .iter()
+            .map(|item| item.hint.chars().count())
+            .max()
+            .unwrap_or(0);
+        let width = ((label_width + hint_width + 4) as u16).min(bounds.width);
+        let height = (popup.items.len().min(8) as u16 + 2).min(bounds.height);
+        let x = field_rect
+            .x
+            .saturating_add(1)
+            .saturating_add(column)
+            .min(bounds.right().saturating_sub(width));
+        let below = field_rect.bottom().saturating_sub(1);
+        let y = if below.saturating_add(height) <= bounds.bottom() {
+            below
+        } else {
+            field_rect.y.saturating_sub(height).max(bounds.y)
+        };
+        let popup_area = Rect::new(x, y, width, height);
+        frame.render_widget(Clear, popup_area);
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.title_style()),
+            )
+            .highlight_style(self.theme.selection_style());
+        let mut state = ListState::default();
+        state.select(Some(popup.selected));
+        frame.render_stateful_widget(list, popup_area, &mut state);
+    }
+
+    pub(crate) fn render_quick_aggregation_modal(&self, frame: &mut ratatui::Frame, area: Rect) {
+        let modal_area = centered_rect(76, 70, area);
+        frame.render_widget(Clear, modal_area);
+        let Some(modal) = &self.quick_aggregation_modal else {
+            return;
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border_style())
+            .title(Line::from(Span::styled(
+                "Quick Aggregation",
+                self.theme.title_style(),
+            )));
+        let inner = block.inner(modal_area);
+        frame.render_widget(block, modal_area);
+        let chunks = quick_aggregation_layout(inner);
+
+        let instructions =
+            Paragraph::new("Enter newline  Tab indent  Ctrl+R run  Ctrl+E editor  Esc cancel")
+                .style(self.theme.text_style())
+                .wrap(Wrap { trim: false });
+        frame.render_widget(instructions, chunks[0]);
+
+        let matched = modal.matching_bracket();
+        let is_match = |offset: usize| matched.is_some_and(|(a, b)| offset == a || offset == b);
+        let mut offset = 0;
+        let mut lines = Vec::new();
+        for line in modal.text.split('\n') {
+            let mut spans = Vec::new();
+            for (index, ch) in line.char_indices() {
+                let style = if is_match(offset + index) {
+                    self.theme.match_style()
+                } else {
+                    self.theme.text_style()
+                };
+                spans.push(Span::styled(ch.to_string(), style));
+            }
+            offset += line.len() + 1;
+            lines.push(Line::from(spans));
+        }
+        let editor_block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.title_style())
+            .title("Pipeline");
+        let scroll = quick_aggregation_scroll(modal, editor_block.inner(chunks[1]));
+        let editor = Paragraph::new(lines)
+            .block(editor_block)
+            .scroll((scroll, 0));
+        frame.render_widget(editor, chunks[1]);
+
+        let status = match &modal.validation {
+            Ok(count) => Line::from(Span::styled(
+                format!("valid pipeline ({count} stages)"),
+                self.theme.text_style(),
+            )),
+            Err(error) => Line::from(Span::styled(error.clone(), self.theme.error_style())),
+        };
+        frame.render_widget(Paragraph::new(status), chunks[2]);
+    }
+
+    pub(crate) fn quick_aggregation_cursor(&self, area: Rect) -> Option<(u16, u16)> {
+        let modal = self.quick_aggregation_modal.as_ref()?;
+        let modal_area = centered_rect(76, 70, area);
+        let inner = Block::default().borders(Borders::ALL).inner(modal_area);
+        let text_area = Block::default()
+            .borders(Borders::ALL)
+            .inner(quick_aggregation_layout(inner)[1]);
+        let (line, column) = modal.cursor_position();
+        let row = (line as u16).saturating_sub(quick_aggregation_scroll(modal, text_area));
+        let x = text_area.x.saturating_add(column as u16);
+        let y = text_area.y.saturating_add(row);
+        Some((
+            x.min(text_area.right().saturating_sub(1)),
+            y.min(text_area.bottom().saturating_sub(1)),
+        ))
     }
 
     fn render_quick_query_field(
@@ -1300,8 +1690,8 @@ impl App {
 
     pub(crate) fn help_lines(&self) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
-        for group in hint_groups(self.screen) {
-            let keys = keys_for_actions(group.actions);
+        for group in hint_groups(self.screen, self.document_grid.enabled) {
+            let keys = self.keymap.keys_for_actions(group.actions);
             lines.push(Line::from(format!("{keys:<12} {}", group.label)));
         }
         lines.push(Line::from(" "));
@@ -1310,9 +1700,15 @@ impl App {
     }
 
     pub(crate) fn hint_line(&self) -> String {
-        hint_groups(self.screen)
+        hint_groups(self.screen, self.document_grid.enabled)
             .iter()
-            .map(|group| format!("{} {}", keys_for_actions(group.actions), group.label))
+            .map(|group| {
+                format!(
+                    "{} {}",
+                    self.keymap.keys_for_actions(group.actions),
+                    group.label
+                )
+            })
             .collect::<Vec<_>>()
             .join("  ")
     }
@@ -1326,6 +1722,7 @@ impl App {
             Screen::IndexView => "Index",
             Screen::Documents => "Documents",
             Screen::DocumentView => "Document",
+            Screen::DocumentDiff => "Document Diff",
             Screen::ExportFormatSelect => match self.export_action {
                 Some(ExportAction::Clipboard) => "Copy Results",
                 _ => "Export Results",
@@ -1335,6 +1732,8 @@ impl App {
             Screen::SaveQueryScopeSelect => "Save Query",
             Screen::SaveAggregationScopeSelect => "Save Aggregation",
             Screen::AddConnectionScopeSelect => "Add Connection",
+            Screen::History => "Query History",
+            Screen::PipelineBuilder => "Pipeline Builder",
         };
         let connection = self
             .selected_connection()
@@ -1344,12 +1743,35 @@ impl App {
         let collection = self.selected_collection().unwrap_or("-");
         let path = format!("Conn: {connection}  Db: {database}  Coll: {collection}");
 
+        let protection = self
+            .selected_connection()
+            .map(|connection| connection.protection)
+            .unwrap_or_default();
+        let mut path_spans = Vec::new();
+        if let Some(profile) = self.storage.config.active_profile.as_deref() {
+            path_spans.push(Span::styled(
+                format!("Profile: {profile}"),
+                self.theme.title_style(),
+            ));
+            path_spans.push(Span::raw("  "));
+        }
+        path_spans.push(Span::styled(path, self.theme.text_style()));
+        if let Some(badge) = self.protection_badge(protection) {
+            path_spans.push(Span::raw("  "));
+            path_spans.push(badge);
+        }
+
         let mut lines = vec![
             Line::from(Span::styled(title.to_string(), self.theme.title_style())),
-            Line::from(Span::styled(path, self.theme.text_style())),
+            Line::from(path_spans),
         ];
 
-        if self.write_enabled {
+        if self.write_enabled && protection == ConnectionProtection::ReadOnly {
+            lines.push(Line::from(Span::styled(
+                "MODE: READ-ONLY (protected connection)",
+                self.theme.warning_style().add_modifier(Modifier::BOLD),
+            )));
+        } else if self.write_enabled {
             lines.push(Line::from(Span::styled(
                 "MODE: WRITE ENABLED",
                 self.theme.warning_style().add_modifier(Modifier::BOLD),
@@ -1364,9 +1786,32 @@ impl App {
         lines
     }
 
+    fn protection_badge(&self, protection: ConnectionProtection) -> Option<Span<'static>> {
+        let (label, style) = match protection {
+            ConnectionProtection::ReadOnly => (" READ-ONLY ", self.theme.error_style()),
+            ConnectionProtection::Confirm => (" CONFIRM WRITES ", self.theme.warning_style()),
+            ConnectionProtection::Open => return None,
+        };
+        Some(Span::styled(
+            label,
+            style.add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        ))
+    }
+
     pub(crate) fn footer_lines(&self) -> Vec<Line<'static>> {
         let hint = self.hint_line();
 
+        if self
+            .quick_query_modal
+            .as_ref()
+            .is_some_and(|modal| modal.completion.is_some())
+        {
+            return vec![
+                Line::from("Tab/Enter accept  Up/Down choose  Esc close"),
+                Line::from(" "),
+            ];
+        }
+
         if self.quick_query_modal.is_some() {
             return vec![
                 Line::from("Enter run  Tab next  Shift+Tab prev  Ctrl+E editor"),
@@ -1374,6 +1819,33 @@ impl App {
             ];
         }
 
+        if self.quick_aggregation_modal.is_some() {
+            return vec![
+                Line::from("Ctrl+R run  Ctrl+E editor  Enter newline  Tab indent"),
+                Line::from("Esc cancel"),
+            ];
+        }
+
+        if let Some(line) = &self.command_line {
+            let detail = match &line.completion {
+                Some(completion) => completion
+                    .candidates
+                    .iter()
+                    .enumerate()
+                    .map(|(index, candidate)| {
+                        if index == completion.selected {
+                            format!("[{candidate}]")
+                        } else {
+                            candidate.clone()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("  "),
+                None => "Enter run  Tab complete  Up/Down history  Esc cancel".to_string(),
+            };
+            return vec![Line::from(format!(":{}", line.input)), Line::from(detail)];
+        }
+
         if let Some(editor_prompt) = &self.editor_prompt {
             let input_display = if editor_prompt.input.is_empty() {
                 "[type below]".to_string()
@@ -1404,8 +1876,16 @@ impl App {
                     "Enter to export (current: {input_display})  Esc to cancel"
                 )),
             ]
+        } else if let Some(field_prompt) = &self.field_prompt {
+            vec![
+                Line::from(Span::styled(
+                    format!("{}: {}", field_prompt.prompt(), field_prompt.input),
+                    self.theme.warning_style(),
+                )),
+                Line::from("Enter to save  Esc to cancel"),
+            ]
         } else if let Some(confirm) = &self.confirm {
-            let action_line = if let Some(required) = confirm.required {
+            let action_line = if let Some(required) = &confirm.required {
                 let input_display = if confirm.input.is_empty() {
                     "[type below]".to_string()
                 } else {
@@ -1425,6 +1905,14 @@ impl App {
                 )),
                 Line::from(action_line),
             ]
+        } else if let Some(search) = self.active_search().filter(|search| search.editing) {
+            vec![
+                Line::from(Span::styled(
+                    format!("/{}", search.query),
+                    self.theme.title_style(),
+                )),
+                Line::from("type to filter  Enter keep filter  Esc clear"),
+            ]
         } else if let Some(message) = &self.message {
             vec![
                 Line::from(Span::styled(message.clone(), self.theme.error_style())),
@@ -1438,6 +1926,50 @@ impl App {
                 )),
                 Line::from(hint),
             ]
+        } else if self.screen == Screen::DocumentDiff
+            && let Some(diff) = &self.document_diff
+        {
+            vec![Line::from(hint), Line::from(diff.summary())]
+        } else if self.screen == Screen::DocumentView
+            && !self.document_json_view
+            && let Some(label) = self.tree_cursor_label()
+        {
+            vec![Line::from(hint), Line::from(format!("field: {label}"))]
+        } else if self.screen == Screen::Documents
+            && !self.selected_document_indexes().is_empty()
+            && self.active_search().is_none()
+        {
+            vec![
+                Line::from(hint),
+                Line::from(format!(
+                    "{} selected: {} delete  {} edit as array  {} export  {} copy  {} clear",
+                    self.selected_document_indexes().len(),
+                    self.keymap.keys_for_actions(&[KeyAction::Delete]),
+                    self.keymap.keys_for_actions(&[KeyAction::Edit]),
+                    self.keymap.keys_for_actions(&[KeyAction::ExportResults]),
+                    self.keymap.keys_for_actions(&[KeyAction::CopyResults]),
+                    self.keymap.keys_for_actions(&[KeyAction::ClearSelection]),
+                )),
+            ]
+        } else if self.screen == Screen::Documents
+            && self.document_grid.enabled
+            && self.active_search().is_none()
+            && let Some(label) = self.grid_focus_label()
+        {
+            vec![Line::from(hint), Line::from(label)]
+        } else if let Some(count) = self.count_prefix {
+            vec![Line::from(hint), Line::from(format!("count {count}"))]
+        } else if let Some(search) = self.active_search() {
+            vec![
+                Line::from(hint),
+                Line::from(format!(
+                    "filter /{}  {} next/prev match  {} clear",
+                    search.query,
+                    self.keymap
+                        .keys_for_actions(&[KeyAction::SearchNext, KeyAction::SearchPrevious]),
+                    self.keymap.keys_for_actions(&[KeyAction::ClearSearch])
+                )),
+            ]
         } else {
             vec![Line::from(hint), Line::from(" ")]
         }
@@ -1479,7 +2011,16 @@ impl App {
     }
 
     pub(crate) fn documents_list_title(&self) -> String {
-        let base = format!("Documents (page {})", self.document_page + 1);
+        let mut base = format!("Documents (page {})", self.document_page + 1);
+        let selected = self.selected_document_indexes().len();
+        if let Some(mark) = &self.diff_mark {
+            base.push_str(&format!(" [diff mark: {}]", mark.label()));
+        }
+        if self.document_selection.visual() {
+            base.push_str(&format!(" [visual: {selected} selected]"));
+        } else if selected > 0 {
+            base.push_str(&format!(" [{selected} selected]"));
+        }
         match &self.document_result_source {
             DocumentResultSource::Collection => base,
             DocumentResultSource::SavedQuery { name } => {
@@ -1498,6 +2039,12 @@ impl App {
                     "{base} [inline aggregation] [e edit draft] [x export] [y copy] [c clear applied]"
                 )
             }
+            DocumentResultSource::Reference { label } => {
+                format!(
+                    "{base} [reference: {label}] [{} back] [c clear applied]",
+                    self.keymap.keys_for_actions(&[KeyAction::ReferenceBack])
+                )
+            }
         }
     }
 }
@@ -1539,4 +2086,48 @@ mod tests {
             MainPaneLayout::Triple
         );
     }
+
+    #[test]
+    fn header_lines_show_connection_protection_badge() {
+        let mut app = App::test_app_with_storage(lazycompass_storage::StorageSnapshot {
+            config: Config {
+                connections: vec![ConnectionSpec {
+                    protection: ConnectionProtection::ReadOnly,
+                    ..App::test_connection("prod")
+                }],
+                ..Config::default()
+            },
+            queries: Vec::new(),
+            aggregations: Vec::new(),
+            warnings: Vec::new(),
+        });
+        app.connection_index = Some(0);
+        app.write_enabled = true;
+
+        let text: Vec<String> = app
+            .header_lines()
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert!(text[1].contains("READ-ONLY"));
+        assert_eq!(text[2], "MODE: READ-ONLY (protected connection)");
+    }
+
+    #[test]
+    fn header_lines_show_active_profile() {
+        let mut app = App::test_app_with_storage(lazycompass_storage::StorageSnapshot {
+            config: Config {
+                active_profile: Some("staging".to_string()),
+                ..Config::default()
+            },
+            queries: Vec::new(),
+            aggregations: Vec::new(),
+            warnings: Vec::new(),
+        });
+        let text = app.header_lines()[1].to_string();
+        assert!(text.starts_with("Profile: staging  Conn: -"));
+
+        app.storage.config.active_profile = None;
+        assert!(app.header_lines()[1].to_string().starts_with("Conn: -"));
+    }
 }
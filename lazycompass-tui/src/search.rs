@@ -0,0 +1,380 @@
+use super::*;
+
+/// `/` filter over the focused list of one screen. Selection indexes keep pointing into the full
+/// list, so actions on the selected item work unchanged while the filter is shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ListSearch {
+    pub(crate) screen: Screen,
+    pub(crate) query: String,
+    /// Keys go to the query until Enter keeps the filter and hands keys back to navigation.
+    pub(crate) editing: bool,
+    /// Selection when the search started, restored if the filter is cleared with no match.
+    pub(crate) origin: Option<usize>,
+}
+
+impl App {
+    pub(crate) fn start_search(&mut self) {
+        if self.list_items(self.screen).is_none() {
+            self.message = Some("search is not available on this screen".to_string());
+            return;
+        }
+        let screen = self.screen;
+        let origin = self
+            .list_selection_mut(screen)
+            .and_then(|selected| *selected);
+        self.search = Some(ListSearch {
+            screen,
+            query: String::new(),
+            editing: true,
+            origin,
+        });
+    }
+
+    /// The search shown on the current screen, if any.
+    pub(crate) fn active_search(&self) -> Option<&ListSearch> {
+        self.search
+            .as_ref()
+            .filter(|search| search.screen == self.screen)
+    }
+
+    /// Handles keys typed into the query, plus the search actions (`n`/`N`/Esc by default)
+    /// while a kept filter is shown. Returns whether the key was consumed.
+    pub(crate) fn handle_search_key(&mut self, key: KeyEvent) -> bool {
+        let Some(search) = self.search.as_mut() else {
+            return false;
+        };
+        if search.screen != self.screen {
+            self.search = None;
+            return false;
+        }
+
+        if search.editing {
+            match key.code {
+                KeyCode::Esc => self.clear_search(),
+                KeyCode::Enter if search.query.is_empty() => self.clear_search(),
+                KeyCode::Enter => search.editing = false,
+                KeyCode::Backspace => {
+                    search.query.pop();
+                    self.select_first_match();
+                }
+                KeyCode::Char(ch)
+                    if !key
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                {
+                    search.query.push(ch);
+                    self.select_first_match();
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        match self.keymap.search_action_for_key(key) {
+            Some(KeyAction::SearchNext) => self.jump_to_match(true),
+            Some(KeyAction::SearchPrevious) => self.jump_to_match(false),
+            Some(KeyAction::ClearSearch) => self.clear_search(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Drops the filter, going back to the item selected before it when no match was left.
+    fn clear_search(&mut self) {
+        let Some(search) = self.search.take() else {
+            return;
+        };
+        if let Some(selected) = self.list_selection_mut(search.screen)
+            && selected.is_none()
+        {
+            *selected = search.origin;
+        }
+    }
+
+    /// Indexes of the items matching the active search, in list order.
+    pub(crate) fn search_matches(&self) -> Option<Vec<usize>> {
+        let search = self.active_search()?;
+        let items = self.list_items(search.screen)?;
+        Some(
+            items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| fuzzy_match(&search.query, item).is_some())
+                .map(|(index, _)| index)
+                .collect(),
+        )
+    }
+
+    /// Keeps j/k and gg/G on matching items while a filter is shown. Returns whether the
+    /// action was handled.
+    pub(crate) fn move_within_matches(&mut self, action: KeyAction) -> bool {
+        if !matches!(
+            action,
+            KeyAction::MoveDown | KeyAction::MoveUp | KeyAction::GoTop | KeyAction::GoBottom
+        ) {
+            return false;
+        }
+        let Some(matches) = self.search_matches() else {
+            return false;
+        };
+        let screen = self.screen;
+        let Some(selected) = self.list_selection_mut(screen) else {
+            return false;
+        };
+        let Some(last) = matches.len().checked_sub(1) else {
+            return true;
+        };
+        let position = selected.and_then(|index| matches.iter().position(|item| *item == index));
+        let target = match (action, position) {
+            (KeyAction::MoveDown, Some(position)) => (position + 1).min(last),
+            (KeyAction::MoveUp, Some(position)) => position.saturating_sub(1),
+            (KeyAction::GoBottom, _) => last,
+            _ => 0,
+        };
+        *selected = Some(matches[target]);
+        true
+    }
+
+    pub(crate) fn jump_to_match(&mut self, forward: bool) {
+        let Some(matches) = self.search_matches() else {
+            return;
+        };
+        let screen = self.screen;
+        if matches.is_empty() {
+            if let Some(search) = self.active_search() {
+                self.message = Some(format!("no matches for '{}'", search.query));
+            }
+            return;
+        }
+        let Some(selected) = self.list_selection_mut(screen) else {
+            return;
+        };
+        let next = match (*selected, forward) {
+            (Some(current), true) => matches.iter().copied().find(|index| *index > current),
+            (Some(current), false) => matches.iter().rev().copied().find(|index| *index < current),
+            (None, _) => None,
+        };
+        let wrapped = if forward {
+            matches[0]
+        } else {
+            matches[matches.len() - 1]
+        };
+        *selected = Some(next.unwrap_or(wrapped));
+    }
+
+    /// Keeps the selection on a visible item; with no matches nothing is selected, so actions
+    /// cannot reach a hidden row.
+    fn select_first_match(&mut self) {
+        let Some(matches) = self.search_matches() else {
+            return;
+        };
+        let screen = self.screen;
+        if let Some(selected) = self.list_selection_mut(screen)
+            && !selected.is_some_and(|index| matches.contains(&index))
+        {
+            *selected = matches.first().copied();
+        }
+    }
+
+    /// Labels of a searchable list, as rendered on its screen.
+    pub(crate) fn list_items(&self, screen: Screen) -> Option<Vec<String>> {
+        let items = match screen {
+            Screen::Connections => self
+                .storage
+                .config
+                .connections
+                .iter()
+                .map(connection_label)
+                .collect(),
+            Screen::Databases => self.database_items.clone(),
+            Screen::Collections => self.collection_items.clone(),
+            Screen::Indexes => self.indexes.iter().map(document_preview).collect(),
+            Screen::Documents => self.document_items(),
+            Screen::SavedQuerySelect => self
+                .storage
+                .queries
+                .iter()
+                .map(|query| format!("{} ({})", query.id, saved_scope_label(&query.scope)))
+                .collect(),
+            Screen::SavedAggregationSelect => self
+                .storage
+                .aggregations
+                .iter()
+                .map(|aggregation| {
+                    format!(
+                        "{} ({})",
+                        aggregation.id,
+                        saved_scope_label(&aggregation.scope)
+                    )
+                })
+                .collect(),
+            Screen::History => self.history_items.iter().map(history_item_label).collect(),
+            _ => return None,
+        };
+        Some(items)
+    }
+
+    pub(crate) fn list_selection_mut(&mut self, screen: Screen) -> Option<&mut Option<usize>> {
+        match screen {
+            Screen::Connections => Some(&mut self.connection_index),
+            Screen::Databases => Some(&mut self.database_index),
+            Screen::Collections => Some(&mut self.collection_index),
+            Screen::Indexes => Some(&mut self.index_index),
+            Screen::Documents => Some(&mut self.document_index),
+            Screen::SavedQuerySelect => Some(&mut self.saved_query_index),
+            Screen::SavedAggregationSelect => Some(&mut self.saved_agg_index),
+            Screen::History => Some(&mut self.history_index),
+            _ => None,
+        }
+    }
+}
+
+/// Character positions in `text` matching `query` in order, or `None` when some query character
+/// is missing. Matching ignores case unless the query has an uppercase letter.
+pub(crate) fn fuzzy_match(query: &str, text: &str) -> Option<Vec<usize>> {
+    let case_sensitive = query.chars().any(char::is_uppercase);
+    let mut needle = query.chars().peekable();
+    let mut positions = Vec::new();
+    for (index, ch) in text.chars().enumerate() {
+        let Some(&wanted) = needle.peek() else {
+            break;
+        };
+        let matched = if case_sensitive {
+            ch == wanted
+        } else {
+            ch.to_lowercase().eq(wanted.to_lowercase())
+        };
+        if matched {
+            positions.push(index);
+            needle.next();
+        }
+    }
+    needle.peek().is_none().then_some(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn app_with_collections() -> App {
+        let mut app = App::test_app();
+        app.screen = Screen::Collections;
+        app.collection_items = ["orders", "users", "user_events", "audit_log"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        app.collection_index = Some(0);
+        app
+    }
+
+    fn type_query(app: &mut App, query: &str) {
+        app.start_search();
+        for ch in query.chars() {
+            assert!(app.handle_search_key(key(KeyCode::Char(ch))));
+        }
+    }
+
+    #[test]
+    fn fuzzy_match_finds_characters_in_order_with_smart_case() {
+        assert_eq!(fuzzy_match("usr", "users"), Some(vec![0, 1, 3]));
+        assert_eq!(fuzzy_match("UE", "user_events"), None);
+        assert_eq!(fuzzy_match("uE", "userEvents"), Some(vec![0, 4]));
+        assert_eq!(fuzzy_match("sx", "users"), None);
+        assert_eq!(fuzzy_match("", "users"), Some(Vec::new()));
+    }
+
+    #[test]
+    fn typing_filters_and_selects_first_match() {
+        let mut app = app_with_collections();
+        type_query(&mut app, "usev");
+
+        assert_eq!(app.search_matches(), Some(vec![2]));
+        assert_eq!(app.collection_index, Some(2));
+
+        assert!(app.handle_search_key(key(KeyCode::Backspace)));
+        assert!(app.handle_search_key(key(KeyCode::Backspace)));
+        assert_eq!(app.search_matches(), Some(vec![1, 2]));
+        assert_eq!(app.collection_index, Some(2));
+    }
+
+    #[test]
+    fn n_and_navigation_stay_on_matches_until_esc() {
+        let mut app = app_with_collections();
+        type_query(&mut app, "se");
+        assert!(app.handle_search_key(key(KeyCode::Enter)));
+        assert_eq!(app.search_matches(), Some(vec![1, 2]));
+        assert_eq!(app.collection_index, Some(1));
+
+        assert!(app.handle_search_key(key(KeyCode::Char('n'))));
+        assert_eq!(app.collection_index, Some(2));
+        assert!(app.handle_search_key(key(KeyCode::Char('n'))));
+        assert_eq!(app.collection_index, Some(1));
+        assert!(app.handle_search_key(KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT)));
+        assert_eq!(app.collection_index, Some(2));
+
+        assert!(app.move_within_matches(KeyAction::MoveUp));
+        assert_eq!(app.collection_index, Some(1));
+        assert!(app.move_within_matches(KeyAction::GoBottom));
+        assert_eq!(app.collection_index, Some(2));
+        assert!(!app.handle_search_key(key(KeyCode::Char('j'))));
+
+        assert!(app.handle_search_key(key(KeyCode::Esc)));
+        assert!(app.search.is_none());
+        assert!(!app.move_within_matches(KeyAction::MoveDown));
+    }
+
+    #[test]
+    fn no_matches_clear_the_selection_until_the_filter_goes() {
+        let mut app = app_with_collections();
+        app.collection_index = Some(3);
+        type_query(&mut app, "zz");
+        assert_eq!(app.search_matches(), Some(Vec::new()));
+        assert_eq!(app.collection_index, None);
+
+        assert!(app.handle_search_key(key(KeyCode::Backspace)));
+        assert!(app.handle_search_key(key(KeyCode::Backspace)));
+        assert!(app.handle_search_key(key(KeyCode::Char('o'))));
+        assert_eq!(app.collection_index, Some(0));
+
+        type_query(&mut app, "zz");
+        assert!(app.handle_search_key(key(KeyCode::Enter)));
+        assert_eq!(app.collection_index, None);
+        assert!(app.handle_search_key(key(KeyCode::Esc)));
+        assert_eq!(app.collection_index, Some(0));
+    }
+
+    #[test]
+    fn kept_filter_keys_follow_the_keymap() {
+        let mut app = app_with_collections();
+        let (keymap, _) = KeyMap::resolve(
+            &[(
+                "search_next".to_string(),
+                lazycompass_core::KeyChords::One("ctrl+n".to_string()),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        app.keymap = keymap;
+        type_query(&mut app, "se");
+        assert!(app.handle_search_key(key(KeyCode::Enter)));
+        assert_eq!(app.collection_index, Some(1));
+
+        assert!(!app.handle_search_key(key(KeyCode::Char('n'))));
+        assert!(app.handle_search_key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL)));
+        assert_eq!(app.collection_index, Some(2));
+    }
+
+    #[test]
+    fn search_is_dropped_when_the_screen_changes() {
+        let mut app = app_with_collections();
+        type_query(&mut app, "u");
+        app.screen = Screen::Databases;
+        assert!(app.active_search().is_none());
+        assert!(!app.handle_search_key(key(KeyCode::Char('x'))));
+        assert!(app.search.is_none());
+    }
+}
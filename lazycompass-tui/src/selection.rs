@@ -0,0 +1,370 @@
+use super::*;
+use lazycompass_mongo::DocumentBulkDeleteSpec;
+use std::collections::BTreeSet;
+
+/// Documents picked on the current page for bulk delete, export, copy, and edit. Indexes point
+/// into `App::documents` and are dropped whenever the documents are replaced.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DocumentSelection {
+    marked: BTreeSet<usize>,
+    /// Row where visual mode started; rows between it and the cursor count as selected.
+    anchor: Option<usize>,
+}
+
+impl DocumentSelection {
+    pub(crate) fn clear(&mut self) {
+        self.marked.clear();
+        self.anchor = None;
+    }
+
+    pub(crate) fn visual(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    /// Selected rows, including the visual range ending at `cursor`.
+    pub(crate) fn indexes(&self, cursor: Option<usize>) -> BTreeSet<usize> {
+        let mut indexes = self.marked.clone();
+        if let (Some(anchor), Some(cursor)) = (self.anchor, cursor) {
+            indexes.extend(anchor.min(cursor)..=anchor.max(cursor));
+        }
+        indexes
+    }
+
+    fn toggle(&mut self, index: usize) {
+        if !self.marked.remove(&index) {
+            self.marked.insert(index);
+        }
+    }
+
+    /// Starts visual mode at `cursor`, or ends it keeping the covered rows selected.
+    fn toggle_visual(&mut self, cursor: Option<usize>) {
+        if self.anchor.is_some() {
+            self.marked = self.indexes(cursor);
+            self.anchor = None;
+        } else {
+            self.anchor = cursor;
+        }
+    }
+}
+
+impl App {
+    /// Selected document indexes on the current page, in list order.
+    pub(crate) fn selected_document_indexes(&self) -> Vec<usize> {
+        if self.screen != Screen::Documents && self.export_screen() != Some(Screen::Documents) {
+            return Vec::new();
+        }
+        self.document_selection
+            .indexes(self.document_index)
+            .into_iter()
+            .filter(|index| *index < self.documents.len())
+            .collect()
+    }
+
+    pub(crate) fn selected_documents(&self) -> Vec<Document> {
+        self.selected_document_indexes()
+            .into_iter()
+            .map(|index| self.documents[index].clone())
+            .collect()
+    }
+
+    pub(crate) fn apply_selection_action(&mut self, action: KeyAction) {
+        if self.screen != Screen::Documents {
+            return;
+        }
+        match action {
+            KeyAction::VisualSelect => {
+                self.document_selection.toggle_visual(self.document_index);
+            }
+            KeyAction::ToggleSelect => {
+                if let Some(index) = self.document_index {
+                    self.document_selection.toggle(index);
+                    self.move_down();
+                }
+            }
+            KeyAction::SelectAll => {
+                let all = (0..self.documents.len()).collect::<BTreeSet<_>>();
+                if self.document_selection.indexes(self.document_index) == all {
+                    self.document_selection.clear();
+                } else {
+                    self.document_selection.marked = all;
+                    self.document_selection.anchor = None;
+                }
+            }
+            KeyAction::ClearSelection => self.document_selection.clear(),
+            _ => {}
+        }
+    }
+
+    /// Labels for the Documents list, marking selected rows while a selection exists.
+    pub(crate) fn document_items(&self) -> Vec<String> {
+        let selected = self.selected_document_indexes();
+        let marking = !selected.is_empty() || self.document_selection.visual();
+        self.documents
+            .iter()
+            .enumerate()
+            .map(|(index, document)| {
+                let preview = document_preview(document);
+                match (marking, selected.contains(&index)) {
+                    (false, _) => preview,
+                    (true, true) => format!("● {preview}"),
+                    (true, false) => format!("  {preview}"),
+                }
+            })
+            .collect()
+    }
+
+    /// Asks once to delete every selected document.
+    pub(crate) fn request_delete_selected_documents(&mut self) -> Result<()> {
+        let documents = self.selected_documents();
+        let (connection, database, collection) = self.selected_context()?;
+        let ids = documents
+            .iter()
+            .map(document_id)
+            .collect::<Result<Vec<_>>>()?;
+        let required = self
+            .connection_confirmation()
+            .unwrap_or_else(|| "delete".to_string());
+        self.confirm = Some(ConfirmState {
+            prompt: format!(
+                "delete {} selected document(s) (Conn: {connection}, Db: {database}, Coll: {collection})",
+                ids.len()
+            ),
+            action: ConfirmAction::DeleteDocuments {
+                spec: DocumentBulkDeleteSpec {
+                    connection: Some(connection),
+                    database,
+                    collection,
+                    ids,
+                },
+            },
+            input: String::new(),
+            required: Some(required),
+        });
+        Ok(())
+    }
+
+    pub(crate) fn perform_delete_documents(&mut self, spec: DocumentBulkDeleteSpec) -> Result<()> {
+        let deleted = self.runtime.block_on(self.executor.delete_documents(
+            &self.storage.config,
+            self.write_guard(),
+            &spec,
+        ))?;
+        self.document_selection.clear();
+        self.reload_documents_after_change()?;
+        self.message = Some(format!("deleted {deleted} document(s)"));
+        Ok(())
+    }
+
+    /// Opens the selected documents in the editor as one JSON array and replaces the ones that
+    /// changed, matching them by `_id`.
+    pub(crate) fn edit_documents_with_context(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        connection: String,
+        database: String,
+        collection: String,
+        documents: Vec<Document>,
+    ) -> Result<()> {
+        let editor = self
+            .editor_command
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("editor command missing"))?;
+        let initial = lazycompass_mongo::render_relaxed_extjson_documents(&documents)?;
+        let contents = self.open_editor(terminal, editor, "edit", &initial)?;
+        if is_editor_cancelled(&contents, &initial) {
+            self.message = Some("cancelled".to_string());
+            return Ok(());
+        }
+        let edited = parse_document_array(&contents)?;
+        let changed = changed_documents(&documents, edited)?;
+        if changed.is_empty() {
+            self.message = Some("no documents changed".to_string());
+            return Ok(());
+        }
+        let specs = changed
+            .into_iter()
+            .map(|(id, document)| DocumentReplaceSpec {
+                connection: Some(connection.clone()),
+                database: database.clone(),
+                collection: collection.clone(),
+                id,
+                document,
+            })
+            .collect::<Vec<_>>();
+        if let Some(required) = self.connection_confirmation() {
+            self.confirm = Some(ConfirmState {
+                prompt: format!(
+                    "replace {} document(s) in {database}.{collection} on protected connection '{required}'",
+                    specs.len()
+                ),
+                action: ConfirmAction::ReplaceDocuments { specs },
+                input: String::new(),
+                required: Some(required),
+            });
+            return Ok(());
+        }
+        self.perform_replace_documents(specs)
+    }
+
+    pub(crate) fn perform_replace_documents(
+        &mut self,
+        specs: Vec<DocumentReplaceSpec>,
+    ) -> Result<()> {
+        let guard = self.write_guard();
+        for (done, spec) in specs.iter().enumerate() {
+            if let Err(error) = self.runtime.block_on(self.executor.replace_document(
+                &self.storage.config,
+                guard,
+                spec,
+            )) {
+                let _ = self.reload_documents_after_change();
+                return Err(error.context(format!(
+                    "updated {done} of {} document(s) before failing",
+                    specs.len()
+                )));
+            }
+        }
+        self.document_selection.clear();
+        self.reload_documents_after_change()?;
+        self.message = Some(format!("updated {} document(s)", specs.len()));
+        Ok(())
+    }
+}
+
+fn parse_document_array(contents: &str) -> Result<Vec<Document>> {
+    match lazycompass_mongo::parse_json_value("documents", contents)? {
+        Bson::Array(items) => items
+            .into_iter()
+            .map(|item| match item {
+                Bson::Document(document) => Ok(document),
+                _ => anyhow::bail!("documents must be a JSON array of objects"),
+            })
+            .collect(),
+        _ => anyhow::bail!("documents must be a JSON array of objects"),
+    }
+}
+
+/// Pairs edited documents with the originals by `_id`, keeping only the ones that changed.
+fn changed_documents(
+    originals: &[Document],
+    edited: Vec<Document>,
+) -> Result<Vec<(Bson, Document)>> {
+    let mut changed = Vec::new();
+    let mut seen = Vec::new();
+    for document in edited {
+        let id = document
+            .get("_id")
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("every edited document needs its original _id"))?;
+        let original = originals
+            .iter()
+            .find(|original| original.get("_id") == Some(&id))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "_id {} is not in the selection; only selected documents can be edited",
+                    format_bson(&id)
+                )
+            })?;
+        if seen.contains(&id) {
+            anyhow::bail!("_id {} appears more than once", format_bson(&id));
+        }
+        seen.push(id.clone());
+        if original != &document {
+            changed.push((id, document));
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn documents() -> Vec<Document> {
+        (1..=4)
+            .map(|id| Document::from_iter([("_id".to_string(), Bson::Int32(id))]))
+            .collect()
+    }
+
+    fn documents_app() -> App {
+        let mut app = App::test_app();
+        app.screen = Screen::Documents;
+        app.documents = documents();
+        app.document_index = Some(0);
+        app
+    }
+
+    #[test]
+    fn visual_mode_and_toggles_build_the_selection() {
+        let mut app = documents_app();
+        app.apply_selection_action(KeyAction::ToggleSelect);
+        assert_eq!(app.selected_document_indexes(), vec![0]);
+        assert_eq!(app.document_index, Some(1));
+
+        app.document_index = Some(2);
+        app.apply_selection_action(KeyAction::VisualSelect);
+        app.document_index = Some(3);
+        assert_eq!(app.selected_document_indexes(), vec![0, 2, 3]);
+        assert_eq!(app.document_items()[1], "  {\"_id\":2}");
+        assert!(app.document_items()[3].starts_with("● "));
+
+        app.apply_selection_action(KeyAction::VisualSelect);
+        app.document_index = Some(0);
+        assert_eq!(app.selected_document_indexes(), vec![0, 2, 3]);
+
+        app.apply_selection_action(KeyAction::SelectAll);
+        assert_eq!(app.selected_document_indexes(), vec![0, 1, 2, 3]);
+        app.apply_selection_action(KeyAction::SelectAll);
+        assert!(app.selected_document_indexes().is_empty());
+        assert_eq!(app.document_items()[0], "{\"_id\":1}");
+    }
+
+    #[test]
+    fn bulk_actions_use_the_selection() {
+        let mut app = documents_app();
+        app.document_result_source = DocumentResultSource::InlineQuery;
+        app.apply_selection_action(KeyAction::SelectAll);
+        app.apply_selection_action(KeyAction::ToggleSelect);
+
+        let target = app.export_target().expect("export target");
+        assert_eq!(target.documents.len(), 3);
+        assert!(!target.single_document);
+
+        app.write_enabled = true;
+        app.select_test_namespace("app", "users");
+        app.request_delete_document().expect("delete");
+        let confirm = app.confirm.expect("confirm");
+        assert_eq!(
+            confirm.prompt,
+            "delete 3 selected document(s) (Conn: local, Db: app, Coll: users)"
+        );
+        match confirm.action {
+            ConfirmAction::DeleteDocuments { spec } => assert_eq!(
+                spec.ids,
+                vec![Bson::Int32(2), Bson::Int32(3), Bson::Int32(4)]
+            ),
+            other => panic!("unexpected action {other:?}"),
+        }
+    }
+
+    #[test]
+    fn array_edits_replace_only_changed_selected_documents() {
+        let originals = documents();
+        let edited =
+            parse_document_array(r#"[{"_id": 1}, {"_id": 2, "status": "done"}]"#).expect("array");
+        let changed = changed_documents(&originals, edited).expect("changed");
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].0, Bson::Int32(2));
+
+        let error = changed_documents(
+            &originals[..1],
+            parse_document_array(r#"[{"_id": 9}]"#).expect("array"),
+        )
+        .expect_err("unknown id");
+        assert_eq!(
+            error.to_string(),
+            "_id 9 is not in the selection; only selected documents can be edited"
+        );
+        assert!(parse_document_array(r#"{"_id": 1}"#).is_err());
+    }
+}
@@ -0,0 +1,373 @@
+use lazycompass_core::{SessionDraft, SessionRestore, SessionScreen, SessionState};
+use lazycompass_storage::{load_session, save_session};
+
+use super::*;
+
+impl App {
+    /// Offers (or performs, with `[session] restore = "always"`) a jump back to where the last
+    /// session in this repo left off.
+    pub(crate) fn offer_session_restore(&mut self) {
+        let restore = self.storage.config.session.restore();
+        if restore == SessionRestore::Never {
+            return;
+        }
+        let session = match load_session(&self.paths) {
+            Ok(Some(session)) => session,
+            Ok(None) => return,
+            Err(error) => {
+                self.warnings.push_back(format!(
+                    "unable to load last session: {}",
+                    format_error(&error)
+                ));
+                return;
+            }
+        };
+        if !self
+            .storage
+            .config
+            .connections
+            .iter()
+            .any(|connection| connection.name == session.connection)
+        {
+            self.warnings.push_back(format!(
+                "last session's connection '{}' no longer exists",
+                session.connection
+            ));
+            return;
+        }
+        if restore == SessionRestore::Always {
+            self.restore_session(session);
+            return;
+        }
+        self.confirm = Some(ConfirmState {
+            prompt: format!("Restore last session ({})?", session_label(&session)),
+            action: ConfirmAction::RestoreSession { session },
+            input: String::new(),
+            required: None,
+        });
+    }
+
+    /// Selects the session's connection and walks down to its namespace as each list loads, so
+    /// a database or collection that no longer exists stops the restore at its parent list.
+    pub(crate) fn restore_session(&mut self, session: SessionState) {
+        let Some(index) = self
+            .storage
+            .config
+            .connections
+            .iter()
+            .position(|connection| connection.name == session.connection)
+        else {
+            return;
+        };
+        self.connection_index = Some(index);
+        if let Some(raw) = session.inline_query.clone() {
+            self.inline_query_draft = Some(InlineQueryDraft {
+                parsed: parse_inline_query_payload(&raw).ok(),
+                raw,
+            });
+        }
+        if let Some(raw) = session.inline_aggregation.clone() {
+            self.inline_aggregation_draft = Some(InlineAggregationDraft {
+                parsed: parse_inline_aggregation_payload(&raw).ok(),
+                raw,
+            });
+        }
+        if session.screen == SessionScreen::Connections || session.database.is_none() {
+            return;
+        }
+        if let Err(error) = self.start_load_databases() {
+            self.set_error_message(&error);
+            return;
+        }
+        self.screen = Screen::Databases;
+        self.pending_session = Some(session);
+    }
+
+    pub(crate) fn resume_session_after_databases(&mut self) {
+        let Some(session) = self.pending_session.take() else {
+            return;
+        };
+        if self.screen != Screen::Databases {
+            return;
+        }
+        let Some(database) = session.database.as_deref() else {
+            return;
+        };
+        let Some(index) = self.database_items.iter().position(|item| item == database) else {
+            self.message = Some(format!("database '{database}' no longer exists"));
+            return;
+        };
+        self.database_index = Some(index);
+        if session.screen == SessionScreen::Databases {
+            return;
+        }
+        if let Err(error) = self.start_load_collections() {
+            self.set_error_message(&error);
+            return;
+        }
+        self.screen = Screen::Collections;
+        self.pending_session = Some(session);
+    }
+
+    pub(crate) fn resume_session_after_collections(&mut self) {
+        let Some(session) = self.pending_session.take() else {
+            return;
+        };
+        if self.screen != Screen::Collections {
+            return;
+        }
+        let Some(collection) = session.collection.as_deref() else {
+            return;
+        };
+        let Some(index) = self
+            .collection_items
+            .iter()
+            .position(|item| item == collection)
+        else {
+            self.message = Some(format!("collection '{collection}' no longer exists"));
+            return;
+        };
+        self.collection_index = Some(index);
+        if session.screen != SessionScreen::Documents {
+            return;
+        }
+
+        // Drafts are only restored, never re-run: a pipeline may contain `$out` or `$merge`.
+        self.screen = Screen::Documents;
+        self.document_page = session.document_page;
+        let pending_index = self.pending_jump_index.take();
+        if let Err(error) =
+            self.start_load_documents(pending_index, DocumentLoadReason::EnterCollection)
+        {
+            self.set_error_message(&error);
+            return;
+        }
+        let (draft, action) = match session.active_inline_draft {
+            Some(SessionDraft::Query) if self.inline_query_draft.is_some() => {
+                ("query", KeyAction::RunInlineQuery)
+            }
+            Some(SessionDraft::Aggregation) if self.inline_aggregation_draft.is_some() => {
+                ("aggregation", KeyAction::RunInlineAggregation)
+            }
+            _ => return,
+        };
+        self.message = Some(format!(
+            "restored last {draft} draft; press {} to run it",
+            self.keymap.keys_for_actions(&[action])
+        ));
+    }
+
+    pub(crate) fn session_state(&self) -> Option<SessionState> {
+        let connection = self.selected_connection()?.name.clone();
+        let screen = match self.screen {
+            Screen::Connections | Screen::AddConnectionScopeSelect => SessionScreen::Connections,
+            Screen::Databases => SessionScreen::Databases,
+            Screen::Collections | Screen::Indexes | Screen::IndexView => SessionScreen::Collections,
+            Screen::Documents
+            | Screen::DocumentView
+            | Screen::DocumentDiff
+            | Screen::ExportFormatSelect
+            | Screen::SavedQuerySelect
+            | Screen::SavedAggregationSelect
+            | Screen::SaveQueryScopeSelect
+            | Screen::SaveAggregationScopeSelect
+            | Screen::History
+            | Screen::PipelineBuilder => SessionScreen::Documents,
+        };
+        let (database, collection) = match screen {
+            SessionScreen::Connections => (None, None),
+            SessionScreen::Databases => (self.selected_database().map(str::to_string), None),
+            SessionScreen::Collections | SessionScreen::Documents => (
+                self.selected_database().map(str::to_string),
+                self.selected_collection().map(str::to_string),
+            ),
+        };
+        let active_inline_draft = match (screen, self.active_inline_draft) {
+            (SessionScreen::Documents, Some(InlineDraftKind::Query)) => Some(SessionDraft::Query),
+            (SessionScreen::Documents, Some(InlineDraftKind::Aggregation)) => {
+                Some(SessionDraft::Aggregation)
+            }
+            _ => None,
+        };
+        Some(SessionState {
+            connection,
+            database,
+            collection,
+            screen,
+            document_page: self.document_page,
+            inline_query: self
+                .inline_query_draft
+                .as_ref()
+                .map(|draft| draft.raw.clone()),
+            inline_aggregation: self
+                .inline_aggregation_draft
+                .as_ref()
+                .map(|draft| draft.raw.clone()),
+            active_inline_draft,
+        })
+    }
+
+    pub(crate) fn record_session(&self) -> Result<()> {
+        if self.storage.config.session.restore() == SessionRestore::Never {
+            return Ok(());
+        }
+        match self.session_state() {
+            Some(session) => save_session(&self.paths, &session),
+            None => Ok(()),
+        }
+    }
+}
+
+fn session_label(session: &SessionState) -> String {
+    match (&session.database, &session.collection) {
+        (Some(database), Some(collection)) => {
+            format!("{} {database}.{collection}", session.connection)
+        }
+        (Some(database), None) => format!("{} {database}", session.connection),
+        _ => session.connection.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazycompass_core::{Config, SessionConfig};
+    use lazycompass_storage::StorageSnapshot;
+
+    use super::*;
+
+    fn app_with_connections(restore: Option<SessionRestore>) -> App {
+        App::test_app_with_storage(StorageSnapshot {
+            config: Config {
+                connections: vec![
+                    App::test_connection("local"),
+                    App::test_connection("staging"),
+                ],
+                session: SessionConfig { restore },
+                ..Config::default()
+            },
+            queries: Vec::new(),
+            aggregations: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn documents_session() -> SessionState {
+        SessionState {
+            connection: "staging".to_string(),
+            database: Some("app".to_string()),
+            collection: Some("users".to_string()),
+            screen: SessionScreen::Documents,
+            document_page: 3,
+            inline_query: Some(r#"{ "filter": { "active": true } }"#.to_string()),
+            inline_aggregation: None,
+            active_inline_draft: None,
+        }
+    }
+
+    #[test]
+    fn session_state_records_namespace_and_drafts() {
+        let mut app = app_with_connections(None);
+        app.connection_index = Some(1);
+        app.database_items = vec!["app".to_string()];
+        app.database_index = Some(0);
+        app.collection_items = vec!["users".to_string()];
+        app.collection_index = Some(0);
+        app.screen = Screen::DocumentView;
+        app.document_page = 3;
+        app.inline_query_draft = Some(InlineQueryDraft {
+            raw: r#"{ "filter": { "active": true } }"#.to_string(),
+            parsed: None,
+        });
+
+        assert_eq!(app.session_state(), Some(documents_session()));
+
+        app.screen = Screen::Databases;
+        let session = app.session_state().expect("session");
+        assert_eq!(session.screen, SessionScreen::Databases);
+        assert_eq!(session.collection, None);
+    }
+
+    #[test]
+    fn offer_session_restore_prompts_for_saved_session() {
+        let mut app = app_with_connections(None);
+        save_session(&app.paths, &documents_session()).expect("save session");
+
+        app.offer_session_restore();
+
+        let confirm = app.confirm.as_ref().expect("restore prompt");
+        assert_eq!(confirm.prompt, "Restore last session (staging app.users)?");
+        assert!(matches!(
+            confirm.action,
+            ConfirmAction::RestoreSession { ref session } if *session == documents_session()
+        ));
+    }
+
+    #[test]
+    fn offer_session_restore_skips_missing_connection_and_never() {
+        let mut app = app_with_connections(None);
+        let mut session = documents_session();
+        session.connection = "removed".to_string();
+        save_session(&app.paths, &session).expect("save session");
+        app.offer_session_restore();
+        assert!(app.confirm.is_none());
+        assert!(
+            app.warnings
+                .iter()
+                .any(|warning| warning == "last session's connection 'removed' no longer exists")
+        );
+
+        let mut app = app_with_connections(Some(SessionRestore::Never));
+        save_session(&app.paths, &documents_session()).expect("save session");
+        app.offer_session_restore();
+        assert!(app.confirm.is_none());
+        assert_eq!(app.connection_index, Some(0));
+    }
+
+    #[test]
+    fn restore_session_walks_down_to_collection() {
+        let mut app = app_with_connections(Some(SessionRestore::Always));
+        app.restore_session(documents_session());
+        assert_eq!(app.connection_index, Some(1));
+        assert_eq!(app.screen, Screen::Databases);
+        assert!(app.inline_query_draft.is_some());
+
+        app.database_items = vec!["admin".to_string(), "app".to_string()];
+        app.resume_session_after_databases();
+        assert_eq!(app.database_index, Some(1));
+        assert_eq!(app.screen, Screen::Collections);
+
+        app.collection_items = vec!["orders".to_string()];
+        app.resume_session_after_collections();
+        assert_eq!(app.screen, Screen::Collections);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("collection 'users' no longer exists")
+        );
+        assert!(app.pending_session.is_none());
+    }
+
+    #[test]
+    fn resume_session_restores_drafts_without_running_them() {
+        let mut app = app_with_connections(Some(SessionRestore::Always));
+        let mut session = documents_session();
+        session.inline_aggregation =
+            Some(r#"[{ "$match": {} }, { "$out": "archive" }]"#.to_string());
+        session.active_inline_draft = Some(SessionDraft::Aggregation);
+        app.restore_session(session);
+        app.database_items = vec!["app".to_string()];
+        app.resume_session_after_databases();
+        app.collection_items = vec!["users".to_string()];
+        app.resume_session_after_collections();
+
+        assert_eq!(app.screen, Screen::Documents);
+        assert_eq!(app.document_page, 3);
+        assert!(app.inline_aggregation_draft.is_some());
+        assert_eq!(app.active_inline_draft, None);
+        assert!(app.pending_history.is_empty());
+        assert!(app.document_load_id.is_some());
+        assert_eq!(
+            app.message.as_deref(),
+            Some("restored last aggregation draft; press S to run it")
+        );
+    }
+}
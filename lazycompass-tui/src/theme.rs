@@ -1,4 +1,8 @@
 use super::*;
+use lazycompass_core::ThemePalette;
+use lazycompass_storage::load_theme_file;
+use std::str::FromStr;
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct Theme {
     text: Color,
@@ -8,6 +12,11 @@ pub(crate) struct Theme {
     selection_bg: Color,
     warning: Color,
     error: Color,
+    json_key: Color,
+    json_string: Color,
+    json_number: Color,
+    json_boolean: Color,
+    json_null: Color,
 }
 
 impl Theme {
@@ -26,10 +35,21 @@ impl Theme {
     }
 
     pub(crate) fn selection_style(self) -> Style {
-        Style::default()
+        let style = Style::default()
             .fg(self.selection_fg)
             .bg(self.selection_bg)
-            .add_modifier(Modifier::BOLD)
+            .add_modifier(Modifier::BOLD);
+        if self.selection_bg == Color::Reset {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style
+        }
+    }
+
+    pub(crate) fn match_style(self) -> Style {
+        Style::default()
+            .fg(self.accent)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
     }
 
     pub(crate) fn warning_style(self) -> Style {
@@ -39,6 +59,20 @@ impl Theme {
     pub(crate) fn error_style(self) -> Style {
         Style::default().fg(self.error)
     }
+
+    /// Extended JSON wrappers such as `$oid` take the accent color so they stand apart from
+    /// ordinary keys.
+    pub(crate) fn json_style(self, token: JsonToken) -> Style {
+        let style = Style::default();
+        match token {
+            JsonToken::Key => style.fg(self.json_key),
+            JsonToken::String => style.fg(self.json_string),
+            JsonToken::Number => style.fg(self.json_number),
+            JsonToken::Boolean => style.fg(self.json_boolean),
+            JsonToken::Null => style.fg(self.json_null),
+            JsonToken::Extended => style.fg(self.accent).add_modifier(Modifier::ITALIC),
+        }
+    }
 }
 
 const THEME_CLASSIC: Theme = Theme {
@@ -49,6 +83,11 @@ const THEME_CLASSIC: Theme = Theme {
     selection_bg: Color::Cyan,
     warning: Color::Yellow,
     error: Color::Red,
+    json_key: Color::Cyan,
+    json_string: Color::Green,
+    json_number: Color::Yellow,
+    json_boolean: Color::Magenta,
+    json_null: Color::DarkGray,
 };
 
 const THEME_EMBER: Theme = Theme {
@@ -59,18 +98,155 @@ const THEME_EMBER: Theme = Theme {
     selection_bg: Color::LightRed,
     warning: Color::LightYellow,
     error: Color::LightRed,
+    json_key: Color::LightRed,
+    json_string: Color::LightYellow,
+    json_number: Color::LightMagenta,
+    json_boolean: Color::LightCyan,
+    json_null: Color::DarkGray,
+};
+
+const THEME_PAPER: Theme = Theme {
+    text: Color::Black,
+    accent: Color::Blue,
+    border: Color::Gray,
+    selection_fg: Color::White,
+    selection_bg: Color::Blue,
+    warning: Color::Indexed(130),
+    error: Color::Red,
+    json_key: Color::Blue,
+    json_string: Color::Indexed(28),
+    json_number: Color::Indexed(90),
+    json_boolean: Color::Indexed(130),
+    json_null: Color::Gray,
+};
+
+/// Terminal defaults only; selection falls back to reverse video.
+const THEME_NO_COLOR: Theme = Theme {
+    text: Color::Reset,
+    accent: Color::Reset,
+    border: Color::Reset,
+    selection_fg: Color::Reset,
+    selection_bg: Color::Reset,
+    warning: Color::Reset,
+    error: Color::Reset,
+    json_key: Color::Reset,
+    json_string: Color::Reset,
+    json_number: Color::Reset,
+    json_boolean: Color::Reset,
+    json_null: Color::Reset,
 };
-pub(crate) fn resolve_theme(config: &Config) -> (Theme, Option<String>) {
-    let name = config.theme.name.as_deref().unwrap_or_default();
-    if name.trim().is_empty() {
-        return (THEME_CLASSIC, None);
-    }
-    match theme_by_name(name) {
-        Some(theme) => (theme, None),
-        None => (
-            THEME_CLASSIC,
-            Some(format!("unknown theme '{name}', using classic")),
-        ),
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Background {
+    Light,
+    Dark,
+}
+
+/// Terminal facts that affect theme choice, read from the environment.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ThemeEnv {
+    no_color: bool,
+    background: Option<Background>,
+}
+
+impl ThemeEnv {
+    pub(crate) fn from_env() -> Self {
+        Self {
+            no_color: std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()),
+            background: background_from_colorfgbg(std::env::var("COLORFGBG").ok().as_deref()),
+        }
+    }
+}
+
+/// Reads the background index from `COLORFGBG` (`"fg;bg"`, as set by rxvt, Konsole, iTerm2).
+pub(crate) fn background_from_colorfgbg(value: Option<&str>) -> Option<Background> {
+    let background: u8 = value?.rsplit(';').next()?.trim().parse().ok()?;
+    Some(match background {
+        7 | 9..=15 => Background::Light,
+        _ => Background::Dark,
+    })
+}
+
+pub(crate) fn resolve_theme(config: &Config, paths: &ConfigPaths) -> (Theme, Vec<String>) {
+    resolve_theme_with(config, paths, ThemeEnv::from_env())
+}
+
+pub(crate) fn resolve_theme_with(
+    config: &Config,
+    paths: &ConfigPaths,
+    env: ThemeEnv,
+) -> (Theme, Vec<String>) {
+    if env.no_color {
+        return (THEME_NO_COLOR, Vec::new());
+    }
+    let mut warnings = Vec::new();
+    let name = config.theme.name.as_deref().unwrap_or_default().trim();
+    let name = if name.is_empty() || name.eq_ignore_ascii_case("auto") {
+        let background = env.background.unwrap_or(Background::Dark);
+        match background {
+            Background::Light => config.theme.light.as_deref().unwrap_or("paper"),
+            Background::Dark => config.theme.dark.as_deref().unwrap_or("classic"),
+        }
+    } else {
+        name
+    };
+
+    let mut theme = match theme_by_name(name) {
+        Some(theme) => theme,
+        None => match load_theme_file(paths, name.trim()) {
+            Ok(Some(file)) => {
+                let base = file.base.as_deref().unwrap_or("classic");
+                let mut theme = theme_by_name(base).unwrap_or_else(|| {
+                    warnings.push(format!(
+                        "unknown base theme '{base}' in theme '{name}', using classic"
+                    ));
+                    THEME_CLASSIC
+                });
+                theme.apply_palette(&file.colors, &format!("theme '{name}'"), &mut warnings);
+                theme
+            }
+            Ok(None) => {
+                warnings.push(format!("unknown theme '{name}', using classic"));
+                THEME_CLASSIC
+            }
+            Err(error) => {
+                warnings.push(format!("{}, using classic", format_error(&error)));
+                THEME_CLASSIC
+            }
+        },
+    };
+    theme.apply_palette(&config.theme.colors, "[theme]", &mut warnings);
+    (theme, warnings)
+}
+
+impl Theme {
+    fn apply_palette(&mut self, palette: &ThemePalette, source: &str, warnings: &mut Vec<String>) {
+        for (key, value) in palette.entries() {
+            let color = match Color::from_str(value.trim()) {
+                Ok(color) => color,
+                Err(_) => {
+                    warnings.push(format!(
+                        "invalid color '{value}' for {key} in {source}; use a name, #rrggbb, or 0-255"
+                    ));
+                    continue;
+                }
+            };
+            match key {
+                "text" => self.text = color,
+                "accent" => self.accent = color,
+                "border" => self.border = color,
+                "selection_fg" => self.selection_fg = color,
+                "selection_bg" => self.selection_bg = color,
+                "warning" => self.warning = color,
+                "error" => self.error = color,
+                "json_key" => self.json_key = color,
+                "json_string" => self.json_string = color,
+                "json_number" => self.json_number = color,
+                "json_boolean" => self.json_boolean = color,
+                "json_null" => self.json_null = color,
+                _ => {}
+            }
+        }
     }
 }
 
@@ -78,6 +254,7 @@ pub(crate) fn theme_by_name(name: &str) -> Option<Theme> {
     match name.trim().to_ascii_lowercase().as_str() {
         "classic" | "default" => Some(THEME_CLASSIC),
         "ember" => Some(THEME_EMBER),
+        "paper" | "light" => Some(THEME_PAPER),
         _ => None,
     }
 }
@@ -85,6 +262,34 @@ pub(crate) fn theme_by_name(name: &str) -> Option<Theme> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use lazycompass_core::ThemeConfig;
+
+    const DARK: ThemeEnv = ThemeEnv {
+        no_color: false,
+        background: Some(Background::Dark),
+    };
+
+    fn test_paths() -> ConfigPaths {
+        let root = std::env::temp_dir().join(format!(
+            "lazycompass_theme_test_{}_{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        ));
+        ConfigPaths {
+            global_root: root.join("global"),
+            repo_root: None,
+        }
+    }
+
+    fn config_with_theme(theme: ThemeConfig) -> Config {
+        Config {
+            theme,
+            ..Config::default()
+        }
+    }
 
     #[test]
     pub(crate) fn resolve_theme_warns_on_unknown() {
@@ -92,13 +297,20 @@ mod tests {
             connections: Vec::new(),
             theme: lazycompass_core::ThemeConfig {
                 name: Some("mystery".to_string()),
+                ..Default::default()
             },
             logging: lazycompass_core::LoggingConfig::default(),
             allow_insecure: None,
             timeouts: lazycompass_core::TimeoutConfig::default(),
+            history: lazycompass_core::HistoryConfig::default(),
+            session: lazycompass_core::SessionConfig::default(),
+            keys: Default::default(),
+            profiles: Default::default(),
+            references: Vec::new(),
+            active_profile: None,
         };
-        let (theme, warning) = resolve_theme(&config);
-        assert!(warning.is_some());
+        let (theme, warnings) = resolve_theme_with(&config, &test_paths(), DARK);
+        assert_eq!(warnings, vec!["unknown theme 'mystery', using classic"]);
         assert_eq!(theme.border, THEME_CLASSIC.border);
     }
 
@@ -108,13 +320,124 @@ mod tests {
             connections: Vec::new(),
             theme: lazycompass_core::ThemeConfig {
                 name: Some("ember".to_string()),
+                ..Default::default()
             },
             logging: lazycompass_core::LoggingConfig::default(),
             allow_insecure: None,
             timeouts: lazycompass_core::TimeoutConfig::default(),
+            history: lazycompass_core::HistoryConfig::default(),
+            session: lazycompass_core::SessionConfig::default(),
+            keys: Default::default(),
+            profiles: Default::default(),
+            references: Vec::new(),
+            active_profile: None,
+        };
+        let (theme, warnings) = resolve_theme_with(&config, &test_paths(), DARK);
+        assert!(warnings.is_empty());
+        assert_eq!(theme.accent, THEME_EMBER.accent);
+    }
+
+    #[test]
+    fn resolve_theme_applies_palette_overrides_and_reports_bad_colors() {
+        let config = config_with_theme(ThemeConfig {
+            name: Some("classic".to_string()),
+            colors: ThemePalette {
+                accent: Some("#ff8800".to_string()),
+                border: Some("240".to_string()),
+                selection_bg: Some("light-blue".to_string()),
+                error: Some("blood".to_string()),
+                json_key: Some("nope".to_string()),
+                ..ThemePalette::default()
+            },
+            ..ThemeConfig::default()
+        });
+        let (theme, warnings) = resolve_theme_with(&config, &test_paths(), DARK);
+        assert_eq!(theme.accent, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.border, Color::Indexed(240));
+        assert_eq!(theme.selection_bg, Color::LightBlue);
+        assert_eq!(theme.error, THEME_CLASSIC.error);
+        assert_eq!(theme.json_key, THEME_CLASSIC.json_key);
+        assert_eq!(
+            warnings,
+            vec![
+                "invalid color 'blood' for error in [theme]; use a name, #rrggbb, or 0-255",
+                "invalid color 'nope' for json_key in [theme]; use a name, #rrggbb, or 0-255",
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_theme_loads_theme_files_over_a_base() {
+        let paths = test_paths();
+        std::fs::create_dir_all(paths.global_root.join("themes")).expect("create themes dir");
+        std::fs::write(
+            paths.global_root.join("themes/solar.toml"),
+            "base = \"ember\"\ntext = \"#eee8d5\"\n",
+        )
+        .expect("write theme");
+        let config = config_with_theme(ThemeConfig {
+            name: Some("solar".to_string()),
+            colors: ThemePalette {
+                warning: Some("magenta".to_string()),
+                json_string: Some("#a3be8c".to_string()),
+                ..ThemePalette::default()
+            },
+            ..ThemeConfig::default()
+        });
+        let (theme, warnings) = resolve_theme_with(&config, &paths, DARK);
+        assert!(warnings.is_empty());
+        assert_eq!(theme.text, Color::Rgb(0xee, 0xe8, 0xd5));
+        assert_eq!(theme.accent, THEME_EMBER.accent);
+        assert_eq!(theme.warning, Color::Magenta);
+        assert_eq!(theme.json_string, Color::Rgb(0xa3, 0xbe, 0x8c));
+        assert_eq!(theme.json_key, THEME_EMBER.json_key);
+    }
+
+    #[test]
+    fn resolve_theme_follows_terminal_background_and_no_color() {
+        let config = config_with_theme(ThemeConfig {
+            dark: Some("ember".to_string()),
+            ..ThemeConfig::default()
+        });
+        let light = ThemeEnv {
+            no_color: false,
+            background: background_from_colorfgbg(Some("0;15")),
         };
-        let (theme, warning) = resolve_theme(&config);
-        assert!(warning.is_none());
+        let (theme, _) = resolve_theme_with(&config, &test_paths(), light);
+        assert_eq!(theme.accent, THEME_PAPER.accent);
+        let (theme, _) = resolve_theme_with(&config, &test_paths(), DARK);
         assert_eq!(theme.accent, THEME_EMBER.accent);
+
+        let no_color = ThemeEnv {
+            no_color: true,
+            background: None,
+        };
+        let (theme, warnings) = resolve_theme_with(&config, &test_paths(), no_color);
+        assert!(warnings.is_empty());
+        assert_eq!(theme.accent, Color::Reset);
+        assert!(
+            theme
+                .selection_style()
+                .add_modifier
+                .contains(Modifier::REVERSED)
+        );
+    }
+
+    #[test]
+    fn background_from_colorfgbg_reads_last_field() {
+        assert_eq!(
+            background_from_colorfgbg(Some("15;0")),
+            Some(Background::Dark)
+        );
+        assert_eq!(
+            background_from_colorfgbg(Some("0;default;15")),
+            Some(Background::Light)
+        );
+        assert_eq!(
+            background_from_colorfgbg(Some("0;7")),
+            Some(Background::Light)
+        );
+        assert_eq!(background_from_colorfgbg(Some("garbage")), None);
+        assert_eq!(background_from_colorfgbg(None), None);
     }
 }
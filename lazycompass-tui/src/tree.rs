@@ -0,0 +1,491 @@
+use super::*;
+use std::collections::HashSet;
+
+const MAX_INLINE_VALUE_CHARS: usize = 80;
+const MAX_TREE_CHILDREN: usize = 500;
+
+/// Expansion state and cursor for the document detail tree. Nodes are keyed by dotted field
+/// path (`address.city`, `tags.0`), so state survives re-rendering the same document.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DocumentTree {
+    expanded: HashSet<String>,
+    full_values: HashSet<String>,
+    pub(crate) cursor: usize,
+    pub(crate) offset: usize,
+    /// `_id` of the document the state belongs to.
+    document_id: Option<Bson>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TreeRow {
+    pub(crate) path: String,
+    pub(crate) depth: usize,
+    pub(crate) key: String,
+    pub(crate) type_name: &'static str,
+    pub(crate) node: TreeNode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TreeNode {
+    Container {
+        len: usize,
+        expanded: bool,
+    },
+    Value {
+        text: String,
+        truncated: bool,
+    },
+    /// Children past [`MAX_TREE_CHILDREN`] that are not listed.
+    More(usize),
+}
+
+impl DocumentTree {
+    /// Starts fresh for `document` unless it is the document already shown, so a reload after
+    /// a field edit keeps the tree open where it was.
+    pub(crate) fn show(&mut self, document: &Document) {
+        let id = document.get("_id").cloned();
+        if id.is_none() || self.document_id != id {
+            *self = Self {
+                document_id: id,
+                ..Self::default()
+            };
+        }
+    }
+
+    pub(crate) fn rows(&self, document: &Document) -> Vec<TreeRow> {
+        let mut rows = Vec::new();
+        self.push_children(
+            document.iter().map(|(key, value)| (key.clone(), value)),
+            document.len(),
+            "",
+            0,
+            &mut rows,
+        );
+        rows
+    }
+
+    fn push_children<'a>(
+        &self,
+        children: impl Iterator<Item = (String, &'a Bson)>,
+        len: usize,
+        parent: &str,
+        depth: usize,
+        rows: &mut Vec<TreeRow>,
+    ) {
+        for (key, value) in children.take(MAX_TREE_CHILDREN) {
+            let path = if parent.is_empty() {
+                key.clone()
+            } else {
+                format!("{parent}.{key}")
+            };
+            self.push_row(key, value, path, depth, rows);
+        }
+        if len > MAX_TREE_CHILDREN {
+            rows.push(TreeRow {
+                path: format!("{parent}.…"),
+                depth,
+                key: String::new(),
+                type_name: "",
+                node: TreeNode::More(len - MAX_TREE_CHILDREN),
+            });
+        }
+    }
+
+    fn push_row(
+        &self,
+        key: String,
+        value: &Bson,
+        path: String,
+        depth: usize,
+        rows: &mut Vec<TreeRow>,
+    ) {
+        let type_name = bson_type_name(value);
+        match value {
+            Bson::Document(document) => {
+                let expanded = self.expanded.contains(&path);
+                rows.push(TreeRow {
+                    path: path.clone(),
+                    depth,
+                    key,
+                    type_name,
+                    node: TreeNode::Container {
+                        len: document.len(),
+                        expanded,
+                    },
+                });
+                if expanded {
+                    self.push_children(
+                        document.iter().map(|(key, value)| (key.clone(), value)),
+                        document.len(),
+                        &path,
+                        depth + 1,
+                        rows,
+                    );
+                }
+            }
+            Bson::Array(items) => {
+                let expanded = self.expanded.contains(&path);
+                rows.push(TreeRow {
+                    path: path.clone(),
+                    depth,
+                    key,
+                    type_name,
+                    node: TreeNode::Container {
+                        len: items.len(),
+                        expanded,
+                    },
+                });
+                if expanded {
+                    self.push_children(
+                        items
+                            .iter()
+                            .enumerate()
+                            .map(|(index, value)| (index.to_string(), value)),
+                        items.len(),
+                        &path,
+                        depth + 1,
+                        rows,
+                    );
+                }
+            }
+            _ => {
+                let (text, truncated) = leaf_text(value, self.full_values.contains(&path));
+                rows.push(TreeRow {
+                    path,
+                    depth,
+                    key,
+                    type_name,
+                    node: TreeNode::Value { text, truncated },
+                });
+            }
+        }
+    }
+
+    /// Expands a container or shows a truncated value in full.
+    pub(crate) fn expand(&mut self, row: &TreeRow) {
+        match row.node {
+            TreeNode::Container { .. } => {
+                self.expanded.insert(row.path.clone());
+            }
+            TreeNode::Value {
+                truncated: true, ..
+            } => {
+                self.full_values.insert(row.path.clone());
+            }
+            _ => {}
+        }
+    }
+
+    /// Collapses the row if it is open; otherwise moves the cursor to its parent. Returns false
+    /// for a closed top-level row, where there is nothing left to collapse.
+    pub(crate) fn collapse(&mut self, rows: &[TreeRow]) -> bool {
+        let Some(row) = rows.get(self.cursor) else {
+            return false;
+        };
+        if self.expanded.remove(&row.path) || self.full_values.remove(&row.path) {
+            return true;
+        }
+        if row.depth == 0 {
+            return false;
+        }
+        if let Some(parent) = rows[..self.cursor]
+            .iter()
+            .rposition(|candidate| candidate.depth < row.depth)
+        {
+            self.cursor = parent;
+        }
+        true
+    }
+
+    pub(crate) fn move_cursor(&mut self, delta: isize, len: usize) {
+        if len == 0 {
+            self.cursor = 0;
+            return;
+        }
+        self.cursor = self.cursor.saturating_add_signed(delta).min(len - 1);
+    }
+}
+
+impl App {
+    pub(crate) fn document_tree_rows(&self) -> Vec<TreeRow> {
+        self.selected_document()
+            .map(|document| self.document_tree.rows(document))
+            .ok()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn move_tree_cursor(&mut self, delta: isize) {
+        let len = self.document_tree_rows().len();
+        self.document_tree.move_cursor(delta, len);
+    }
+
+    pub(crate) fn tree_cursor_to_end(&mut self) {
+        let len = self.document_tree_rows().len();
+        self.document_tree.cursor = len.saturating_sub(1);
+    }
+
+    pub(crate) fn expand_tree_node(&mut self) {
+        let rows = self.document_tree_rows();
+        if let Some(row) = rows.get(self.document_tree.cursor) {
+            self.document_tree.expand(row);
+        }
+    }
+
+    /// Collapses the node under the cursor. Once nothing is open, returns from a followed
+    /// reference or leaves the document view.
+    pub(crate) fn collapse_tree_node(&mut self) {
+        let rows = self.document_tree_rows();
+        if !self.document_tree.collapse(&rows) && !self.reference_back() {
+            self.screen = Screen::Documents;
+        }
+    }
+
+    pub(crate) fn toggle_document_view_mode(&mut self) {
+        if self.screen != Screen::DocumentView {
+            return;
+        }
+        self.document_json_view = !self.document_json_view;
+    }
+
+    /// Dotted path and BSON type of the tree row under the cursor.
+    pub(crate) fn tree_cursor_label(&self) -> Option<String> {
+        let rows = self.document_tree_rows();
+        let row = rows.get(self.document_tree.cursor)?;
+        if matches!(row.node, TreeNode::More(_)) {
+            return None;
+        }
+        Some(format!("{} ({})", row.path, row.type_name))
+    }
+
+    pub(crate) fn tree_lines(&self, rows: &[TreeRow]) -> Vec<Line<'static>> {
+        let dim = self.theme.text_style().add_modifier(Modifier::DIM);
+        let key_style = self.theme.json_style(JsonToken::Key);
+        let document = self.selected_document().ok();
+        let now_ms = self.date_hint_now();
+        rows.iter()
+            .map(|row| {
+                let indent = "  ".repeat(row.depth);
+                match &row.node {
+                    TreeNode::Container { len, expanded } => {
+                        let marker = if *expanded { "▾" } else { "▸" };
+                        let count = match row.type_name {
+                            "array" => format!("{len} item{}", plural(*len)),
+                            _ => format!("{len} field{}", plural(*len)),
+                        };
+                        Line::from(vec![
+                            Span::raw(format!("{indent}{marker} ")),
+                            Span::styled(row.key.clone(), key_style),
+                            Span::styled(format!("  {} · {count}", row.type_name), dim),
+                        ])
+                    }
+                    TreeNode::Value { text, truncated } => {
+                        let value_style = tree_value_token(row.type_name)
+                            .map_or_else(Style::default, |token| self.theme.json_style(token));
+                        let mut spans = vec![
+                            Span::raw(format!("{indent}  ")),
+                            Span::styled(row.key.clone(), key_style),
+                            Span::raw(": "),
+                            Span::styled(text.clone(), value_style),
+                            Span::styled(format!("  {}", row.type_name), dim),
+                        ];
+                        if *truncated {
+                            spans.push(Span::styled(" · truncated", dim));
+                        }
+                        if let Some(now_ms) = now_ms
+                            && let Some(hint) = document
+                                .and_then(|document| value_at(document, &row.path))
+                                .and_then(|value| bson_time_hint(value, now_ms))
+                        {
+                            spans.push(hint_span(self.theme, hint));
+                        }
+                        Line::from(spans)
+                    }
+                    TreeNode::More(count) => {
+                        Line::from(Span::styled(format!("{indent}  … {count} more"), dim))
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+/// Short display text for a scalar, truncating long strings and binary unless `full`.
+pub(crate) fn leaf_text(value: &Bson, full: bool) -> (String, bool) {
+    let text = match value {
+        Bson::ObjectId(id) => return (format!("ObjectId(\"{id}\")"), false),
+        Bson::DateTime(date) => {
+            let text = date
+                .try_to_rfc3339_string()
+                .map(|date| format!("ISODate(\"{date}\")"))
+                .unwrap_or_else(|_| format_bson(value));
+            return (text, false);
+        }
+        Bson::String(text) => serde_json::to_string(text).unwrap_or_else(|_| format!("\"{text}\"")),
+        Bson::Binary(binary) if !full => {
+            return (
+                format!(
+                    "<binary subtype {:#04x}, {} bytes>",
+                    u8::from(binary.subtype),
+                    binary.bytes.len()
+                ),
+                true,
+            );
+        }
+        _ => format_bson(value),
+    };
+    if full || text.chars().count() <= MAX_INLINE_VALUE_CHARS {
+        return (text, false);
+    }
+    let mut short = text
+        .chars()
+        .take(MAX_INLINE_VALUE_CHARS - 1)
+        .collect::<String>();
+    short.push('…');
+    (short, true)
+}
+
+/// MongoDB `$type` alias for a value.
+pub(crate) fn bson_type_name(value: &Bson) -> &'static str {
+    match value {
+        Bson::Double(_) => "double",
+        Bson::String(_) => "string",
+        Bson::Array(_) => "array",
+        Bson::Document(_) => "object",
+        Bson::Boolean(_) => "bool",
+        Bson::Null => "null",
+        Bson::RegularExpression(_) => "regex",
+        Bson::JavaScriptCode(_) => "javascript",
+        Bson::JavaScriptCodeWithScope(_) => "javascriptWithScope",
+        Bson::Int32(_) => "int",
+        Bson::Int64(_) => "long",
+        Bson::Timestamp(_) => "timestamp",
+        Bson::Binary(_) => "binData",
+        Bson::ObjectId(_) => "objectId",
+        Bson::DateTime(_) => "date",
+        Bson::Symbol(_) => "symbol",
+        Bson::Decimal128(_) => "decimal",
+        Bson::Undefined => "undefined",
+        Bson::MaxKey => "maxKey",
+        Bson::MinKey => "minKey",
+        Bson::DbPointer(_) => "dbPointer",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_document() -> Document {
+        let mut address = Document::new();
+        address.insert("city", "Lisbon");
+        address.insert("zip", "1000-001");
+        let mut document = Document::new();
+        document.insert("_id", 7_i32);
+        document.insert("address", address);
+        document.insert("tags", vec![Bson::from("a"), Bson::from("b")]);
+        document.insert("bio", "x".repeat(200));
+        document
+    }
+
+    fn paths(rows: &[TreeRow]) -> Vec<&str> {
+        rows.iter().map(|row| row.path.as_str()).collect()
+    }
+
+    #[test]
+    fn rows_start_collapsed_and_expand_by_path() {
+        let document = sample_document();
+        let mut tree = DocumentTree::default();
+        let rows = tree.rows(&document);
+        assert_eq!(paths(&rows), vec!["_id", "address", "tags", "bio"]);
+        assert_eq!(rows[1].type_name, "object");
+        assert_eq!(
+            rows[2].node,
+            TreeNode::Container {
+                len: 2,
+                expanded: false
+            }
+        );
+
+        tree.expand(&rows[1]);
+        tree.expand(&rows[2]);
+        let rows = tree.rows(&document);
+        assert_eq!(
+            paths(&rows),
+            vec![
+                "_id",
+                "address",
+                "address.city",
+                "address.zip",
+                "tags",
+                "tags.0",
+                "tags.1",
+                "bio"
+            ]
+        );
+        assert_eq!(rows[5].depth, 1);
+        assert_eq!(rows[5].key, "0");
+    }
+
+    #[test]
+    fn long_values_truncate_until_expanded() {
+        let document = sample_document();
+        let mut tree = DocumentTree::default();
+        let rows = tree.rows(&document);
+        let TreeNode::Value { text, truncated } = &rows[3].node else {
+            panic!("expected value row");
+        };
+        assert!(*truncated);
+        assert_eq!(text.chars().count(), MAX_INLINE_VALUE_CHARS);
+
+        tree.expand(&rows[3]);
+        let rows = tree.rows(&document);
+        let TreeNode::Value { text, truncated } = &rows[3].node else {
+            panic!("expected value row");
+        };
+        assert!(!*truncated);
+        assert_eq!(text.len(), 202);
+    }
+
+    #[test]
+    fn collapse_closes_node_then_moves_to_parent() {
+        let document = sample_document();
+        let mut tree = DocumentTree::default();
+        let rows = tree.rows(&document);
+        tree.expand(&rows[1]);
+        let rows = tree.rows(&document);
+        tree.cursor = 3;
+
+        assert!(tree.collapse(&rows));
+        assert_eq!(tree.cursor, 1);
+        assert!(tree.collapse(&rows));
+        assert_eq!(paths(&tree.rows(&document)).len(), 4);
+        assert!(!tree.collapse(&tree.rows(&document)));
+    }
+
+    #[test]
+    fn app_tracks_cursor_path_and_leaves_view_from_top_level() {
+        let mut app = App::test_app();
+        app.documents = vec![sample_document()];
+        app.document_index = Some(0);
+        app.prepare_document_view();
+        app.screen = Screen::DocumentView;
+
+        app.move_tree_cursor(1);
+        app.expand_tree_node();
+        app.move_tree_cursor(1);
+        assert_eq!(
+            app.tree_cursor_label().as_deref(),
+            Some("address.city (string)")
+        );
+
+        app.collapse_tree_node();
+        app.collapse_tree_node();
+        assert_eq!(app.screen, Screen::DocumentView);
+        app.collapse_tree_node();
+        assert_eq!(app.screen, Screen::Documents);
+    }
+}